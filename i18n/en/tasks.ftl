@@ -5,6 +5,25 @@ empty-trash = Empty trash
 empty-trash-confirm = All tasks in the trash will be permanently deleted. This action cannot be undone.
 no-favorites = No favorites
 no-favorites-suggestion = Mark tasks as favorites to see them here
+my-day = My Day
+my-day-suggestions = Suggestions
+my-day-clear = Start afresh
+my-day-keep = Carry over
+no-my-day = Nothing planned for today
+no-my-day-suggestion = Add tasks to My Day from their row or details
+upcoming = Upcoming
+no-upcoming = Nothing coming up
+no-upcoming-suggestion = Open tasks with a due date will appear here
+overdue = Overdue
+due-today = Today
+due-tomorrow = Tomorrow
+due-this-week = This week
+due-later = Later
+new-day = A new day
+new-day-confirm = { $count ->
+    [one] 1 task is still in My Day from before. Start afresh or carry it over?
+   *[other] { $count } tasks are still in My Day from before. Start afresh or carry them over?
+}
 no-trash = Trash is empty
 no-trash-suggestion = Deleted tasks will appear here
 restore = Restore
@@ -30,17 +49,71 @@ no-search-results-suggestion = Try a different search term
 search-scope-current-list = Search current list
 search-scope-global = Search all lists
 
+# Tags
+tags = Tags
+add-tag = Add tag
+no-tags = No tags
+no-tags-suggestion = Tag tasks from their details to browse them here
+tag-task-count = { $count ->
+    [one] 1 task
+   *[other] { $count } tasks
+}
+
 # Details
 title = Title
 details = Details
 favorite = Favorite
 priority = Priority
 due-date = Due date
+due-time = Due time
+due-time-zone = Keep in this time zone
+due-time-floating = Follows the system time zone
+due-time-zone-local = { $zone } ({ $local } here)
 reminder = Reminder
+repeat = Repeat
+repeat-never = Does not repeat
+repeat-description = A new task is added for the next occurrence when this one is completed
+repeat-daily = Daily
+repeat-weekly = Weekly
+repeat-monthly = Monthly
+repeat-yearly = Yearly
+repeat-from-completion = Count from completion
+repeat-rule = Recurrence rule, e.g. FREQ=MONTHLY;BYDAY=-1FR
+repeat-invalid = Not a valid recurrence rule
+weekday-monday = Mo
+weekday-tuesday = Tu
+weekday-wednesday = We
+weekday-thursday = Th
+weekday-friday = Fr
+weekday-saturday = Sa
+weekday-sunday = Su
 created-at = Created
+updated-at = Last edited
+just-now = Just now
+minutes-ago = { $minutes } min ago
+hours-ago = { $hours ->
+    [one] 1 hour ago
+   *[other] { $hours } hours ago
+}
+days-ago = { $days ->
+    [one] Yesterday
+   *[other] { $days } days ago
+}
 completed-at = Completed
 notes = Notes
 add-notes = Add notes
+state = State
+unknown-state = Unknown state
+priority-low = Low
+priority-normal = Normal
+priority-high = High
+
+# History
+history = History
+no-history = No earlier versions yet
+history-first = First version
+history-none = None
+no-revision-changes = No differences
 
 # Empty
 no-tasks = No tasks
@@ -51,6 +124,8 @@ no-list-suggestion = Create or select a new list to get started
 sub-tasks = Sub-tasks
 add-sub-task = Add sub-task
 move-to-trash = Move to trash
+indent = Indent
+outdent = Outdent
 
 # New List Dialog
 create-list = Create a new list
@@ -58,6 +133,10 @@ create-list = Create a new list
 # Rename List Dialog
 rename-list = Rename list
 
+# Rename Tag Dialog
+rename-tag = Rename tag
+rename-tag-merge = Renaming to a tag that is already in use merges the two.
+
 # Delete List Dialog
 delete-list = The selected list is about to be deleted
 delete-list-confirm = Are you sure you want to delete "{ $name }"?
@@ -77,6 +156,62 @@ delete-task-permanently-confirm = Are you sure you want to permanently delete "{
 delete-list-permanently = Delete list permanently
 delete-list-permanently-confirm = Are you sure you want to permanently delete "{ $name }" and all of its tasks? This action cannot be undone.
 
+# Damaged items
+damaged-items = Damaged items
+damaged-items-description = These files could not be read and were set aside. Open one to fix it, then retry to put it back.
+no-damaged-items = No damaged items
+no-damaged-items-suggestion = Files that cannot be read will appear here
+damaged-task = Task
+damaged-trashed-task = Trashed task
+damaged-item-error = Line { $line }, column { $column }: { $message }
+damaged-item-found = Found at { $path } on { $date }
+damaged-item-restored = "{ $name }" restored
+damaged-item-still-damaged = "{ $name }" still cannot be read
+recovered-list = Recovered list { $id }
+open-file = Open file
+retry = Retry
+discard = Discard
+discard-damaged-item = Discard damaged item
+discard-damaged-item-confirm = Are you sure you want to discard "{ $name }"? The file will be deleted and cannot be recovered.
+
+# Snapshots
+snapshots = Snapshots
+snapshots-description = Copies of your lists and tasks are kept automatically, so they can be compared and restored if something goes wrong.
+snapshots-unavailable = Snapshots are not available for this storage backend.
+no-snapshots = No snapshots yet
+take-snapshot = Take snapshot now
+compare = Compare
+no-snapshot-changes = Nothing has changed since this snapshot
+snapshot-scheduled = Automatic
+snapshot-manual = Manual
+snapshot-before-restore = Before restore
+snapshot-added = Added since
+snapshot-removed = Deleted since
+snapshot-changed = Changed
+snapshot-taken = Snapshot taken
+snapshot-restored = Snapshot restored
+snapshot-failed = Snapshot failed: { $error }
+restore-snapshot = Restore snapshot
+restore-snapshot-confirm = Restore everything to how it was on { $date }? A snapshot of the current state is taken first.
+restore-snapshot-list-confirm = Restore "{ $name }" to how it was in this snapshot? A snapshot of the current state is taken first.
+
+# Task states
+states = Task states
+states-description = Tasks move through these states, in this order. Tasks in a state that counts as completed are checked off.
+manage-states = Create, rename and reorder states
+new-state = New state
+add-state = Add state
+state-name = State name
+state-counts-as-completed = Counts as completed
+delete-state = Delete state
+delete-state-confirm = { $count ->
+    [0] Delete "{ $name }"?
+    [one] Delete "{ $name }"? The task in it is moved to the state picked below.
+   *[other] Delete "{ $name }"? The { $count } tasks in it are moved to the state picked below.
+}
+move-tasks-to = Move tasks to
+state-by-completion = Pending or Completed
+
 # Icon Dialog
 icon = Set icon
 icon-select = Select an icon
@@ -89,6 +224,8 @@ select-date-time = Select date and time
 hour = Hour
 minute = Minute
 set-reminder = Set reminder
+set-due-time = Add a time
+select-due-time = Select a due time
 clear-reminder = Clear reminder
 
 # Export Dialog
@@ -101,6 +238,7 @@ copy = Copy
 confirm = Confirm
 save = Save
 list-name = List name
+tag-name = Tag name
 
 # Context Pages
 
@@ -112,6 +250,8 @@ settings = Settings
 
 ### Appearance
 appearance = Appearance
+show-my-day = Show My Day
+show-upcoming = Show upcoming
 show-favorites = Show favorites
 show-trash = Show trash
 sort-lists-by = Sort lists by
@@ -120,6 +260,14 @@ match-desktop = Match desktop
 dark = Dark
 light = Light
 
+### Snapshots
+keep-hourly-snapshots = Hourly snapshots to keep
+keep-daily-snapshots = Daily snapshots to keep
+keep-weekly-snapshots = Weekly snapshots to keep
+snapshots-off = Off
+browse-snapshots = Browse snapshots
+browse = Browse
+
 # Menu
 
 ## File
@@ -130,6 +278,11 @@ quit = Quit
 
 ## Edit
 edit = Edit
+redo = Redo
+nothing-to-undo = Nothing to undo
+nothing-to-redo = Nothing to redo
+undo-conflict = This change was edited again since and can no longer be undone
+undo-failed = Could not undo: { $error }
 rename = Rename
 delete = Delete
 
@@ -155,4 +308,5 @@ sort-name-asc = Name A-Z
 sort-name-desc = Name Z-A
 sort-date-asc = Date added (Old to New)
 sort-date-desc = Date added (New to Old)
+sort-modified = Recently modified
 sort-manual = Manual
@@ -11,16 +11,26 @@ use cosmic::{
 use crate::{
     config::AppConfig,
     features::{
+        damaged::{self, DamagedMarker},
         favorites::{self, FavoritesMarker},
         lists::{content, List},
+        my_day::{self, MyDayMarker},
         reminders::reminder,
-        tasks::{self, details},
+        snapshots::snapshots,
+        states::states,
+        tags::{self, TagsMarker},
+        tasks::{self, details, due::DueDate},
         trash::{self, TrashMarker},
+        upcoming::{self, UpcomingMarker},
     },
     fl,
     shared::{
         dialogs::{DialogAction, DialogPage},
-        navigation::{nav::NavMenuAction, ui},
+        navigation::{
+            nav::{NavMenuAction, TasksAction},
+            ui,
+        },
+        store::watcher::StoreEvent,
     },
 };
 
@@ -64,6 +74,16 @@ impl Application for AppModel {
                 Message::ToggleContextDrawer,
             )
             .title(self.context_page.title()),
+            ContextPage::Snapshots => app::context_drawer::context_drawer(
+                self.snapshots.view().map(Message::Snapshots),
+                Message::ToggleContextDrawer,
+            )
+            .title(self.context_page.title()),
+            ContextPage::States => app::context_drawer::context_drawer(
+                self.states.view().map(Message::States),
+                Message::ToggleContextDrawer,
+            )
+            .title(self.context_page.title()),
         })
     }
 
@@ -106,7 +126,12 @@ impl Application for AppModel {
                     ),
                     NavMenuAction::TrashEmptyAll,
                 ));
-            } else if favorites_index_opt.is_some() {
+            } else if favorites_index_opt.is_some()
+                || self.nav.data::<MyDayMarker>(entity).is_some()
+                || self.nav.data::<UpcomingMarker>(entity).is_some()
+                || self.nav.data::<TagsMarker>(entity).is_some()
+                || self.nav.data::<DamagedMarker>(entity).is_some()
+            {
                 return items;
             } else {
                 items.push(cosmic::widget::menu::Item::Button(
@@ -168,12 +193,30 @@ impl Application for AppModel {
     fn on_nav_select(&mut self, entity: Entity) -> app::Task<Self::Message> {
         let mut tasks = vec![];
         self.nav.activate(entity);
+        self.refresh_damaged_nav_item();
+
+        if self.nav.data::<MyDayMarker>(entity).is_some() {
+            let _ = self.update(Message::Content(content::Message::SetList(None)));
+            return self.update(Message::MyDay(my_day::my_day::Message::Load));
+        }
+
+        if self.nav.data::<UpcomingMarker>(entity).is_some() {
+            let _ = self.update(Message::Content(content::Message::SetList(None)));
+            return self.update(Message::Upcoming(upcoming::upcoming::Message::Load));
+        }
 
         if self.nav.data::<FavoritesMarker>(entity).is_some() {
             let _ = self.update(Message::Content(content::Message::SetList(None)));
             return self.update(Message::Favorites(favorites::favorites::Message::Load));
         }
 
+        if self.nav.data::<TagsMarker>(entity).is_some() {
+            return app::Task::batch(vec![
+                self.update(Message::Content(content::Message::SetList(None))),
+                self.update(Message::Tags(tags::tags::Message::Load)),
+            ]);
+        }
+
         if self.nav.data::<TrashMarker>(entity).is_some() {
             return app::Task::batch(vec![
                 self.update(Message::Content(content::Message::SetList(None))),
@@ -181,6 +224,13 @@ impl Application for AppModel {
             ]);
         }
 
+        if self.nav.data::<DamagedMarker>(entity).is_some() {
+            return app::Task::batch(vec![
+                self.update(Message::Content(content::Message::SetList(None))),
+                self.update(Message::Damaged(damaged::damaged::Message::Load)),
+            ]);
+        }
+
         let location_opt = self.nav.data::<List>(entity);
 
         if let Some(list) = location_opt {
@@ -209,9 +259,9 @@ impl Application for AppModel {
 
                     Message::UpdateConfig(update.config)
                 }),
-            cosmic::iced::event::listen_with(|event, _status, _window_id| match event {
+            cosmic::iced::event::listen_with(|event, status, _window_id| match event {
                 Event::Keyboard(KeyEvent::KeyPressed { key, modifiers, .. }) => Some(
-                    Message::Application(ui::ApplicationAction::Key(modifiers, key)),
+                    Message::Application(ui::ApplicationAction::Key(modifiers, key, status)),
                 ),
                 Event::Keyboard(KeyEvent::ModifiersChanged(modifiers)) => Some(
                     Message::Application(ui::ApplicationAction::Modifiers(modifiers)),
@@ -232,16 +282,58 @@ impl Application for AppModel {
                 .map(|_| Message::Reminder(reminder::ReminderMessage::Tick)),
         );
 
-        subscriptions.push(crate::shared::store::watcher::subscription(
-            self.store.base_dir().to_path_buf(),
-        ));
+        subscriptions.push(
+            cosmic::iced::time::every(std::time::Duration::from_secs(10 * 60))
+                .map(|_| Message::Snapshots(snapshots::Message::Tick)),
+        );
+
+        if let Some(watcher) = crate::shared::store::watcher::subscription(self.store.clone()) {
+            subscriptions.push(watcher);
+        }
 
         Subscription::batch(subscriptions)
     }
 
     fn update(&mut self, message: Self::Message) -> app::Task<Self::Message> {
+        let task = self.handle(message);
+        self.can_undo = self.store.can_undo();
+        self.can_redo = self.store.can_redo();
+        task
+    }
+
+    fn view(&self) -> Element<'_, Self::Message> {
+        let content = if self.search.has_query() && self.search.is_global() {
+            self.search.view().map(Message::Search)
+        } else if self.nav.active_data::<TrashMarker>().is_some() {
+            self.trash.view().map(Message::Trash)
+        } else if self.nav.active_data::<DamagedMarker>().is_some() {
+            self.damaged.view().map(Message::Damaged)
+        } else if self.nav.active_data::<MyDayMarker>().is_some() {
+            self.my_day.view().map(Message::MyDay)
+        } else if self.nav.active_data::<UpcomingMarker>().is_some() {
+            self.upcoming.view().map(Message::Upcoming)
+        } else if self.nav.active_data::<FavoritesMarker>().is_some() {
+            self.favorites.view().map(Message::Favorites)
+        } else if self.nav.active_data::<TagsMarker>().is_some() {
+            self.tags.view().map(Message::Tags)
+        } else {
+            self.content.view(self.search.query()).map(Message::Content)
+        };
+
+        widget::toaster(&self.toasts, content)
+    }
+}
+
+impl AppModel {
+    /// Handles a message. [`Application::update`] wraps this to keep the
+    /// state the view reads from the store up to date.
+    fn handle(&mut self, message: Message) -> app::Task<Message> {
         match message {
             Message::UpdateConfig(config) => {
+                if config.snapshot_retention != self.config.snapshot_retention {
+                    self.snapshots
+                        .update(snapshots::Message::SetRetention(config.snapshot_retention));
+                }
                 self.config = config;
                 return cosmic::task::message(Message::Content(content::Message::SetConfig(
                     self.config.clone(),
@@ -259,6 +351,15 @@ impl Application for AppModel {
                 if let Some(output) = self.content.update(message) {
                     match output {
                         content::Output::Focus(id) => return cosmic::widget::text_input::focus(id),
+                        content::Output::ListUpdated(list) => {
+                            if let Some(data) = self
+                                .nav
+                                .active_data_mut::<List>()
+                                .filter(|data| data.id == list.id)
+                            {
+                                *data = list;
+                            }
+                        }
                         content::Output::OpenTaskDetails(key, id) => {
                             let Some(list_id) = self.nav.active_data::<List>().map(|list| list.id)
                             else {
@@ -335,28 +436,54 @@ impl Application for AppModel {
                             return cosmic::task::batch(tasks);
                         }
                         details::Output::OpenCalendarDialog => {
+                            let calendar = match &self.details.task.due_date {
+                                Some(due) => CalendarModel::new(due.date, due.date),
+                                None => CalendarModel::now(),
+                            };
                             return cosmic::task::message(Message::Dialog(DialogAction::Open(
-                                DialogPage::Calendar(CalendarModel::now()),
+                                DialogPage::Calendar(calendar),
+                            )));
+                        }
+                        details::Output::OpenDueTimeDialog => {
+                            let time = self
+                                .details
+                                .task
+                                .due_date
+                                .as_ref()
+                                .and_then(|due| due.time)
+                                .unwrap_or_else(|| jiff::Zoned::now().time());
+                            return cosmic::task::message(Message::Dialog(DialogAction::Open(
+                                DialogPage::DueTime {
+                                    hour: time.hour() as u32,
+                                    minute: time.minute() as u32,
+                                },
                             )));
                         }
                         details::Output::OpenReminderDialog => {
-                            let (cal, hour, minute) =
-                                if let Some(ts) = self.details.task.reminder_date {
-                                    let zoned = ts.to_zoned(jiff::tz::TimeZone::system());
-                                    let date = zoned.date();
-                                    let h = zoned.hour() as u32;
-                                    let m = zoned.minute() as u32;
-                                    (CalendarModel::new(date, date), h, m)
-                                } else {
-                                    let now = jiff::Timestamp::now()
-                                        .to_zoned(jiff::tz::TimeZone::system());
-                                    let date = now.date();
-                                    (
-                                        CalendarModel::new(date, date),
-                                        now.hour() as u32,
-                                        now.minute() as u32,
-                                    )
+                            let (cal, hour, minute) = if let Some(ts) =
+                                self.details.task.reminder_date
+                            {
+                                let zoned = ts.to_zoned(jiff::tz::TimeZone::system());
+                                let date = zoned.date();
+                                let h = zoned.hour() as u32;
+                                let m = zoned.minute() as u32;
+                                (CalendarModel::new(date, date), h, m)
+                            } else {
+                                // Without a reminder, offer the due time, or
+                                // the current time on the due date.
+                                let now =
+                                    jiff::Timestamp::now().to_zoned(jiff::tz::TimeZone::system());
+                                let due = self.details.task.due_date.as_ref();
+                                let (date, time) = match due.and_then(DueDate::local) {
+                                    Some(local) => (local.date(), local.time()),
+                                    None => (due.map_or(now.date(), |due| due.date), now.time()),
                                 };
+                                (
+                                    CalendarModel::new(date, date),
+                                    time.hour() as u32,
+                                    time.minute() as u32,
+                                )
+                            };
                             return cosmic::task::message(Message::Dialog(DialogAction::Open(
                                 DialogPage::ReminderDateTime {
                                     calendar: cal,
@@ -365,11 +492,38 @@ impl Application for AppModel {
                                 },
                             )));
                         }
-                        details::Output::RefreshTask(task) => {
-                            return cosmic::task::message(Message::Content(
-                                content::Message::RefreshTask(task.clone()),
+                        details::Output::ListChanged(list_id) => {
+                            return cosmic::task::message(Message::Tasks(
+                                TasksAction::StoreChanged(StoreEvent::ListChanged(list_id)),
                             ));
                         }
+                        details::Output::RefreshTask(task) => {
+                            return app::Task::batch(vec![
+                                cosmic::task::message(Message::Content(
+                                    content::Message::RefreshTask(task.clone()),
+                                )),
+                                cosmic::task::message(Message::MyDay(
+                                    my_day::my_day::Message::Load,
+                                )),
+                                cosmic::task::message(Message::Upcoming(
+                                    upcoming::upcoming::Message::Load,
+                                )),
+                            ]);
+                        }
+                        details::Output::TagsChanged(task) => {
+                            return app::Task::batch(vec![
+                                cosmic::task::message(Message::Content(
+                                    content::Message::RefreshTask(task.clone()),
+                                )),
+                                cosmic::task::message(Message::MyDay(
+                                    my_day::my_day::Message::Load,
+                                )),
+                                cosmic::task::message(Message::Upcoming(
+                                    upcoming::upcoming::Message::Load,
+                                )),
+                                cosmic::task::message(Message::Tags(tags::tags::Message::Load)),
+                            ]);
+                        }
                     }
                 }
             }
@@ -416,6 +570,97 @@ impl Application for AppModel {
                     None => {}
                 }
             }
+            Message::Damaged(msg) => {
+                let output = self.damaged.update(msg);
+                self.refresh_damaged_nav_item();
+                match output {
+                    Some(damaged::damaged::Output::OpenFile(path)) => {
+                        return cosmic::task::message(Message::Open(
+                            path.to_string_lossy().into_owned(),
+                        ));
+                    }
+                    Some(damaged::damaged::Output::Restored(name)) => {
+                        return self
+                            .toasts
+                            .push(widget::Toast::new(fl!(
+                                "damaged-item-restored",
+                                name = name.as_str()
+                            )))
+                            .map(cosmic::Action::App);
+                    }
+                    Some(damaged::damaged::Output::StillDamaged(name)) => {
+                        return self
+                            .toasts
+                            .push(widget::Toast::new(fl!(
+                                "damaged-item-still-damaged",
+                                name = name.as_str()
+                            )))
+                            .map(cosmic::Action::App);
+                    }
+                    Some(damaged::damaged::Output::DiscardRequested(id, name)) => {
+                        return cosmic::task::message(Message::Dialog(DialogAction::Open(
+                            DialogPage::DiscardDamagedItem(id, name),
+                        )));
+                    }
+                    None => {}
+                }
+            }
+            Message::States(msg) => match self.states.update(msg) {
+                Some(states::Output::Changed) => {
+                    return cosmic::task::message(Message::Tasks(TasksAction::StoreChanged(
+                        StoreEvent::Reload,
+                    )));
+                }
+                Some(states::Output::DeleteRequested {
+                    state,
+                    task_count,
+                    others,
+                }) => {
+                    return cosmic::task::message(Message::Dialog(DialogAction::Open(
+                        DialogPage::DeleteState(state, task_count, others, 0),
+                    )));
+                }
+                None => {}
+            },
+            Message::Snapshots(msg) => match self.snapshots.update(msg) {
+                Some(snapshots::Output::Taken) => {
+                    return self
+                        .toasts
+                        .push(widget::Toast::new(fl!("snapshot-taken")))
+                        .map(cosmic::Action::App);
+                }
+                Some(snapshots::Output::RestoreRequested(id, date)) => {
+                    return cosmic::task::message(Message::Dialog(DialogAction::Open(
+                        DialogPage::RestoreSnapshot(id, date),
+                    )));
+                }
+                Some(snapshots::Output::RestoreListRequested(id, list_id, name)) => {
+                    return cosmic::task::message(Message::Dialog(DialogAction::Open(
+                        DialogPage::RestoreSnapshotList(id, list_id, name),
+                    )));
+                }
+                Some(snapshots::Output::Restored) => {
+                    return app::Task::batch(vec![
+                        cosmic::task::message(Message::Tasks(TasksAction::StoreChanged(
+                            StoreEvent::Reload,
+                        ))),
+                        self.toasts
+                            .push(widget::Toast::new(fl!("snapshot-restored")))
+                            .map(cosmic::Action::App),
+                    ]);
+                }
+                Some(snapshots::Output::Failed(error)) => {
+                    tracing::error!("Snapshot operation failed: {error}");
+                    return self
+                        .toasts
+                        .push(widget::Toast::new(fl!(
+                            "snapshot-failed",
+                            error = error.as_str()
+                        )))
+                        .map(cosmic::Action::App);
+                }
+                None => {}
+            },
             Message::CloseToast(id) => {
                 self.toasts.remove(id);
             }
@@ -439,6 +684,20 @@ impl Application for AppModel {
                     }
                 }
             }
+            Message::MyDay(msg) => {
+                if let Some(my_day::my_day::Output::OpenTask { task, list_id }) =
+                    self.my_day.update(msg)
+                {
+                    return self.open_task(task, list_id);
+                }
+            }
+            Message::Upcoming(msg) => {
+                if let Some(upcoming::upcoming::Output::OpenTask { task, list_id }) =
+                    self.upcoming.update(msg)
+                {
+                    return self.open_task(task, list_id);
+                }
+            }
             Message::Favorites(msg) => {
                 if let Some(output) = self.favorites.update(msg) {
                     match output {
@@ -520,6 +779,26 @@ impl Application for AppModel {
                     }
                 }
             }
+            Message::Tags(msg) => {
+                let output = self.tags.update(msg);
+                self.refresh_tags_nav_item();
+                match output {
+                    Some(tags::tags::Output::OpenTask { task, list_id }) => {
+                        return self.open_task(task, list_id);
+                    }
+                    Some(tags::tags::Output::RenameRequested(tag)) => {
+                        return cosmic::task::message(Message::Dialog(DialogAction::Open(
+                            DialogPage::RenameTag(tag.clone(), tag),
+                        )));
+                    }
+                    Some(tags::tags::Output::Renamed) => {
+                        return cosmic::task::message(Message::Tasks(TasksAction::StoreChanged(
+                            StoreEvent::Reload,
+                        )));
+                    }
+                    None => {}
+                }
+            }
             Message::Tasks(action) => {
                 return self.update_tasks(action);
             }
@@ -539,6 +818,9 @@ impl Application for AppModel {
                     self.context_page = page;
                     self.core.window.show_context = true;
                 }
+                if self.core.window.show_context && self.context_page == ContextPage::States {
+                    return cosmic::task::message(Message::States(states::Message::Load));
+                }
             }
             Message::NavMenu(action) => {
                 return self.update_nav_menu(action);
@@ -551,17 +833,38 @@ impl Application for AppModel {
         app::Task::none()
     }
 
-    fn view(&self) -> Element<'_, Self::Message> {
-        let content = if self.search.has_query() && self.search.is_global() {
-            self.search.view().map(Message::Search)
-        } else if self.nav.active_data::<TrashMarker>().is_some() {
-            self.trash.view().map(Message::Trash)
-        } else if self.nav.active_data::<FavoritesMarker>().is_some() {
-            self.favorites.view().map(Message::Favorites)
-        } else {
-            self.content.view(self.search.query()).map(Message::Content)
+    /// Switches to the list of `task` and opens it in the details drawer,
+    /// for views that gather tasks from several lists.
+    fn open_task(&mut self, task: tasks::task::Task, list_id: uuid::Uuid) -> app::Task<Message> {
+        let entity = self
+            .nav
+            .iter()
+            .find(|e| self.nav.data::<List>(*e).is_some_and(|l| l.id == list_id));
+        let Some(entity) = entity else {
+            tracing::error!("Nav entity not found for list {list_id}");
+            return app::Task::none();
         };
+        self.nav.activate(entity);
 
-        widget::toaster(&self.toasts, content)
+        let mut tasks = vec![cosmic::task::message(Message::ToggleContextPage(
+            ContextPage::TaskDetails,
+        ))];
+
+        if let Some(list) = self.nav.data::<List>(entity) {
+            tasks.push(self.update(Message::Content(content::Message::SetList(Some(
+                list.clone(),
+            )))));
+        }
+
+        let Some(key) = self.content.find_task_key(task.id) else {
+            tracing::error!("Task key not found after loading list");
+            return app::Task::none();
+        };
+
+        tasks.push(cosmic::task::message(Message::Details(
+            details::Message::SetTask(key, task, list_id),
+        )));
+
+        app::Task::batch(tasks)
     }
 }
@@ -2,9 +2,12 @@ use cosmic::{
     cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry},
     theme,
 };
+use jiff::civil::Date;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::shared::store::snapshot::SnapshotRetention;
+
 pub const CONFIG_VERSION: u64 = 1;
 
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize, CosmicConfigEntry)]
@@ -12,11 +15,17 @@ pub const CONFIG_VERSION: u64 = 1;
 pub struct AppConfig {
     pub app_theme: AppTheme,
     pub hide_completed: bool,
+    pub show_my_day: bool,
+    pub show_upcoming: bool,
     pub show_favorites: bool,
     pub show_trash: bool,
     pub sort_by: SortBy,
     pub last_list_id: Option<Uuid>,
     pub list_sort_by: ListSortBy,
+    pub snapshot_retention: SnapshotRetention,
+    /// The last day My Day was planned, to offer a fresh start on the
+    /// first launch of the next one.
+    pub my_day_date: Option<Date>,
 }
 
 impl Default for AppConfig {
@@ -24,11 +33,15 @@ impl Default for AppConfig {
         Self {
             app_theme: AppTheme::default(),
             hide_completed: false,
+            show_my_day: true,
+            show_upcoming: true,
             show_favorites: true,
             show_trash: true,
             sort_by: SortBy::default(),
             last_list_id: None,
             list_sort_by: ListSortBy::default(),
+            snapshot_retention: SnapshotRetention::default(),
+            my_day_date: None,
         }
     }
 }
@@ -39,6 +52,7 @@ pub enum ListSortBy {
     NameAsc,
     NameDesc,
     Manual,
+    Modified,
 }
 
 impl From<usize> for ListSortBy {
@@ -46,6 +60,7 @@ impl From<usize> for ListSortBy {
         match value {
             1 => ListSortBy::NameDesc,
             2 => ListSortBy::Manual,
+            3 => ListSortBy::Modified,
             _ => ListSortBy::NameAsc,
         }
     }
@@ -57,6 +72,7 @@ impl From<ListSortBy> for usize {
             ListSortBy::NameAsc => 0,
             ListSortBy::NameDesc => 1,
             ListSortBy::Manual => 2,
+            ListSortBy::Modified => 3,
         }
     }
 }
@@ -69,6 +85,7 @@ pub enum SortBy {
     DateAsc,
     DateDesc,
     Manual,
+    Modified,
 }
 
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
@@ -17,11 +17,18 @@ pub enum Error {
     #[error("Deserialization error: {0}")]
     Deserialize(#[from] ron::error::SpannedError),
 
+    #[cfg(feature = "sqlite")]
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
     #[error("{0}")]
     Store(#[from] StoreError),
 
     #[error("Migration failed: {0}")]
     MigrationFailed(String),
+
+    #[error("Invalid recurrence rule: {0}")]
+    InvalidRecurrence(String),
 }
 
 #[derive(Debug, Error)]
@@ -34,6 +41,33 @@ pub enum StoreError {
 
     #[error("State not found: {0}")]
     StateNotFound(uuid::Uuid),
+
+    #[error("Built-in state {0} cannot be deleted or change whether it counts as completed")]
+    BuiltInState(uuid::Uuid),
+
+    #[error("Task {0} cannot become a sub-task of itself or of one of its sub-tasks")]
+    SubTaskCycle(uuid::Uuid),
+
+    #[error("Damaged item not found: {0}")]
+    QuarantinedItemNotFound(uuid::Uuid),
+
+    #[error("Snapshot not found: {0}")]
+    SnapshotNotFound(String),
+
+    #[error("Revision not found for task {0}")]
+    RevisionNotFound(uuid::Uuid),
+
+    #[error("The change was overwritten since and cannot be undone")]
+    UndoConflict,
+
+    #[error("Timed out waiting for the store lock at {0:?}")]
+    LockTimeout(std::path::PathBuf),
+
+    #[error("Store schema v{found} is newer than this version supports (v{supported})")]
+    UnsupportedSchema { found: u32, supported: u32 },
+
+    #[error("Invalid schema version in {0:?}: {1:?}")]
+    InvalidSchemaVersion(std::path::PathBuf, String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
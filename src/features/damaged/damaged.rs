@@ -0,0 +1,210 @@
+use std::path::PathBuf;
+
+use cosmic::{
+    iced::{
+        alignment::{Horizontal, Vertical},
+        Alignment, Length,
+    },
+    theme, widget, Apply, Element,
+};
+use uuid::Uuid;
+
+use crate::{
+    features::tasks::task::Task,
+    fl,
+    shared::{
+        store::{backend::RecordKind, quarantine::QuarantinedItem, Store},
+        widgets::collapsible_section,
+    },
+};
+
+/// Files the store could not parse and set aside, so they can be looked at,
+/// fixed and put back instead of silently disappearing.
+pub struct Damaged {
+    pub items: Vec<QuarantinedItem>,
+    store: Store,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Load,
+    Loaded(Vec<QuarantinedItem>),
+    Open(Uuid),
+    Retry(Uuid),
+    RequestDiscard(Uuid),
+    Discard(Uuid),
+}
+
+pub enum Output {
+    OpenFile(PathBuf),
+    Restored(String),
+    StillDamaged(String),
+    DiscardRequested(Uuid, String),
+}
+
+impl Damaged {
+    pub fn new(store: Store) -> Self {
+        Self {
+            items: Vec::new(),
+            store,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<Output> {
+        match message {
+            Message::Load => {
+                let items = self.store.quarantine().load_all().unwrap_or_else(|e| {
+                    tracing::error!("Failed to load damaged items: {e}");
+                    vec![]
+                });
+                return self.update(Message::Loaded(items));
+            }
+            Message::Loaded(items) => {
+                self.items = items;
+            }
+            Message::Open(id) => {
+                if let Some(item) = self.item(id) {
+                    return Some(Output::OpenFile(item.path.clone()));
+                }
+            }
+            Message::Retry(id) => {
+                let name = self.item(id)?.file_name();
+                let output = match self.store.quarantine().retry(id) {
+                    Ok(()) => Output::Restored(name),
+                    Err(e) => {
+                        tracing::warn!("{name} is still damaged: {e}");
+                        Output::StillDamaged(name)
+                    }
+                };
+                self.update(Message::Load);
+                return Some(output);
+            }
+            Message::RequestDiscard(id) => {
+                let item = self.item(id)?;
+                return Some(Output::DiscardRequested(id, item.file_name()));
+            }
+            Message::Discard(id) => {
+                if let Err(e) = self.store.quarantine().discard(id) {
+                    tracing::error!("Failed to discard damaged item: {e}");
+                }
+                self.items.retain(|item| item.id != id);
+            }
+        }
+
+        None
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        if self.is_empty() {
+            return self.empty_view();
+        }
+
+        let header = widget::row::with_capacity(2)
+            .align_y(Alignment::Center)
+            .spacing(spacing.space_s)
+            .padding([spacing.space_none, spacing.space_xxs])
+            .push(widget::icon::from_name("dialog-warning-symbolic").size(spacing.space_m))
+            .push(widget::text::title4(fl!("damaged-items")).width(Length::Fill));
+
+        let rows: Vec<_> = self.items.iter().map(|item| self.item_row(item)).collect();
+        let list = widget::column::with_children(rows).spacing(spacing.space_xxs);
+
+        widget::column::with_capacity(3)
+            .push(header)
+            .push(widget::text::caption(fl!("damaged-items-description")))
+            .push(widget::scrollable(list).height(Length::Fill))
+            .spacing(spacing.space_s)
+            .padding([spacing.space_xxs, spacing.space_xxxs])
+            .apply(widget::container)
+            .height(Length::Fill)
+            .width(Length::Fill)
+            .center_x(Length::Fill)
+            .max_width(800.)
+            .apply(widget::container)
+            .height(Length::Fill)
+            .width(Length::Fill)
+            .center(Length::Fill)
+            .into()
+    }
+
+    fn item_row<'a>(&'a self, item: &'a QuarantinedItem) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let kind = match item.kind {
+            RecordKind::TrashedTask => fl!("damaged-trashed-task"),
+            _ => fl!("damaged-task"),
+        };
+        let error = fl!(
+            "damaged-item-error",
+            line = item.error.start.0,
+            column = item.error.start.1,
+            message = item.error.message.as_str()
+        );
+        let found = fl!(
+            "damaged-item-found",
+            path = item.origin.display().to_string(),
+            date = Task::format_timestamp(&item.quarantined_at)
+        );
+
+        let text_col = widget::column::with_capacity(3)
+            .push(widget::text::body(format!("{kind} · {}", item.file_name())))
+            .push(widget::text::caption(error))
+            .push(widget::text::caption(found))
+            .width(Length::Fill);
+
+        let open_button = widget::button::icon(widget::icon::from_name("document-open-symbolic"))
+            .tooltip(fl!("open-file"))
+            .class(theme::Button::Standard)
+            .on_press(Message::Open(item.id));
+
+        let retry_button = widget::button::icon(widget::icon::from_name("view-refresh-symbolic"))
+            .tooltip(fl!("retry"))
+            .class(theme::Button::Standard)
+            .on_press(Message::Retry(item.id));
+
+        let discard_button = widget::button::icon(widget::icon::from_name("edit-delete-symbolic"))
+            .tooltip(fl!("discard"))
+            .class(theme::Button::Destructive)
+            .on_press(Message::RequestDiscard(item.id));
+
+        let row = widget::row::with_capacity(4)
+            .align_y(Alignment::Center)
+            .spacing(spacing.space_s)
+            .padding([spacing.space_xxs, spacing.space_xs])
+            .push(text_col)
+            .push(open_button)
+            .push(retry_button)
+            .push(discard_button);
+
+        collapsible_section::row_item(row.into())
+    }
+
+    fn empty_view(&self) -> Element<'_, Message> {
+        widget::container(
+            widget::column::with_children(vec![
+                widget::icon::from_name("emblem-ok-symbolic")
+                    .size(56)
+                    .into(),
+                widget::text::title1(fl!("no-damaged-items")).into(),
+                widget::text(fl!("no-damaged-items-suggestion")).into(),
+            ])
+            .spacing(10)
+            .align_x(Alignment::Center),
+        )
+        .align_y(Vertical::Center)
+        .align_x(Horizontal::Center)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into()
+    }
+
+    fn item(&self, id: Uuid) -> Option<&QuarantinedItem> {
+        self.items.iter().find(|item| item.id == id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
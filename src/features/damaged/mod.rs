@@ -0,0 +1,4 @@
+pub mod damaged;
+pub mod nav;
+
+pub use nav::DamagedMarker;
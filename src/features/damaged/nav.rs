@@ -0,0 +1,31 @@
+use cosmic::widget;
+
+use crate::{app::AppModel, fl};
+
+pub struct DamagedMarker;
+
+impl AppModel {
+    /// Shows the "Damaged items" entry while there is something in it. It
+    /// stays while it is open, so fixing the last item does not pull the
+    /// page out from under the user.
+    pub fn refresh_damaged_nav_item(&mut self) {
+        let shown = self
+            .nav
+            .data::<DamagedMarker>(self.damaged_entity)
+            .is_some();
+        if !shown && !self.damaged.is_empty() {
+            let icon = widget::icon::from_name("dialog-warning-symbolic").size(16);
+            self.damaged_entity = self
+                .nav
+                .insert()
+                .text(fl!("damaged-items"))
+                .icon(icon)
+                .data(DamagedMarker)
+                .id();
+            self.reposition_special_items();
+        } else if shown && self.damaged.is_empty() && self.nav.active() != self.damaged_entity {
+            self.nav.remove(self.damaged_entity);
+            self.reposition_special_items();
+        }
+    }
+}
@@ -13,7 +13,10 @@ use uuid::Uuid;
 use crate::{
     features::{lists::list::List, tasks::task::Task},
     fl,
-    shared::{store::Store, widgets::collapsible_section},
+    shared::{
+        store::{query::TaskQuery, Store},
+        widgets::collapsible_section,
+    },
 };
 
 pub struct FavoriteEntry {
@@ -32,6 +35,8 @@ pub struct Favorites {
 pub enum Message {
     Load,
     Loaded(Vec<FavoriteEntry>),
+    TaskChanged(Uuid, Task),
+    TaskRemoved(Uuid),
     Unfavorite(Uuid),
     Open(Uuid),
     ToggleSection(Uuid),
@@ -77,33 +82,58 @@ impl Favorites {
                     vec![]
                 });
 
-                let mut entries = Vec::new();
-                for list in &lists {
-                    let tasks = self.store.tasks(list.id).load_all().unwrap_or_else(|e| {
-                        tracing::error!("Failed to load tasks for list {}: {e}", list.id);
+                let favorites = self
+                    .store
+                    .query(&TaskQuery::new().favorite(true))
+                    .unwrap_or_else(|e| {
+                        tracing::error!("Failed to load favorite tasks: {e}");
                         vec![]
                     });
-                    for task in tasks {
-                        if task.favorite {
-                            entries.push(FavoriteEntry {
-                                task,
-                                list_id: list.id,
-                                list_name: list.name.clone(),
-                            });
-                        }
-                    }
-                }
 
-                entries.sort_by(|a, b| {
-                    a.list_name
-                        .cmp(&b.list_name)
-                        .then_with(|| a.task.title.cmp(&b.task.title))
-                });
+                let entries: Vec<FavoriteEntry> = favorites
+                    .into_iter()
+                    .filter_map(|(list_id, task)| {
+                        let list = lists.iter().find(|l| l.id == list_id)?;
+                        Some(FavoriteEntry {
+                            task,
+                            list_id,
+                            list_name: list.name.clone(),
+                        })
+                    })
+                    .collect();
 
                 self.update(Message::Loaded(entries));
             }
             Message::Loaded(entries) => {
                 self.entries = entries;
+                self.sort_entries();
+            }
+            Message::TaskChanged(list_id, task) => {
+                let pos = self.entries.iter().position(|e| e.task.id == task.id);
+                match (pos, task.favorite) {
+                    (Some(pos), true) => {
+                        self.entries[pos].task = task;
+                        self.sort_entries();
+                    }
+                    (Some(pos), false) => {
+                        self.entries.remove(pos);
+                    }
+                    (None, true) => match self.store.lists().get(list_id) {
+                        Ok(list) => {
+                            self.entries.push(FavoriteEntry {
+                                task,
+                                list_id,
+                                list_name: list.name,
+                            });
+                            self.sort_entries();
+                        }
+                        Err(e) => tracing::error!("Failed to find list for favorite: {e}"),
+                    },
+                    (None, false) => {}
+                }
+            }
+            Message::TaskRemoved(task_id) => {
+                self.entries.retain(|e| e.task.id != task_id);
             }
             Message::Unfavorite(task_id) => {
                 if let Some(pos) = self.entries.iter().position(|e| e.task.id == task_id) {
@@ -136,6 +166,14 @@ impl Favorites {
         None
     }
 
+    fn sort_entries(&mut self) {
+        self.entries.sort_by(|a, b| {
+            a.list_name
+                .cmp(&b.list_name)
+                .then_with(|| a.task.title.cmp(&b.task.title))
+        });
+    }
+
     pub fn view(&self) -> Element<'_, Message> {
         if self.entries.is_empty() {
             return self.empty_view();
@@ -19,17 +19,18 @@ use uuid::Uuid;
 use crate::{
     config,
     features::{
-        lists::list::List,
-        tasks::{
-            state::TaskState,
-            task::{Task, TrashedTask},
-        },
+        lists::list::{List, ListLayout},
+        tasks::{state::TaskState, task::Task},
     },
     fl,
-    shared::{store::Store, widgets::collapsible_section},
+    shared::{
+        store::Store,
+        widgets::{self, collapsible_section},
+    },
 };
 
 const TASK_DRAG_MIME: &str = "application/x-cosmic-tasks-item";
+const BOARD_COLUMN_WIDTH: f32 = 280.0;
 
 #[derive(Debug, Clone)]
 struct TaskDrag {
@@ -91,6 +92,8 @@ pub struct Content {
 
     add_task_input: String,
     drag_hover: Option<DefaultKey>,
+    /// The board column whose empty end a card is dragged over.
+    drag_hover_column: Option<Uuid>,
 }
 
 pub use crate::config::SortBy;
@@ -101,8 +104,14 @@ pub enum Message {
 
     TaskExpand(DefaultKey),
     TaskAddSubTask(DefaultKey),
+    TaskIndent(DefaultKey),
+    TaskOutdent(DefaultKey),
+    IndentFocusedTask,
+    OutdentFocusedTask,
     TaskComplete(DefaultKey, bool),
+    TaskSetState(DefaultKey, Uuid),
     TaskToggleFavorite(DefaultKey),
+    TaskToggleToday(DefaultKey),
     TaskToggleTitleEditMode(DefaultKey, bool),
     TaskTitleInput(String),
     TaskOpenDetails(DefaultKey),
@@ -112,6 +121,9 @@ pub enum Message {
     SetList(Option<List>),
     SetTasks(Vec<Task>),
     SyncTasks(Vec<Task>),
+    TaskChanged(Uuid, Task),
+    TaskRemoved(Uuid),
+    SetStates(Vec<TaskState>),
     SetConfig(config::AppConfig),
     RefreshTask(Task),
     Empty,
@@ -126,11 +138,20 @@ pub enum Message {
         from: Option<Uuid>,
         onto: DefaultKey,
     },
+    ColumnDragEntered(Uuid),
+    /// A card dropped on a board column, before another card or at the end.
+    CardDropped {
+        from: Option<Uuid>,
+        state_id: Uuid,
+        before: Option<DefaultKey>,
+    },
     ToggleSection(Uuid),
+    SetLayout(ListLayout),
 }
 
 pub enum Output {
     Focus(widget::Id),
+    ListUpdated(List),
     OpenTaskDetails(DefaultKey, Uuid),
     TaskDeleted {
         task_id: Uuid,
@@ -142,8 +163,11 @@ pub enum Output {
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum TaskAction {
     AddSubTask(DefaultKey),
+    Indent(DefaultKey),
+    Outdent(DefaultKey),
     Edit(DefaultKey),
     Delete(DefaultKey),
+    SetState(DefaultKey, Uuid),
 }
 
 impl MenuAction for TaskAction {
@@ -153,7 +177,10 @@ impl MenuAction for TaskAction {
         match self {
             TaskAction::Edit(id) => Message::TaskOpenDetails(*id),
             TaskAction::AddSubTask(id) => Message::TaskAddSubTask(*id),
+            TaskAction::Indent(id) => Message::TaskIndent(*id),
+            TaskAction::Outdent(id) => Message::TaskOutdent(*id),
             TaskAction::Delete(id) => Message::OpenTaskDeletionDialog(*id),
+            TaskAction::SetState(id, state_id) => Message::TaskSetState(*id, *state_id),
         }
     }
 }
@@ -170,8 +197,12 @@ impl Content {
 
         column = column.push(self.new_task_view());
 
+        // The board uses all the width it gets for its columns.
+        if list.layout == ListLayout::List {
+            column = column.max_width(800.);
+        }
+
         column
-            .max_width(800.)
             .padding([spacing.space_xxs, spacing.space_xxxs])
             .spacing(spacing.space_xxs)
             .apply(widget::container)
@@ -195,6 +226,19 @@ impl Content {
             Message::SyncTasks(tasks) => {
                 self.reconcile_tasks(tasks);
             }
+            Message::TaskChanged(list_id, task) => {
+                if self.selected_list.as_ref().is_some_and(|l| l.id == list_id) {
+                    self.reconcile_task(task);
+                }
+            }
+            Message::TaskRemoved(task_id) => {
+                if let Some(key) = self.find_task_key(task_id) {
+                    self.remove_task_row(key);
+                }
+            }
+            Message::SetStates(states) => {
+                self.states = states;
+            }
             Message::SetList(list) => {
                 match (&self.selected_list, &list) {
                     (Some(current), Some(list)) => {
@@ -328,7 +372,9 @@ impl Content {
                     self.editing.insert(id, state);
                 }
             }
-            Message::TaskTitleInput(input) => self.add_task_input = input,
+            Message::TaskTitleInput(input) => {
+                self.add_task_input = input;
+            }
             Message::TaskTitleSubmit(id) => {
                 let Some(list) = &self.selected_list else {
                     tracing::warn!("No list selected");
@@ -365,13 +411,9 @@ impl Content {
                     self.editing.remove(id);
                     self.inputs.remove(id);
 
-                    let trashed = TrashedTask::new(task.clone(), list_id);
-                    if let Err(err) = self.store.trash().save(&trashed) {
+                    if let Err(err) = self.store.trash().trash_task(list_id, &task) {
                         tracing::error!("Error moving task to trash: {err}");
                     }
-                    if let Err(err) = self.store.tasks(list_id).delete(task.id) {
-                        tracing::error!("Error removing task from list after trashing: {err}");
-                    }
                     output = Some(Output::TaskDeleted {
                         task_id: task.id,
                         list_id,
@@ -380,27 +422,15 @@ impl Content {
                 }
             }
             Message::RestoreTask(task_id, list_id, _) => {
-                let trashed = self.store.trash().load_all().unwrap_or_else(|err| {
-                    tracing::error!("Failed to load trash for restore: {err}");
-                    Vec::new()
-                });
-                if let Some(trashed) = trashed.into_iter().find(|t| t.task.id == task_id) {
-                    if let Err(err) = self
-                        .store
-                        .tasks(trashed.original_list_id)
-                        .save(&trashed.task)
-                    {
-                        tracing::error!("Error restoring task from trash: {err}");
-                    } else if let Err(err) = self.store.trash().delete(task_id) {
-                        tracing::error!("Error removing restored task from trash: {err}");
-                    } else if self
-                        .selected_list
-                        .as_ref()
-                        .is_some_and(|list| list.id == list_id)
-                    {
-                        if let Ok(tasks) = self.store.tasks(list_id).load_all() {
-                            self.update(Message::SetTasks(tasks));
-                        }
+                if let Err(err) = self.store.trash().restore_task(task_id) {
+                    tracing::error!("Error restoring task from trash: {err}");
+                } else if self
+                    .selected_list
+                    .as_ref()
+                    .is_some_and(|list| list.id == list_id)
+                {
+                    if let Ok(tasks) = self.store.tasks(list_id).load_all() {
+                        self.update(Message::SetTasks(tasks));
                     }
                 }
             }
@@ -410,23 +440,38 @@ impl Content {
                     return None;
                 };
 
-                let task = self.tasks.get_mut(id);
-                if let Some(task) = task {
-                    task.completion_date = if complete {
-                        Some(jiff::Timestamp::now())
-                    } else {
-                        None
-                    };
-
-                    if let Err(error) = self
-                        .store
-                        .tasks(list.id)
-                        .update(task.id, |t| *t = task.clone())
-                    {
+                let list_id = list.id;
+                let Some(task_id) = self.tasks.get(id).map(|task| task.id) else {
+                    return None;
+                };
+                match self.store.tasks(list_id).complete(task_id, complete) {
+                    Ok((task, next)) => self.reconcile_completed(list_id, task, next),
+                    Err(error) => {
                         tracing::error!("Failed to update task: {:?}", error);
                     }
                 }
             }
+            Message::TaskSetState(id, state_id) => {
+                let Some(list) = &self.selected_list else {
+                    tracing::warn!("No list selected");
+                    return None;
+                };
+
+                let list_id = list.id;
+                let Some(task_id) = self.tasks.get(id).map(|task| task.id) else {
+                    return None;
+                };
+                let Some(state) = self.states.iter().find(|state| state.id == state_id) else {
+                    tracing::warn!("Task state {state_id} not found");
+                    return None;
+                };
+                match self.store.tasks(list_id).set_state(task_id, state) {
+                    Ok((task, next)) => self.reconcile_completed(list_id, task, next),
+                    Err(error) => {
+                        tracing::error!("Failed to set task state: {:?}", error);
+                    }
+                }
+            }
             Message::TaskToggleFavorite(id) => {
                 let Some(list) = &self.selected_list else {
                     tracing::warn!("No list selected");
@@ -444,6 +489,23 @@ impl Content {
                     }
                 }
             }
+            Message::TaskToggleToday(id) => {
+                let Some(list) = &self.selected_list else {
+                    tracing::warn!("No list selected");
+                    return None;
+                };
+
+                if let Some(task) = self.tasks.get_mut(id) {
+                    task.today = !task.today;
+                    if let Err(error) = self
+                        .store
+                        .tasks(list.id)
+                        .update(task.id, |t| t.today = task.today)
+                    {
+                        tracing::error!("Failed to update task My Day flag: {:?}", error);
+                    }
+                }
+            }
             Message::TaskAddSubTask(id) => {
                 let Some(list) = &self.selected_list else {
                     tracing::warn!("No list selected");
@@ -455,16 +517,16 @@ impl Content {
                     let mut sub_task = Task::new("".to_string());
                     sub_task.parent_id = Some(task.id);
 
-                    match self.store.tasks(list.id).save(&sub_task) {
+                    let added = self.store.transaction(|tx| {
+                        tx.tasks(list.id).save(&sub_task)?;
+                        tx.tasks(list.id).update(task.id, |t| {
+                            t.expanded = true;
+                            t.sub_task_ids.push(sub_task.id);
+                        })
+                    });
+                    match added {
                         Ok(_) => {
                             task.sub_task_ids.push(sub_task.id);
-                            if let Err(error) = self
-                                .store
-                                .tasks(list.id)
-                                .update(task.id, |t| *t = task.clone())
-                            {
-                                tracing::error!("Failed to update task with sub-task: {:?}", error);
-                            }
 
                             let sub_task_id = self.tasks.insert(sub_task);
                             self.inputs.insert(sub_task_id, widget::Id::unique());
@@ -477,17 +539,48 @@ impl Content {
                     }
                 }
             }
+            Message::TaskIndent(id) => {
+                let task = self.tasks.get(id)?;
+                let siblings = self.siblings(task);
+                let index = siblings.iter().position(|(key, _)| *key == id)?;
+                let (_, previous) = siblings.get(index.checked_sub(1)?)?;
+                let parent_id = previous.id;
+                output = self.reparent_task(id, Some(parent_id), None);
+            }
+            Message::TaskOutdent(id) => {
+                let parent_id = self.tasks.get(id)?.parent_id?;
+                let grandparent_id = self
+                    .find_task_key(parent_id)
+                    .and_then(|key| self.tasks.get(key))
+                    .and_then(|parent| parent.parent_id);
+                output = self.reparent_task(id, grandparent_id, Some(parent_id));
+            }
+            Message::IndentFocusedTask => {
+                let id = self.editing_task()?;
+                return self.update(Message::TaskIndent(id));
+            }
+            Message::OutdentFocusedTask => {
+                let id = self.editing_task()?;
+                return self.update(Message::TaskOutdent(id));
+            }
             Message::SetSort(sort_by) => {
                 self.config.sort_by = sort_by;
             }
             Message::DragStarted(_id) => {
                 self.drag_hover = None;
+                self.drag_hover_column = None;
             }
             Message::DragEntered(id) => {
                 self.drag_hover = Some(id);
+                self.drag_hover_column = None;
+            }
+            Message::ColumnDragEntered(state_id) => {
+                self.drag_hover = None;
+                self.drag_hover_column = Some(state_id);
             }
             Message::DragLeft => {
                 self.drag_hover = None;
+                self.drag_hover_column = None;
             }
             Message::TaskDropped { from, onto } => {
                 self.drag_hover = None;
@@ -495,11 +588,36 @@ impl Content {
                     self.reorder_tasks(from_uuid, onto);
                 }
             }
+            Message::CardDropped {
+                from,
+                state_id,
+                before,
+            } => {
+                self.drag_hover = None;
+                self.drag_hover_column = None;
+                if let Some(from_uuid) = from {
+                    self.move_card(from_uuid, state_id, before);
+                }
+            }
             Message::ToggleSection(state_id) => {
                 if !self.collapsed_sections.remove(&state_id) {
                     self.collapsed_sections.insert(state_id);
                 }
             }
+            Message::SetLayout(layout) => {
+                let Some(list) = &mut self.selected_list else {
+                    tracing::warn!("No list selected");
+                    return None;
+                };
+
+                match self.store.lists().update(list.id, |l| l.layout = layout) {
+                    Ok(updated) => {
+                        *list = updated.clone();
+                        output = Some(Output::ListUpdated(updated));
+                    }
+                    Err(error) => tracing::error!("Failed to update list layout: {:?}", error),
+                }
+            }
         }
         output
     }
@@ -523,6 +641,22 @@ impl Content {
             states,
             collapsed_sections: HashSet::new(),
             drag_hover: None,
+            drag_hover_column: None,
+        }
+    }
+
+    /// Shows a task that was just completed or reopened, with the next
+    /// occurrence it may have left behind and that occurrence's parent.
+    fn reconcile_completed(&mut self, list_id: Uuid, task: Task, next: Option<Task>) {
+        self.reconcile_task(task);
+        if let Some(next) = next {
+            if let Some(parent_id) = next.parent_id {
+                match self.store.tasks(list_id).get(parent_id) {
+                    Ok(parent) => self.reconcile_task(parent),
+                    Err(error) => tracing::error!("Failed to reload task: {:?}", error),
+                }
+            }
+            self.reconcile_task(next);
         }
     }
 
@@ -593,6 +727,131 @@ impl Content {
         }
     }
 
+    /// Makes a task a sub-task of `parent_id`, or a top-level task, after
+    /// `after` among its new siblings. A row being edited stays in edit mode.
+    fn reparent_task(
+        &mut self,
+        key: DefaultKey,
+        parent_id: Option<Uuid>,
+        after: Option<Uuid>,
+    ) -> Option<Output> {
+        let list_id = self.selected_list.as_ref()?.id;
+        let task_id = self.tasks.get(key)?.id;
+        let was_editing = self.editing_task() == Some(key);
+
+        match self
+            .store
+            .tasks(list_id)
+            .set_parent(task_id, parent_id, after)
+        {
+            Ok(changed) => {
+                for task in changed {
+                    if let Some(local) = self.tasks.get_mut(key).filter(|t| t.id == task.id) {
+                        // Rows being edited keep their title, but not their old place.
+                        local.parent_id = task.parent_id;
+                        local.sort_order = task.sort_order;
+                    }
+                    self.reconcile_task(task);
+                }
+            }
+            Err(error) => {
+                tracing::error!("Failed to move task: {:?}", error);
+                return None;
+            }
+        }
+
+        was_editing.then(|| {
+            self.editing.insert(key, EditState::Entering);
+            Output::Focus(self.inputs[key].clone())
+        })
+    }
+
+    /// The row whose title is being edited, which Tab and Shift+Tab indent
+    /// and outdent.
+    fn editing_task(&self) -> Option<DefaultKey> {
+        self.editing
+            .iter()
+            .find(|(_, state)| **state == EditState::Editing)
+            .map(|(key, _)| key)
+    }
+
+    /// The tasks shown at the same level as `task`, in the order shown.
+    fn siblings(&self, task: &Task) -> Vec<(DefaultKey, &Task)> {
+        let parent = task
+            .parent_id
+            .and_then(|parent_id| self.find_task_key(parent_id))
+            .and_then(|key| self.tasks.get(key));
+        if let Some(parent) = parent {
+            return self.get_subtasks(parent);
+        }
+
+        let state_id = self.section_state_id(task);
+        let tasks = match self.selected_list.as_ref().map(|list| list.layout) {
+            Some(ListLayout::Board) => self.column_tasks(state_id),
+            _ => self
+                .sort_tasks()
+                .into_iter()
+                .filter(|(_, t)| t.parent_id.is_none() && self.section_state_id(t) == state_id)
+                .collect(),
+        };
+        tasks
+            .into_iter()
+            .filter(|(_, t)| !self.config.hide_completed || !t.is_completed())
+            .collect()
+    }
+
+    /// Moves a card dropped on the board into the column of `state_id`,
+    /// before the card `before` or at the end, as one undo step.
+    fn move_card(&mut self, from_uuid: Uuid, state_id: Uuid, before: Option<DefaultKey>) {
+        let Some(list) = &self.selected_list else {
+            tracing::warn!("move_card: no list selected");
+            return;
+        };
+        let list_id = list.id;
+
+        let Some(from_key) = self.find_task_key(from_uuid) else {
+            tracing::warn!("move_card: source task {from_uuid} not found");
+            return;
+        };
+        if before == Some(from_key) {
+            return;
+        }
+        let Some(state) = self.states.iter().find(|s| s.id == state_id).cloned() else {
+            tracing::warn!("move_card: task state {state_id} not found");
+            return;
+        };
+
+        let mut order: Vec<Uuid> = self
+            .column_tasks(state_id)
+            .into_iter()
+            .filter(|(key, _)| *key != from_key)
+            .map(|(_, task)| task.id)
+            .collect();
+        let at = before
+            .and_then(|before| self.tasks.get(before))
+            .and_then(|before| order.iter().position(|id| *id == before.id))
+            .unwrap_or(order.len());
+        order.insert(at, from_uuid);
+
+        match self
+            .store
+            .tasks(list_id)
+            .move_to_state(from_uuid, &state, &order)
+        {
+            Ok((tasks, mut next)) => {
+                for task in tasks {
+                    let next = if task.id == from_uuid {
+                        next.take()
+                    } else {
+                        None
+                    };
+                    self.reconcile_completed(list_id, task, next);
+                }
+            }
+            Err(e) => tracing::error!("Failed to move card for {from_uuid}: {e}"),
+        }
+    }
+
     pub fn list_view<'a>(&'a self, list: &'a List, search_query: &str) -> Element<'a, Message> {
         let spacing = theme::active().cosmic().spacing;
 
@@ -609,6 +868,14 @@ impl Content {
             return self.empty(list);
         }
 
+        if list.layout == ListLayout::Board {
+            return column
+                .push(self.board_view(search_query))
+                .spacing(spacing.space_s)
+                .height(Length::Fill)
+                .into();
+        }
+
         let sections = self.section_views(visible_tasks);
         let items = widget::column::with_children(sections).spacing(spacing.space_s);
 
@@ -625,16 +892,202 @@ impl Content {
 
         let list_icon = self.create_list_icon(list, &spacing);
         let title = widget::text::title4(&list.name).width(Length::Fill);
+        let layout_buttons: Vec<Element<'a, Message>> = [
+            (ListLayout::List, "view-list-symbolic"),
+            (ListLayout::Board, "view-grid-symbolic"),
+        ]
+        .into_iter()
+        .map(|(layout, icon)| {
+            widget::button::icon(widget::icon::from_name(icon).size(16))
+                .selected(list.layout == layout)
+                .padding(spacing.space_xxs)
+                .on_press(Message::SetLayout(layout))
+                .into()
+        })
+        .collect();
 
-        widget::row::with_capacity(2)
+        widget::row::with_capacity(4)
             .align_y(Alignment::Center)
             .spacing(spacing.space_s)
             .padding([spacing.space_none, spacing.space_xxs])
             .push(list_icon)
             .push(title)
+            .push(widget::row::with_children(layout_buttons).spacing(spacing.space_xxxs))
             .into()
     }
 
+    /// The list as a board: a column per state, in state order, with a card
+    /// for each top-level task.
+    fn board_view<'a>(&'a self, search_query: &str) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let mut states: Vec<&TaskState> = self.states.iter().collect();
+        states.sort_by_key(|state| state.position);
+
+        let columns: Vec<Element<'a, Message>> = states
+            .into_iter()
+            .map(|state| {
+                let cards = self
+                    .column_tasks(state.id)
+                    .into_iter()
+                    .filter(|(_, task)| self.should_show_task(task, search_query))
+                    .collect();
+                self.board_column(state, cards)
+            })
+            .collect();
+
+        widget::scrollable::horizontal(
+            widget::row::with_children(columns)
+                .spacing(spacing.space_s)
+                .padding([
+                    spacing.space_none,
+                    spacing.space_none,
+                    spacing.space_s,
+                    spacing.space_none,
+                ])
+                .height(Length::Fill),
+        )
+        .height(Length::Fill)
+        .into()
+    }
+
+    /// The top-level tasks on a state's board column, in card order.
+    fn column_tasks(&self, state_id: Uuid) -> Vec<(DefaultKey, &Task)> {
+        let mut tasks: Vec<_> = self
+            .tasks
+            .iter()
+            .filter(|(_, task)| task.parent_id.is_none() && self.section_state_id(task) == state_id)
+            .collect();
+        tasks.sort_by(|a, b| {
+            a.1.sort_order
+                .cmp(&b.1.sort_order)
+                .then(a.1.creation_date.cmp(&b.1.creation_date))
+        });
+        tasks
+    }
+
+    fn board_column<'a>(
+        &'a self,
+        state: &'a TaskState,
+        tasks: Vec<(DefaultKey, &'a Task)>,
+    ) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let state_id = state.id;
+
+        let header = widget::row::with_capacity(2)
+            .align_y(Alignment::Center)
+            .spacing(spacing.space_xxs)
+            .padding([spacing.space_none, spacing.space_xxs])
+            .push(widget::text::heading(state.name.as_str()).width(Length::Fill))
+            .push(widget::text::caption(tasks.len().to_string()));
+
+        let mut cards = widget::column::with_capacity(tasks.len() + 1).spacing(spacing.space_xxs);
+        for (id, task) in tasks {
+            cards = cards.push(self.board_card(state_id, id, task));
+        }
+
+        // Below the last card, drops go to the end of the column.
+        let end: Element<'_, Message> = if self.drag_hover_column == Some(state_id) {
+            drop_indicator()
+        } else {
+            widget::Space::new().height(Length::Fixed(4.0)).into()
+        };
+        let end = widget::dnd_destination::dnd_destination_for_data::<TaskDrag, Message>(
+            widget::container(end)
+                .width(Length::Fill)
+                .height(Length::Fixed(spacing.space_xxl.into())),
+            move |data, _action| Message::CardDropped {
+                from: data.map(|d| d.uuid),
+                state_id,
+                before: None,
+            },
+        )
+        .on_enter(move |_x, _y, _mimes| Message::ColumnDragEntered(state_id))
+        .on_leave(move || Message::DragLeft);
+
+        widget::container(
+            widget::column::with_capacity(2)
+                .push(header)
+                .push(widget::scrollable(cards.push(end)).height(Length::Fill))
+                .spacing(spacing.space_xs),
+        )
+        .class(theme::Container::Primary)
+        .padding(spacing.space_xs)
+        .width(Length::Fixed(BOARD_COLUMN_WIDTH))
+        .height(Length::Fill)
+        .into()
+    }
+
+    fn board_card<'a>(
+        &'a self,
+        state_id: Uuid,
+        id: DefaultKey,
+        task: &'a Task,
+    ) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let sub_tasks = self.get_subtasks(task);
+
+        let title = widget::button::custom(widget::text::body(task.title.as_str()))
+            .class(cosmic::style::Button::Text)
+            .padding(spacing.space_none)
+            .width(Length::Fill)
+            .on_press(Message::TaskOpenDetails(id));
+        let title_row = widget::row::with_capacity(3)
+            .align_y(Alignment::Center)
+            .spacing(spacing.space_xxs)
+            .push(self.create_task_checkbox(id, task))
+            .push(title)
+            .push(self.create_task_menu(id, task));
+
+        let due_badge = self.create_due_badge(task, &spacing);
+        let tags = self.create_tag_chips(task, &spacing);
+        let subtask_count = self.create_subtask_counter(&sub_tasks);
+        let badges =
+            (due_badge.is_some() || tags.is_some() || subtask_count.is_some()).then(|| {
+                widget::row::with_capacity(3)
+                    .align_y(Alignment::Center)
+                    .spacing(spacing.space_xxs)
+                    .push_maybe(due_badge)
+                    .push_maybe(tags)
+                    .push_maybe(subtask_count)
+            });
+
+        let card = widget::container(
+            widget::column::with_capacity(2)
+                .push(title_row)
+                .push_maybe(badges)
+                .spacing(spacing.space_xxs),
+        )
+        .class(theme::Container::Card)
+        .padding(spacing.space_xs)
+        .width(Length::Fill);
+
+        let inner: Element<'_, Message> = if self.drag_hover == Some(id) {
+            widget::column::with_capacity(2)
+                .push(drop_indicator())
+                .push(card)
+                .spacing(spacing.space_xxs)
+                .into()
+        } else {
+            card.into()
+        };
+
+        let uuid = task.id;
+        widget::dnd_destination::dnd_destination_for_data::<TaskDrag, Message>(
+            widget::dnd_source::<Message, TaskDrag>(inner)
+                .drag_content(move || TaskDrag { uuid })
+                .on_start(Some(Message::DragStarted(id))),
+            move |data, _action| Message::CardDropped {
+                from: data.map(|d| d.uuid),
+                state_id,
+                before: Some(id),
+            },
+        )
+        .on_enter(move |_x, _y, _mimes| Message::DragEntered(id))
+        .on_leave(move || Message::DragLeft)
+        .into()
+    }
+
     fn create_list_icon<'a>(&'a self, list: &'a List, spacing: &Spacing) -> Element<'a, Message> {
         widget::icon::from_name(list.icon.as_deref().unwrap_or("view-list-symbolic"))
             .size(spacing.space_m)
@@ -653,6 +1106,7 @@ impl Content {
             }
             SortBy::DateAsc => tasks.sort_by(|a, b| a.1.creation_date.cmp(&b.1.creation_date)),
             SortBy::DateDesc => tasks.sort_by(|a, b| b.1.creation_date.cmp(&a.1.creation_date)),
+            SortBy::Modified => tasks.sort_by(|a, b| b.1.updated_at.cmp(&a.1.updated_at)),
             SortBy::Manual => {
                 tasks.sort_by(|a, b| {
                     a.1.sort_order
@@ -678,7 +1132,7 @@ impl Content {
                 let section_tasks: Vec<_> = tasks
                     .iter()
                     .copied()
-                    .filter(|(_, task)| task.effective_state_id() == state.id)
+                    .filter(|(_, task)| self.section_state_id(task) == state.id)
                     .collect();
 
                 (!section_tasks.is_empty()).then(|| self.section_view(state, section_tasks))
@@ -686,6 +1140,17 @@ impl Content {
             .collect()
     }
 
+    /// The state whose section a task is listed under. Tasks in a state
+    /// this list does not know about yet go under the built-in one.
+    fn section_state_id(&self, task: &Task) -> Uuid {
+        let state_id = task.effective_state_id();
+        if self.states.iter().any(|state| state.id == state_id) {
+            state_id
+        } else {
+            task.default_state_id()
+        }
+    }
+
     fn section_view<'a>(
         &'a self,
         state: &'a TaskState,
@@ -743,16 +1208,7 @@ impl Content {
 
         let inner: Element<'_, Message> = if is_hover {
             widget::column::with_capacity(2)
-                .push(
-                    cosmic::iced::widget::rule::horizontal(4).class(theme::Rule::custom(|theme| {
-                        cosmic::iced::widget::rule::Style {
-                            color: theme.cosmic().accent_color().into(),
-                            radius: 0.0.into(),
-                            fill_mode: cosmic::iced::widget::rule::FillMode::Full,
-                            snap: false,
-                        }
-                    })),
-                )
+                .push(drop_indicator())
                 .push(column)
                 .into()
         } else {
@@ -773,17 +1229,26 @@ impl Content {
         .into()
     }
 
+    /// The direct sub-tasks of `task`, in the order of its `sub_task_ids`.
     fn get_subtasks(&self, task: &Task) -> Vec<(DefaultKey, &Task)> {
         let should_hide_completed = self.config.hide_completed;
 
-        self.tasks
+        let mut sub_tasks: Vec<_> = self
+            .tasks
             .iter()
             .filter(|(_, sub_task)| {
                 let is_child = sub_task.parent_id == Some(task.id);
                 let show_despite_completion = !should_hide_completed || !sub_task.is_completed();
                 is_child && show_despite_completion
             })
-            .collect()
+            .collect();
+        sub_tasks.sort_by_key(|(_, sub_task)| {
+            task.sub_task_ids
+                .iter()
+                .position(|id| *id == sub_task.id)
+                .unwrap_or(usize::MAX)
+        });
+        sub_tasks
     }
 
     fn create_task_row<'a>(
@@ -797,8 +1262,11 @@ impl Content {
         let title_input = self.create_task_title_input(id, task);
         let expand_button = self.create_expand_button(id, task, sub_tasks, spacing);
         let subtask_count = self.create_subtask_counter(sub_tasks);
+        let tags = self.create_tag_chips(task, spacing);
+        let due_badge = self.create_due_badge(task, spacing);
+        let today_button = self.create_today_button(id, task, spacing);
         let favorite_button = self.create_favorite_button(id, task, spacing);
-        let menu = self.create_task_menu(id);
+        let menu = self.create_task_menu(id, task);
 
         let drag_handle: Option<Element<'_, Message>> = (self.config.sort_by == SortBy::Manual)
             .then(|| {
@@ -811,15 +1279,18 @@ impl Content {
                 }
             });
 
-        widget::row::with_capacity(7)
+        widget::row::with_capacity(10)
             .align_y(Alignment::Center)
             .spacing(spacing.space_xxxs)
             .padding([spacing.space_xxxs, spacing.space_s])
             .push_maybe(drag_handle)
             .push(checkbox)
             .push(title_input)
+            .push_maybe(due_badge)
+            .push_maybe(tags)
             .push_maybe(expand_button)
             .push_maybe(subtask_count)
+            .push(today_button)
             .push(favorite_button)
             .push(menu)
             .into()
@@ -831,6 +1302,19 @@ impl Content {
             .into()
     }
 
+    fn create_today_button<'a>(
+        &'a self,
+        id: DefaultKey,
+        task: &'a Task,
+        spacing: &Spacing,
+    ) -> Element<'a, Message> {
+        widget::button::icon(widget::icon::from_name("weather-clear-symbolic").size(16))
+            .selected(task.today)
+            .padding(spacing.space_xxs)
+            .on_press(Message::TaskToggleToday(id))
+            .into()
+    }
+
     fn create_favorite_button<'a>(
         &'a self,
         id: DefaultKey,
@@ -893,6 +1377,36 @@ impl Content {
         )
     }
 
+    fn create_due_badge<'a>(
+        &'a self,
+        task: &'a Task,
+        spacing: &Spacing,
+    ) -> Option<Element<'a, Message>> {
+        let due_date = task.due_date.as_ref().filter(|_| !task.is_completed())?;
+        Some(widgets::due_badge(due_date, &jiff::Zoned::now(), spacing))
+    }
+
+    fn create_tag_chips<'a>(
+        &'a self,
+        task: &'a Task,
+        spacing: &Spacing,
+    ) -> Option<Element<'a, Message>> {
+        if task.tags.is_empty() {
+            return None;
+        }
+
+        Some(
+            widget::row::with_children(
+                task.tags
+                    .iter()
+                    .map(|tag| widgets::tag_chip(tag, None, spacing))
+                    .collect::<Vec<_>>(),
+            )
+            .spacing(spacing.space_xxxs)
+            .into(),
+        )
+    }
+
     fn create_subtask_counter<'a>(
         &'a self,
         sub_tasks: &[(DefaultKey, &Task)],
@@ -912,7 +1426,22 @@ impl Content {
         Some(widget::text(format!("{}/{}", completed, total)).into())
     }
 
-    fn create_task_menu<'a>(&'a self, id: DefaultKey) -> Element<'a, Message> {
+    fn create_task_menu<'a>(&'a self, id: DefaultKey, task: &'a Task) -> Element<'a, Message> {
+        let mut states: Vec<&TaskState> = self.states.iter().collect();
+        states.sort_by_key(|state| state.position);
+        let current = task.effective_state_id();
+        let state_items = states
+            .into_iter()
+            .map(|state| {
+                widget::menu::Item::CheckBox(
+                    state.name.clone(),
+                    None,
+                    state.id == current,
+                    TaskAction::SetState(id, state.id),
+                )
+            })
+            .collect();
+
         widget::menu::MenuBar::new(vec![widget::menu::Tree::with_children(
             Element::from(
                 cosmic::widget::button::icon(
@@ -929,6 +1458,9 @@ impl Content {
                         None,
                         TaskAction::AddSubTask(id),
                     ),
+                    widget::menu::Item::Button(fl!("indent"), None, TaskAction::Indent(id)),
+                    widget::menu::Item::Button(fl!("outdent"), None, TaskAction::Outdent(id)),
+                    widget::menu::Item::Folder(fl!("state"), state_items),
                     widget::menu::Item::Button(fl!("move-to-trash"), None, TaskAction::Delete(id)),
                 ],
             ),
@@ -1027,38 +1559,43 @@ impl Content {
             .map(|(key, _)| key)
             .collect();
         for key in stale_keys {
-            self.tasks.remove(key);
-            self.inputs.remove(key);
-            self.editing.remove(key);
+            self.remove_task_row(key);
         }
 
         for task in tasks {
-            let existing_key = self
-                .tasks
-                .iter()
-                .find(|(_, t)| t.id == task.id)
-                .map(|(k, _)| k);
-            match existing_key {
-                Some(key) => {
-                    let is_editing = matches!(
-                        self.editing.get(key),
-                        Some(EditState::Editing) | Some(EditState::Entering)
-                    );
-                    if !is_editing {
-                        if let Some(existing) = self.tasks.get_mut(key) {
-                            *existing = task;
-                        }
+            self.reconcile_task(task);
+        }
+    }
+
+    /// Takes in a task read back from disk. Rows whose title is being edited
+    /// keep the local copy so the edit is not lost.
+    fn reconcile_task(&mut self, task: Task) {
+        match self.find_task_key(task.id) {
+            Some(key) => {
+                let is_editing = matches!(
+                    self.editing.get(key),
+                    Some(EditState::Editing) | Some(EditState::Entering)
+                );
+                if !is_editing {
+                    if let Some(existing) = self.tasks.get_mut(key) {
+                        *existing = task;
                     }
                 }
-                None => {
-                    let key = self.tasks.insert(task);
-                    self.inputs.insert(key, widget::Id::unique());
-                    self.editing.insert(key, EditState::Idle);
-                }
+            }
+            None => {
+                let key = self.tasks.insert(task);
+                self.inputs.insert(key, widget::Id::unique());
+                self.editing.insert(key, EditState::Idle);
             }
         }
     }
 
+    fn remove_task_row(&mut self, key: DefaultKey) {
+        self.tasks.remove(key);
+        self.inputs.remove(key);
+        self.editing.remove(key);
+    }
+
     fn create_no_list_selected_view<'a>(&'a self) -> Element<'a, Message> {
         widget::container(
             widget::column::with_children(vec![
@@ -1078,3 +1615,68 @@ impl Content {
         .into()
     }
 }
+
+/// The accent line showing where a dragged task will land.
+fn drop_indicator<'a>() -> Element<'a, Message> {
+    cosmic::iced::widget::rule::horizontal(4)
+        .class(theme::Rule::custom(|theme| {
+            cosmic::iced::widget::rule::Style {
+                color: theme.cosmic().accent_color().into(),
+                radius: 0.0.into(),
+                fill_mode: cosmic::iced::widget::rule::FillMode::Full,
+                snap: false,
+            }
+        }))
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content_with_tasks(titles: &[&str]) -> (Content, Vec<Task>) {
+        let store = Store::in_memory();
+        let list = List::new("List");
+        store.lists().save(&list).unwrap();
+        let tasks: Vec<Task> = titles
+            .iter()
+            .enumerate()
+            .map(|(order, title)| {
+                let mut task = Task::new(title);
+                task.sort_order = order as u32;
+                store.tasks(list.id).save(&task).unwrap();
+                task
+            })
+            .collect();
+
+        let mut content = Content::new(store, config::AppConfig::default());
+        content.update(Message::SetList(Some(list)));
+        (content, tasks)
+    }
+
+    fn parents(content: &Content, list_id: Uuid) -> Vec<Option<Uuid>> {
+        let mut tasks = content.store.tasks(list_id).load_all().unwrap();
+        tasks.sort_by_key(|task| task.sort_order);
+        tasks.into_iter().map(|task| task.parent_id).collect()
+    }
+
+    #[test]
+    fn tab_without_editing_leaves_hierarchy_unchanged() {
+        let (mut content, tasks) = content_with_tasks(&["first", "second"]);
+        let list_id = content.selected_list.as_ref().unwrap().id;
+
+        content.update(Message::IndentFocusedTask);
+        assert_eq!(parents(&content, list_id), vec![None, None]);
+
+        // A title that was edited and lost focus is no longer indented either.
+        let second = content.find_task_key(tasks[1].id).unwrap();
+        content.update(Message::TaskToggleTitleEditMode(second, true));
+        content.update(Message::TaskToggleTitleEditMode(second, false));
+        content.update(Message::IndentFocusedTask);
+        assert_eq!(parents(&content, list_id), vec![None, None]);
+
+        content.update(Message::TaskToggleTitleEditMode(second, true));
+        content.update(Message::IndentFocusedTask);
+        assert_eq!(parents(&content, list_id), vec![None, Some(tasks[0].id)]);
+    }
+}
@@ -2,6 +2,8 @@ use jiff::Timestamp;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::fl;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct List {
     pub id: Uuid,
@@ -9,8 +11,21 @@ pub struct List {
     pub description: String,
     pub icon: Option<String>,
     pub hide_completed: bool,
+    pub layout: ListLayout,
     #[serde(default = "Timestamp::now")]
     pub created_at: Timestamp,
+    /// Last time the list was changed through `ListStore::update`.
+    pub updated_at: Timestamp,
+}
+
+/// How a list shows its tasks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ListLayout {
+    /// Rows, in a section per state.
+    #[default]
+    List,
+    /// A column per state, with a card per task.
+    Board,
 }
 
 impl Default for List {
@@ -21,7 +36,9 @@ impl Default for List {
             description: String::default(),
             icon: None,
             hide_completed: false,
+            layout: ListLayout::default(),
             created_at: Timestamp::now(),
+            updated_at: Timestamp::now(),
         }
     }
 }
@@ -34,7 +51,18 @@ impl List {
             description: String::new(),
             icon: None,
             hide_completed: false,
+            layout: ListLayout::default(),
             created_at: Timestamp::now(),
+            updated_at: Timestamp::now(),
+        }
+    }
+
+    /// Stands in for a list whose tasks were found on disk but whose
+    /// registry entry was lost.
+    pub fn recovered(id: Uuid) -> Self {
+        Self {
+            id,
+            ..Self::new(fl!("recovered-list", id = id.to_string()[..8].to_string()))
         }
     }
 }
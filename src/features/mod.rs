@@ -1,7 +1,13 @@
+pub mod damaged;
 pub mod favorites;
 pub mod lists;
+pub mod my_day;
 pub mod reminders;
 pub mod search;
 pub mod settings;
+pub mod snapshots;
+pub mod states;
+pub mod tags;
 pub mod tasks;
 pub mod trash;
+pub mod upcoming;
@@ -0,0 +1,4 @@
+pub mod my_day;
+pub mod nav;
+
+pub use nav::MyDayMarker;
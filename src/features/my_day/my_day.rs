@@ -0,0 +1,302 @@
+use std::collections::HashSet;
+
+use cosmic::{
+    iced::{
+        alignment::{Horizontal, Vertical},
+        Alignment, Length,
+    },
+    theme, widget, Apply, Element,
+};
+
+use uuid::Uuid;
+
+use crate::{
+    features::{lists::list::List, tasks::task::Task},
+    fl,
+    shared::{
+        store::{query::TaskQuery, Store},
+        widgets::collapsible_section,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct MyDayEntry {
+    pub task: Task,
+    pub list_id: Uuid,
+    pub list_name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Section {
+    Picked,
+    Suggested,
+}
+
+/// The tasks picked for today, and the overdue or due ones that could be.
+pub struct MyDay {
+    picked: Vec<MyDayEntry>,
+    suggested: Vec<MyDayEntry>,
+    collapsed_sections: HashSet<Section>,
+    store: Store,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Load,
+    Add(Uuid),
+    Remove(Uuid),
+    Clear,
+    Open(Uuid),
+    ToggleSection(Section),
+}
+
+pub enum Output {
+    OpenTask { task: Task, list_id: Uuid },
+}
+
+impl MyDay {
+    pub fn new(store: Store) -> Self {
+        Self {
+            picked: Vec::new(),
+            suggested: Vec::new(),
+            collapsed_sections: HashSet::new(),
+            store,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<Output> {
+        match message {
+            Message::Load => {
+                let lists: Vec<List> = self.store.lists().load_all().unwrap_or_else(|e| {
+                    tracing::error!("Failed to load lists for My Day: {e}");
+                    vec![]
+                });
+
+                let picked = self
+                    .store
+                    .query(&TaskQuery::new().today(true))
+                    .unwrap_or_else(|e| {
+                        tracing::error!("Failed to load My Day tasks: {e}");
+                        vec![]
+                    });
+                let suggested = self
+                    .store
+                    .my_day_suggestions(jiff::Zoned::now().date())
+                    .unwrap_or_else(|e| {
+                        tracing::error!("Failed to load My Day suggestions: {e}");
+                        vec![]
+                    });
+
+                self.picked = entries(&lists, picked);
+                self.suggested = entries(&lists, suggested);
+            }
+            Message::Add(task_id) => self.set_today(task_id, true),
+            Message::Remove(task_id) => self.set_today(task_id, false),
+            Message::Clear => {
+                if let Err(e) = self.store.clear_my_day() {
+                    tracing::error!("Failed to clear My Day: {e}");
+                }
+                return self.update(Message::Load);
+            }
+            Message::Open(task_id) => {
+                if let Some(entry) = self
+                    .picked
+                    .iter()
+                    .chain(&self.suggested)
+                    .find(|e| e.task.id == task_id)
+                {
+                    return Some(Output::OpenTask {
+                        task: entry.task.clone(),
+                        list_id: entry.list_id,
+                    });
+                }
+            }
+            Message::ToggleSection(section) => {
+                if !self.collapsed_sections.remove(&section) {
+                    self.collapsed_sections.insert(section);
+                }
+            }
+        }
+        None
+    }
+
+    fn set_today(&mut self, task_id: Uuid, today: bool) {
+        let Some(list_id) = self
+            .picked
+            .iter()
+            .chain(&self.suggested)
+            .find(|e| e.task.id == task_id)
+            .map(|e| e.list_id)
+        else {
+            return;
+        };
+        if let Err(e) = self
+            .store
+            .tasks(list_id)
+            .update(task_id, |t| t.today = today)
+        {
+            tracing::error!("Failed to update My Day: {e}");
+        }
+        self.update(Message::Load);
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        if self.picked.is_empty() && self.suggested.is_empty() {
+            return self.empty_view();
+        }
+
+        let spacing = theme::active().cosmic().spacing;
+
+        let mut sections = widget::column::with_capacity(2).spacing(spacing.space_xxs);
+        if !self.picked.is_empty() {
+            sections = sections.push(self.section(Section::Picked, fl!("my-day"), &self.picked));
+        }
+        if !self.suggested.is_empty() {
+            sections = sections.push(self.section(
+                Section::Suggested,
+                fl!("my-day-suggestions"),
+                &self.suggested,
+            ));
+        }
+
+        let content = widget::column::with_capacity(2)
+            .push(self.header_view())
+            .push(widget::scrollable(sections).height(Length::Fill))
+            .spacing(spacing.space_s)
+            .padding([spacing.space_xxs, spacing.space_xxxs]);
+
+        widget::container(content)
+            .height(Length::Fill)
+            .width(Length::Fill)
+            .center_x(Length::Fill)
+            .max_width(800.)
+            .apply(widget::container)
+            .height(Length::Fill)
+            .width(Length::Fill)
+            .center(Length::Fill)
+            .into()
+    }
+
+    fn header_view(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let icon = widget::icon::from_name("weather-clear-symbolic").size(spacing.space_m);
+        let title = widget::column::with_capacity(2)
+            .push(widget::text::title4(fl!("my-day")))
+            .push(widget::text::caption(
+                jiff::Zoned::now().strftime("%A, %B %-d").to_string(),
+            ))
+            .width(Length::Fill);
+
+        let clear = (!self.picked.is_empty())
+            .then(|| widget::button::text(fl!("my-day-clear")).on_press(Message::Clear));
+
+        widget::row::with_capacity(3)
+            .align_y(Alignment::Center)
+            .spacing(spacing.space_s)
+            .padding([spacing.space_none, spacing.space_xxs])
+            .push(icon)
+            .push(title)
+            .push_maybe(clear)
+            .into()
+    }
+
+    fn section<'a>(
+        &'a self,
+        section: Section,
+        title: String,
+        entries: &'a [MyDayEntry],
+    ) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let collapsed = self.collapsed_sections.contains(&section);
+
+        let header = collapsible_section::section_header(
+            title,
+            None,
+            entries.len(),
+            collapsed,
+            Vec::new(),
+            Message::ToggleSection(section),
+            &spacing,
+        );
+
+        let rows = entries.iter().map(|entry| self.entry_row(entry)).collect();
+
+        collapsible_section::section(header, rows, collapsed)
+    }
+
+    fn entry_row<'a>(&'a self, entry: &'a MyDayEntry) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let task_id = entry.task.id;
+        let today_button = if entry.task.today {
+            widget::button::icon(widget::icon::from_name("weather-clear-symbolic").size(16))
+                .selected(true)
+                .padding(spacing.space_xxs)
+                .on_press(Message::Remove(task_id))
+        } else {
+            widget::button::icon(widget::icon::from_name("list-add-symbolic").size(16))
+                .padding(spacing.space_xxs)
+                .on_press(Message::Add(task_id))
+        };
+
+        let mut subtitle = entry.list_name.clone();
+        if let Some(due_date) = &entry.task.due_date {
+            subtitle = format!("{subtitle} · {due_date}");
+        }
+        let title = widget::column::with_capacity(2)
+            .push(widget::text::body(entry.task.title.as_str()))
+            .push(widget::text::caption(subtitle))
+            .width(Length::Fill);
+
+        let open_button =
+            widget::button::icon(widget::icon::from_name("go-next-symbolic").size(16))
+                .padding(spacing.space_xxs)
+                .on_press(Message::Open(task_id));
+
+        let row = widget::row::with_capacity(3)
+            .align_y(Alignment::Center)
+            .spacing(spacing.space_s)
+            .padding([spacing.space_xxs, spacing.space_xs])
+            .push(today_button)
+            .push(title)
+            .push(open_button);
+
+        collapsible_section::row_item(row.into())
+    }
+
+    fn empty_view(&self) -> Element<'_, Message> {
+        widget::container(
+            widget::column::with_children(vec![
+                widget::icon::from_name("weather-clear-symbolic")
+                    .size(56)
+                    .into(),
+                widget::text::title1(fl!("no-my-day")).into(),
+                widget::text(fl!("no-my-day-suggestion")).into(),
+            ])
+            .spacing(10)
+            .align_x(Alignment::Center),
+        )
+        .align_y(Vertical::Center)
+        .align_x(Horizontal::Center)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into()
+    }
+}
+
+/// Pairs each task with the name of its list, leaving out tasks whose list
+/// is gone.
+fn entries(lists: &[List], tasks: Vec<(Uuid, Task)>) -> Vec<MyDayEntry> {
+    tasks
+        .into_iter()
+        .filter_map(|(list_id, task)| {
+            let list = lists.iter().find(|l| l.id == list_id)?;
+            Some(MyDayEntry {
+                task,
+                list_id,
+                list_name: list.name.clone(),
+            })
+        })
+        .collect()
+}
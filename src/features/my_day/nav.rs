@@ -0,0 +1,49 @@
+use cosmic::widget;
+
+use crate::{
+    app::AppModel,
+    fl,
+    shared::{dialogs::DialogPage, store::query::TaskQuery},
+};
+
+pub struct MyDayMarker;
+
+impl AppModel {
+    pub fn show_my_day_nav_item(&mut self) {
+        let icon = widget::icon::from_name("weather-clear-symbolic").size(16);
+        self.my_day_entity = self
+            .nav
+            .insert()
+            .text(fl!("my-day"))
+            .icon(icon)
+            .data(MyDayMarker)
+            .id();
+        self.reposition_special_items();
+    }
+
+    pub fn hide_my_day_nav_item(&mut self) {
+        self.nav.remove(self.my_day_entity);
+        self.reposition_special_items();
+    }
+
+    /// On the first launch of a day, offers to clear the tasks left in My
+    /// Day from before or carry them over.
+    pub fn start_my_day(&mut self) {
+        let today = jiff::Zoned::now().date();
+        if self.config.my_day_date == Some(today) {
+            return;
+        }
+        if let Err(err) = self.config.set_my_day_date(&self.handler, Some(today)) {
+            tracing::error!("{err}");
+        }
+
+        match self.store.query(&TaskQuery::new().today(true)) {
+            Ok(picked) if !picked.is_empty() => {
+                self.dialog_pages
+                    .push_back(DialogPage::NewDay(picked.len()));
+            }
+            Ok(_) => {}
+            Err(err) => tracing::error!("Error loading My Day tasks: {err}"),
+        }
+    }
+}
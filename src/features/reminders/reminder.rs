@@ -4,6 +4,7 @@ use jiff::Timestamp;
 use notify_rust::Timeout;
 use uuid::Uuid;
 
+use crate::shared::store::query::{TaskQuery, TaskSort};
 use crate::shared::store::Store;
 
 #[derive(Debug, Clone)]
@@ -19,56 +20,49 @@ pub fn check_and_notify(
 ) -> Vec<(Uuid, i64)> {
     let mut notified = Vec::new();
 
-    let lists = match store.lists().load_all() {
-        Ok(lists) => lists,
+    let query = TaskQuery::new()
+        .reminder_between(window_start, now)
+        .sort_by(TaskSort::Reminder);
+    let due = match store.query(&query) {
+        Ok(due) => due,
         Err(err) => {
-            tracing::error!("reminder: failed to load lists: {err}");
+            tracing::error!("reminder: failed to load reminders: {err}");
             return notified;
         }
     };
 
-    for list in lists {
-        let tasks = match store.tasks(list.id).load_all() {
-            Ok(tasks) => tasks,
-            Err(err) => {
-                tracing::error!("reminder: failed to load tasks for list {}: {err}", list.id);
-                continue;
-            }
+    for (_, task) in due {
+        let Some(reminder) = task.reminder_date else {
+            continue;
         };
 
-        for task in tasks {
-            let Some(reminder) = task.reminder_date else {
-                continue;
-            };
+        let key = (task.id, reminder.as_second());
 
-            let key = (task.id, reminder.as_second());
-
-            if sent.contains(&key) || reminder < window_start || reminder > now {
-                continue;
-            }
+        if sent.contains(&key) {
+            continue;
+        }
 
-            let result = notify_rust::Notification::new()
-                .summary("Task Reminder")
-                .body(&task.title)
-                .icon("dev.edfloreshz.Tasks")
-                .timeout(Timeout::Milliseconds(5000))
-                .show();
+        let result = notify_rust::Notification::new()
+            .summary("Task Reminder")
+            .body(&task.title)
+            .icon("dev.edfloreshz.Tasks")
+            .timeout(Timeout::Milliseconds(5000))
+            .show();
 
-            match result {
-                Ok(_) => {
-                    tracing::info!(
-                        "reminder: sent notification for task \"{}\" ({})",
-                        task.title,
-                        task.id
-                    );
-                    notified.push(key);
-                }
-                Err(err) => {
-                    tracing::error!(
-                        "reminder: failed to send notification for task \"{}\": {err}",
-                        task.title
-                    );
-                }
+        match result {
+            Ok(_) => {
+                tracing::info!(
+                    "reminder: sent notification for task \"{}\" ({})",
+                    task.title,
+                    task.id
+                );
+                notified.push(key);
+            }
+            Err(err) => {
+                tracing::error!(
+                    "reminder: failed to send notification for task \"{}\": {err}",
+                    task.title
+                );
             }
         }
     }
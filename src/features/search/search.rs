@@ -13,7 +13,10 @@ use uuid::Uuid;
 use crate::{
     features::{lists::list::List, tasks::task::Task},
     fl,
-    shared::{store::Store, widgets::collapsible_section},
+    shared::{
+        store::{query::TaskQuery, Store},
+        widgets::collapsible_section,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -44,6 +47,8 @@ pub enum Message {
     ToggleScope,
     Load,
     Loaded(Vec<SearchEntry>),
+    TaskChanged(Uuid, Task),
+    TaskRemoved(Uuid),
     Open(Uuid),
     ToggleSection(Uuid),
 }
@@ -92,26 +97,45 @@ impl Search {
                     vec![]
                 });
 
-                let mut entries = Vec::new();
-                for list in &lists {
-                    let tasks = self.store.tasks(list.id).load_all().unwrap_or_else(|e| {
-                        tracing::error!("Failed to load tasks for list {}: {e}", list.id);
-                        vec![]
-                    });
-                    for task in tasks {
-                        entries.push(SearchEntry {
+                let tasks = self.store.query(&TaskQuery::new()).unwrap_or_else(|e| {
+                    tracing::error!("Failed to load tasks for search: {e}");
+                    vec![]
+                });
+
+                let entries = tasks
+                    .into_iter()
+                    .filter_map(|(list_id, task)| {
+                        let list = lists.iter().find(|l| l.id == list_id)?;
+                        Some(SearchEntry {
                             task,
-                            list_id: list.id,
+                            list_id,
                             list_name: list.name.clone(),
-                        });
-                    }
-                }
+                        })
+                    })
+                    .collect();
 
                 return self.update(Message::Loaded(entries));
             }
             Message::Loaded(entries) => {
                 self.entries = entries;
             }
+            Message::TaskChanged(list_id, task) => {
+                if let Some(entry) = self.entries.iter_mut().find(|e| e.task.id == task.id) {
+                    entry.task = task;
+                } else {
+                    match self.store.lists().get(list_id) {
+                        Ok(list) => self.entries.push(SearchEntry {
+                            task,
+                            list_id,
+                            list_name: list.name,
+                        }),
+                        Err(e) => tracing::error!("Failed to find list for search entry: {e}"),
+                    }
+                }
+            }
+            Message::TaskRemoved(task_id) => {
+                self.entries.retain(|e| e.task.id != task_id);
+            }
             Message::Open(task_id) => {
                 if let Some(entry) = self.entries.iter().find(|e| e.task.id == task_id) {
                     return Some(Output::OpenTask {
@@ -1,51 +1,144 @@
 use cosmic::{widget, Element};
 
 use crate::{
-    app::{AppModel, Message},
+    app::{AppModel, ContextPage, Message},
     fl,
-    shared::navigation::ui::{ApplicationAction, MenuAction},
+    shared::{
+        navigation::ui::{ApplicationAction, MenuAction},
+        store::snapshot::SnapshotRetention,
+    },
 };
 
+const HOURLY_SNAPSHOTS: [u32; 5] = [0, 6, 12, 24, 48];
+const DAILY_SNAPSHOTS: [u32; 5] = [0, 3, 7, 14, 30];
+const WEEKLY_SNAPSHOTS: [u32; 5] = [0, 2, 4, 8, 12];
+
 pub fn settings(app: &AppModel) -> Element<'_, Message> {
-    widget::scrollable(
-        widget::settings::section()
-            .title(fl!("appearance"))
-            .add(widget::settings::item::item(
-                fl!("theme"),
-                widget::dropdown(
-                    vec![fl!("match-desktop"), fl!("dark"), fl!("light")],
-                    Some(app.config.app_theme.into()),
-                    |theme| Message::Application(ApplicationAction::AppTheme(theme)),
-                ),
-            ))
-            .add(widget::settings::item::item(
-                fl!("show-favorites"),
-                widget::toggler(app.config.show_favorites).on_toggle(|val| {
-                    Message::Application(ApplicationAction::ToggleShowFavorites(val))
-                }),
-            ))
-            .add(widget::settings::item::item(
-                fl!("show-trash"),
-                widget::toggler(app.config.show_trash)
-                    .on_toggle(|val| Message::Application(ApplicationAction::ToggleShowTrash(val))),
-            ))
-            .add(widget::settings::item::item(
-                fl!("sort-lists-by"),
-                widget::dropdown(
-                    vec![
-                        fl!("sort-name-asc"),
-                        fl!("sort-name-desc"),
-                        fl!("sort-manual"),
-                    ],
-                    Some(app.config.list_sort_by.into()),
-                    |sort_by| Message::Application(ApplicationAction::ListSortBy(sort_by)),
-                ),
-            ))
-            .add(widget::settings::item::item(
-                fl!("hide-completed"),
-                widget::toggler(app.config.hide_completed)
-                    .on_toggle(|val| Message::Menu(MenuAction::ToggleHideCompleted(val))),
-            )),
+    let mut sections = vec![appearance(app), states()];
+    if app.snapshots.is_available() {
+        sections.push(snapshots(app));
+    }
+    widget::scrollable(widget::settings::view_column(sections)).into()
+}
+
+fn appearance(app: &AppModel) -> Element<'_, Message> {
+    widget::settings::section()
+        .title(fl!("appearance"))
+        .add(widget::settings::item::item(
+            fl!("theme"),
+            widget::dropdown(
+                vec![fl!("match-desktop"), fl!("dark"), fl!("light")],
+                Some(app.config.app_theme.into()),
+                |theme| Message::Application(ApplicationAction::AppTheme(theme)),
+            ),
+        ))
+        .add(widget::settings::item::item(
+            fl!("show-my-day"),
+            widget::toggler(app.config.show_my_day)
+                .on_toggle(|val| Message::Application(ApplicationAction::ToggleShowMyDay(val))),
+        ))
+        .add(widget::settings::item::item(
+            fl!("show-upcoming"),
+            widget::toggler(app.config.show_upcoming)
+                .on_toggle(|val| Message::Application(ApplicationAction::ToggleShowUpcoming(val))),
+        ))
+        .add(widget::settings::item::item(
+            fl!("show-favorites"),
+            widget::toggler(app.config.show_favorites)
+                .on_toggle(|val| Message::Application(ApplicationAction::ToggleShowFavorites(val))),
+        ))
+        .add(widget::settings::item::item(
+            fl!("show-trash"),
+            widget::toggler(app.config.show_trash)
+                .on_toggle(|val| Message::Application(ApplicationAction::ToggleShowTrash(val))),
+        ))
+        .add(widget::settings::item::item(
+            fl!("sort-lists-by"),
+            widget::dropdown(
+                vec![
+                    fl!("sort-name-asc"),
+                    fl!("sort-name-desc"),
+                    fl!("sort-manual"),
+                    fl!("sort-modified"),
+                ],
+                Some(app.config.list_sort_by.into()),
+                |sort_by| Message::Application(ApplicationAction::ListSortBy(sort_by)),
+            ),
+        ))
+        .add(widget::settings::item::item(
+            fl!("hide-completed"),
+            widget::toggler(app.config.hide_completed)
+                .on_toggle(|val| Message::Menu(MenuAction::ToggleHideCompleted(val))),
+        ))
+        .into()
+}
+
+fn states<'a>() -> Element<'a, Message> {
+    widget::settings::section()
+        .title(fl!("states"))
+        .add(widget::settings::item::item(
+            fl!("manage-states"),
+            widget::button::standard(fl!("edit"))
+                .on_press(Message::ToggleContextPage(ContextPage::States)),
+        ))
+        .into()
+}
+
+fn snapshots(app: &AppModel) -> Element<'_, Message> {
+    let retention = app.config.snapshot_retention;
+
+    widget::settings::section()
+        .title(fl!("snapshots"))
+        .add(widget::settings::item::item(
+            fl!("keep-hourly-snapshots"),
+            retention_dropdown(&HOURLY_SNAPSHOTS, retention.hourly, move |hourly| {
+                SnapshotRetention {
+                    hourly,
+                    ..retention
+                }
+            }),
+        ))
+        .add(widget::settings::item::item(
+            fl!("keep-daily-snapshots"),
+            retention_dropdown(&DAILY_SNAPSHOTS, retention.daily, move |daily| {
+                SnapshotRetention { daily, ..retention }
+            }),
+        ))
+        .add(widget::settings::item::item(
+            fl!("keep-weekly-snapshots"),
+            retention_dropdown(&WEEKLY_SNAPSHOTS, retention.weekly, move |weekly| {
+                SnapshotRetention {
+                    weekly,
+                    ..retention
+                }
+            }),
+        ))
+        .add(widget::settings::item::item(
+            fl!("browse-snapshots"),
+            widget::button::standard(fl!("browse"))
+                .on_press(Message::ToggleContextPage(ContextPage::Snapshots)),
+        ))
+        .into()
+}
+
+fn retention_dropdown<'a>(
+    choices: &'static [u32],
+    current: u32,
+    with: impl Fn(u32) -> SnapshotRetention + Send + Sync + 'static,
+) -> Element<'a, Message> {
+    let labels: Vec<String> = choices
+        .iter()
+        .map(|&count| match count {
+            0 => fl!("snapshots-off"),
+            count => count.to_string(),
+        })
+        .collect();
+    widget::dropdown(
+        labels,
+        choices.iter().position(|&count| count == current),
+        move |index| {
+            Message::Application(ApplicationAction::SnapshotRetention(with(choices[index])))
+        },
     )
     .into()
 }
@@ -0,0 +1,254 @@
+use cosmic::{
+    iced::{Alignment, Length},
+    theme, widget, Element,
+};
+use uuid::Uuid;
+
+use crate::{
+    features::tasks::task::Task,
+    fl,
+    shared::store::snapshot::{
+        DiffStatus, ListDiff, Snapshot, SnapshotArchive, SnapshotDiff, SnapshotReason,
+        SnapshotRetention,
+    },
+};
+
+/// Browses the rolling snapshots of the store, compares them with the
+/// current state and restores them. Also takes the scheduled snapshots.
+pub struct Snapshots {
+    archive: Option<SnapshotArchive>,
+    retention: SnapshotRetention,
+    snapshots: Vec<Snapshot>,
+    selected: Option<(Snapshot, SnapshotDiff)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Tick,
+    Load,
+    SetRetention(SnapshotRetention),
+    TakeNow,
+    Select(String),
+    Back,
+    RequestRestore(String),
+    Restore(String),
+    RequestRestoreList(String, Uuid),
+    RestoreList(String, Uuid),
+}
+
+pub enum Output {
+    Taken,
+    RestoreRequested(String, String),
+    RestoreListRequested(String, Uuid, String),
+    Restored,
+    Failed(String),
+}
+
+impl Snapshots {
+    /// `archive` is `None` when the store is not kept in a directory that
+    /// can be snapshotted.
+    pub fn new(archive: Option<SnapshotArchive>, retention: SnapshotRetention) -> Self {
+        Self {
+            archive,
+            retention,
+            snapshots: Vec::new(),
+            selected: None,
+        }
+    }
+
+    /// Whether the store can be snapshotted at all. Settings and the
+    /// snapshot browser are only offered if it can.
+    pub fn is_available(&self) -> bool {
+        self.archive.is_some()
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<Output> {
+        let archive = self.archive.as_ref()?;
+        match message {
+            Message::Tick => {
+                if let Err(e) = archive.take_if_due(self.retention) {
+                    tracing::error!("Failed to take scheduled snapshot: {e}");
+                }
+                return self.update(Message::Load);
+            }
+            Message::Load => {
+                self.snapshots = archive.list().unwrap_or_else(|e| {
+                    tracing::error!("Failed to list snapshots: {e}");
+                    vec![]
+                });
+                if let Some((snapshot, _)) = &self.selected {
+                    let id = snapshot.id.clone();
+                    return self.update(Message::Select(id));
+                }
+            }
+            Message::SetRetention(retention) => {
+                self.retention = retention;
+                if let Err(e) = archive.prune(retention) {
+                    tracing::error!("Failed to prune snapshots: {e}");
+                }
+                return self.update(Message::Load);
+            }
+            Message::TakeNow => {
+                let output = match archive.take(SnapshotReason::Manual) {
+                    Ok(_) => Output::Taken,
+                    Err(e) => Output::Failed(e.to_string()),
+                };
+                self.update(Message::Load);
+                return Some(output);
+            }
+            Message::Select(id) => {
+                self.selected = match archive.get(&id).and_then(|s| Ok((s, archive.diff(&id)?))) {
+                    Ok(selected) => Some(selected),
+                    Err(e) => {
+                        tracing::error!("Failed to compare snapshot {id}: {e}");
+                        None
+                    }
+                };
+            }
+            Message::Back => {
+                self.selected = None;
+            }
+            Message::RequestRestore(id) => {
+                let snapshot = self.snapshots.iter().find(|s| s.id == id)?;
+                let date = Task::format_timestamp(&snapshot.taken_at);
+                return Some(Output::RestoreRequested(id, date));
+            }
+            Message::Restore(id) => {
+                let output = match archive.restore(&id) {
+                    Ok(()) => Output::Restored,
+                    Err(e) => Output::Failed(e.to_string()),
+                };
+                self.selected = None;
+                self.update(Message::Load);
+                return Some(output);
+            }
+            Message::RequestRestoreList(id, list_id) => {
+                let (_, diff) = self.selected.as_ref()?;
+                let list = diff.lists.iter().find(|l| l.list_id == list_id)?;
+                return Some(Output::RestoreListRequested(id, list_id, list.name.clone()));
+            }
+            Message::RestoreList(id, list_id) => {
+                let output = match archive.restore_list(&id, list_id) {
+                    Ok(_) => Output::Restored,
+                    Err(e) => Output::Failed(e.to_string()),
+                };
+                self.update(Message::Load);
+                return Some(output);
+            }
+        }
+
+        None
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        if self.archive.is_none() {
+            return widget::text::body(fl!("snapshots-unavailable")).into();
+        }
+        match &self.selected {
+            Some((snapshot, diff)) => self.diff_view(snapshot, diff),
+            None => self.list_view(),
+        }
+    }
+
+    fn list_view(&self) -> Element<'_, Message> {
+        let mut section = widget::settings::section().title(fl!("snapshots"));
+        for snapshot in &self.snapshots {
+            section = section.add(
+                widget::settings::item::builder(Task::format_timestamp(&snapshot.taken_at))
+                    .description(reason_label(snapshot.reason))
+                    .control(
+                        widget::button::text(fl!("compare"))
+                            .on_press(Message::Select(snapshot.id.clone())),
+                    ),
+            );
+        }
+
+        let mut children: Vec<Element<'_, Message>> = vec![
+            widget::text::caption(fl!("snapshots-description")).into(),
+            widget::button::standard(fl!("take-snapshot"))
+                .on_press(Message::TakeNow)
+                .into(),
+        ];
+        if self.snapshots.is_empty() {
+            children.push(widget::text::body(fl!("no-snapshots")).into());
+        } else {
+            children.push(section.into());
+        }
+
+        widget::settings::view_column(children).into()
+    }
+
+    fn diff_view<'a>(
+        &'a self,
+        snapshot: &'a Snapshot,
+        diff: &'a SnapshotDiff,
+    ) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let header = widget::row::with_capacity(3)
+            .align_y(Alignment::Center)
+            .spacing(spacing.space_s)
+            .push(
+                widget::button::icon(widget::icon::from_name("go-previous-symbolic"))
+                    .on_press(Message::Back),
+            )
+            .push(
+                widget::text::title4(Task::format_timestamp(&snapshot.taken_at))
+                    .width(Length::Fill),
+            )
+            .push(
+                widget::button::destructive(fl!("restore-all"))
+                    .on_press(Message::RequestRestore(snapshot.id.clone())),
+            );
+
+        let mut children: Vec<Element<'a, Message>> = vec![header.into()];
+        if diff.is_empty() {
+            children.push(widget::text::body(fl!("no-snapshot-changes")).into());
+        }
+        children.extend(
+            diff.lists
+                .iter()
+                .map(|list| Self::list_diff_view(&snapshot.id, list)),
+        );
+
+        widget::settings::view_column(children).into()
+    }
+
+    fn list_diff_view<'a>(id: &str, list: &'a ListDiff) -> Element<'a, Message> {
+        let title = format!("{} · {}", list.name, status_label(list.status));
+        let mut section = widget::settings::section().title(title);
+
+        // A list added since the snapshot has nothing in it to go back to.
+        if list.status != DiffStatus::Added {
+            section = section.add(
+                widget::settings::item::builder(fl!("restore-list")).control(
+                    widget::button::text(fl!("restore"))
+                        .on_press(Message::RequestRestoreList(id.to_string(), list.list_id)),
+                ),
+            );
+        }
+        for task in &list.tasks {
+            section = section.add(
+                widget::settings::item::builder(task.title.clone())
+                    .control(widget::text::caption(status_label(task.status))),
+            );
+        }
+        section.into()
+    }
+}
+
+fn reason_label(reason: SnapshotReason) -> String {
+    match reason {
+        SnapshotReason::Scheduled => fl!("snapshot-scheduled"),
+        SnapshotReason::Manual => fl!("snapshot-manual"),
+        SnapshotReason::BeforeRestore => fl!("snapshot-before-restore"),
+    }
+}
+
+fn status_label(status: DiffStatus) -> String {
+    match status {
+        DiffStatus::Added => fl!("snapshot-added"),
+        DiffStatus::Removed => fl!("snapshot-removed"),
+        DiffStatus::Changed => fl!("snapshot-changed"),
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use cosmic::{
+    iced::{Alignment, Length},
+    theme, widget, Element,
+};
+use uuid::Uuid;
+
+use crate::{features::tasks::state::TaskState, fl, shared::store::Store};
+
+/// Creates, renames, reorders and deletes task states, and sets which of
+/// them count as completed.
+pub struct States {
+    states: Vec<TaskState>,
+    /// Names as typed so far, until they are submitted.
+    names: HashMap<Uuid, String>,
+    task_counts: HashMap<Uuid, usize>,
+    new_state: String,
+    store: Store,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Load,
+    NameInput(Uuid, String),
+    Rename(Uuid),
+    SetCompleted(Uuid, bool),
+    MoveUp(Uuid),
+    MoveDown(Uuid),
+    Delete(Uuid),
+    DeleteConfirmed(Uuid, Option<Uuid>),
+    NewStateInput(String),
+    Add,
+}
+
+pub enum Output {
+    /// States changed, and maybe the tasks in them.
+    Changed,
+    DeleteRequested {
+        state: TaskState,
+        task_count: usize,
+        others: Vec<TaskState>,
+    },
+}
+
+impl States {
+    pub fn new(store: Store) -> Self {
+        Self {
+            states: Vec::new(),
+            names: HashMap::new(),
+            task_counts: HashMap::new(),
+            new_state: String::new(),
+            store,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<Output> {
+        match message {
+            Message::Load => {
+                self.states = self.store.states().load_all().unwrap_or_else(|e| {
+                    tracing::error!("Failed to load task states: {e}");
+                    vec![]
+                });
+                self.states.sort_by_key(|state| state.position);
+                self.names = self
+                    .states
+                    .iter()
+                    .map(|state| (state.id, state.name.clone()))
+                    .collect();
+                self.task_counts = self.store.state_task_counts().unwrap_or_else(|e| {
+                    tracing::error!("Failed to count tasks per state: {e}");
+                    HashMap::new()
+                });
+            }
+            Message::NameInput(state_id, name) => {
+                self.names.insert(state_id, name);
+            }
+            Message::Rename(state_id) => {
+                let name = self.names.get(&state_id)?.trim().to_string();
+                if name.is_empty() {
+                    return self.update(Message::Load);
+                }
+                let result = self
+                    .store
+                    .states()
+                    .update(state_id, |state| state.name = name);
+                return self.changed(result.map(drop));
+            }
+            Message::SetCompleted(state_id, completed) => {
+                let result = self.store.set_state_completed(state_id, completed);
+                return self.changed(result.map(drop));
+            }
+            Message::MoveUp(state_id) => return self.move_state(state_id, -1),
+            Message::MoveDown(state_id) => return self.move_state(state_id, 1),
+            Message::Delete(state_id) => {
+                let state = self.states.iter().find(|state| state.id == state_id)?;
+                return Some(Output::DeleteRequested {
+                    state: state.clone(),
+                    task_count: self.task_counts.get(&state_id).copied().unwrap_or(0),
+                    others: self
+                        .states
+                        .iter()
+                        .filter(|other| other.id != state_id)
+                        .cloned()
+                        .collect(),
+                });
+            }
+            Message::DeleteConfirmed(state_id, into) => {
+                let into = into.and_then(|into| self.states.iter().find(|s| s.id == into));
+                let result = self.store.delete_state(state_id, into);
+                return self.changed(result.map(drop));
+            }
+            Message::NewStateInput(name) => {
+                self.new_state = name;
+            }
+            Message::Add => {
+                let name = self.new_state.trim();
+                if name.is_empty() {
+                    return None;
+                }
+                let position = self
+                    .states
+                    .iter()
+                    .map(|state| state.position + 1)
+                    .max()
+                    .unwrap_or(0);
+                let state = TaskState::new(name, false, position);
+                self.new_state.clear();
+                return self.changed(self.store.states().save(&state));
+            }
+        }
+        None
+    }
+
+    fn move_state(&mut self, state_id: Uuid, by: isize) -> Option<Output> {
+        let from = self.states.iter().position(|state| state.id == state_id)?;
+        let to = from.checked_add_signed(by)?;
+        if to >= self.states.len() {
+            return None;
+        }
+        let mut order: Vec<Uuid> = self.states.iter().map(|state| state.id).collect();
+        order.swap(from, to);
+        let result = self.store.states().reorder(&order);
+        self.changed(result)
+    }
+
+    /// Reloads after a change, which the rest of the app hears about only
+    /// when it went through.
+    fn changed(&mut self, result: crate::Result<()>) -> Option<Output> {
+        let output = match result {
+            Ok(()) => Some(Output::Changed),
+            Err(e) => {
+                tracing::error!("Failed to change task states: {e}");
+                None
+            }
+        };
+        self.update(Message::Load);
+        output
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let mut section = widget::settings::section().title(fl!("states"));
+        let last = self.states.len().saturating_sub(1);
+        for (index, state) in self.states.iter().enumerate() {
+            let name = self.names.get(&state.id).map_or("", String::as_str);
+            let count = self.task_counts.get(&state.id).copied().unwrap_or(0);
+
+            let completed = widget::checkbox(state.is_completed);
+            let completed = if state.is_built_in() {
+                completed
+            } else {
+                let state_id = state.id;
+                completed.on_toggle(move |completed| Message::SetCompleted(state_id, completed))
+            };
+
+            let move_up = widget::button::icon(widget::icon::from_name("go-up-symbolic").size(16))
+                .padding(spacing.space_xxs)
+                .on_press_maybe((index > 0).then_some(Message::MoveUp(state.id)));
+            let move_down =
+                widget::button::icon(widget::icon::from_name("go-down-symbolic").size(16))
+                    .padding(spacing.space_xxs)
+                    .on_press_maybe((index < last).then_some(Message::MoveDown(state.id)));
+            let delete = (!state.is_built_in()).then(|| {
+                widget::button::icon(widget::icon::from_name("user-trash-symbolic").size(16))
+                    .padding(spacing.space_xxs)
+                    .on_press(Message::Delete(state.id))
+            });
+
+            let state_id = state.id;
+            section = section.add(
+                widget::column::with_capacity(2)
+                    .spacing(spacing.space_xxs)
+                    .padding([spacing.space_xxs, spacing.space_none])
+                    .push(
+                        widget::row::with_capacity(4)
+                            .align_y(Alignment::Center)
+                            .spacing(spacing.space_xxs)
+                            .push(
+                                widget::text_input(fl!("state-name"), name)
+                                    .on_input(move |name| Message::NameInput(state_id, name))
+                                    .on_submit(move |_| Message::Rename(state_id))
+                                    .width(Length::Fill),
+                            )
+                            .push(move_up)
+                            .push(move_down)
+                            .push_maybe(delete),
+                    )
+                    .push(
+                        widget::row::with_capacity(2)
+                            .align_y(Alignment::Center)
+                            .spacing(spacing.space_s)
+                            .push(
+                                widget::text::caption(fl!("tag-task-count", count = count))
+                                    .width(Length::Fill),
+                            )
+                            .push(completed.label(fl!("state-counts-as-completed"))),
+                    ),
+            );
+        }
+
+        let add = widget::row::with_capacity(2)
+            .align_y(Alignment::Center)
+            .spacing(spacing.space_xxs)
+            .push(
+                widget::text_input(fl!("new-state"), &self.new_state)
+                    .on_input(Message::NewStateInput)
+                    .on_submit(|_| Message::Add)
+                    .width(Length::Fill),
+            )
+            .push(
+                widget::button::standard(fl!("add-state"))
+                    .on_press_maybe((!self.new_state.trim().is_empty()).then_some(Message::Add)),
+            );
+
+        widget::settings::view_column(vec![
+            widget::text::caption(fl!("states-description")).into(),
+            section.into(),
+            add.into(),
+        ])
+        .into()
+    }
+}
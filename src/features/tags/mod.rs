@@ -0,0 +1,4 @@
+pub mod nav;
+pub mod tags;
+
+pub use nav::TagsMarker;
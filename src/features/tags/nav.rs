@@ -0,0 +1,27 @@
+use cosmic::widget;
+
+use crate::{app::AppModel, fl};
+
+pub struct TagsMarker;
+
+impl AppModel {
+    /// Shows the "Tags" entry while some task carries a tag. Like the
+    /// damaged items entry, it stays while it is open.
+    pub fn refresh_tags_nav_item(&mut self) {
+        let shown = self.nav.data::<TagsMarker>(self.tags_entity).is_some();
+        if !shown && !self.tags.is_empty() {
+            let icon = widget::icon::from_name("tag-symbolic").size(16);
+            self.tags_entity = self
+                .nav
+                .insert()
+                .text(fl!("tags"))
+                .icon(icon)
+                .data(TagsMarker)
+                .id();
+            self.reposition_special_items();
+        } else if shown && self.tags.is_empty() && self.nav.active() != self.tags_entity {
+            self.nav.remove(self.tags_entity);
+            self.reposition_special_items();
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use std::collections::HashSet;
+
+use cosmic::{
+    iced::{
+        alignment::{Horizontal, Vertical},
+        Alignment, Length,
+    },
+    theme, widget, Apply, Element,
+};
+
+use uuid::Uuid;
+
+use crate::{
+    features::{lists::list::List, tasks::task::Task},
+    fl,
+    shared::{
+        store::{query::TaskQuery, Store},
+        widgets::{self, collapsible_section},
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct TagEntry {
+    pub task: Task,
+    pub list_id: Uuid,
+    pub list_name: String,
+}
+
+/// Every tag in the store, and the tasks of the one opened.
+pub struct Tags {
+    tags: Vec<(String, usize)>,
+    selected: Option<String>,
+    entries: Vec<TagEntry>,
+    collapsed_sections: HashSet<Uuid>,
+    store: Store,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Load,
+    Select(String),
+    Back,
+    Rename(String),
+    RenameConfirmed(String, String),
+    Open(Uuid),
+    ToggleSection(Uuid),
+}
+
+pub enum Output {
+    OpenTask { task: Task, list_id: Uuid },
+    RenameRequested(String),
+    Renamed,
+}
+
+impl Tags {
+    pub fn new(store: Store) -> Self {
+        Self {
+            tags: Vec::new(),
+            selected: None,
+            entries: Vec::new(),
+            collapsed_sections: HashSet::new(),
+            store,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<Output> {
+        match message {
+            Message::Load => {
+                self.tags = self.store.tags().unwrap_or_else(|e| {
+                    tracing::error!("Failed to load tags: {e}");
+                    vec![]
+                });
+                if self
+                    .selected
+                    .as_ref()
+                    .is_some_and(|selected| !self.tags.iter().any(|(tag, _)| tag == selected))
+                {
+                    self.selected = None;
+                }
+                self.load_entries();
+            }
+            Message::Select(tag) => {
+                self.selected = Some(tag);
+                self.load_entries();
+            }
+            Message::Back => {
+                self.selected = None;
+                self.entries.clear();
+            }
+            Message::Rename(tag) => return Some(Output::RenameRequested(tag)),
+            Message::RenameConfirmed(from, to) => {
+                let to = to.trim().to_string();
+                if to.is_empty() || to == from {
+                    return None;
+                }
+                if let Err(e) = self.store.rename_tag(&from, &to) {
+                    tracing::error!("Failed to rename tag: {e}");
+                    return None;
+                }
+                if self.selected.as_ref() == Some(&from) {
+                    self.selected = Some(to);
+                }
+                self.update(Message::Load);
+                return Some(Output::Renamed);
+            }
+            Message::Open(task_id) => {
+                if let Some(entry) = self.entries.iter().find(|e| e.task.id == task_id) {
+                    return Some(Output::OpenTask {
+                        task: entry.task.clone(),
+                        list_id: entry.list_id,
+                    });
+                }
+            }
+            Message::ToggleSection(list_id) => {
+                if !self.collapsed_sections.remove(&list_id) {
+                    self.collapsed_sections.insert(list_id);
+                }
+            }
+        }
+        None
+    }
+
+    fn load_entries(&mut self) {
+        let Some(tag) = &self.selected else {
+            self.entries.clear();
+            return;
+        };
+
+        let lists: Vec<List> = self.store.lists().load_all().unwrap_or_else(|e| {
+            tracing::error!("Failed to load lists for tags: {e}");
+            vec![]
+        });
+
+        let tasks = self
+            .store
+            .query(&TaskQuery::new().tagged(tag.as_str()))
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to load tagged tasks: {e}");
+                vec![]
+            });
+
+        self.entries = tasks
+            .into_iter()
+            .filter_map(|(list_id, task)| {
+                let list = lists.iter().find(|l| l.id == list_id)?;
+                Some(TagEntry {
+                    task,
+                    list_id,
+                    list_name: list.name.clone(),
+                })
+            })
+            .collect();
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        if self.tags.is_empty() {
+            return self.empty_view();
+        }
+
+        let spacing = theme::active().cosmic().spacing;
+
+        let body: Element<'_, Message> = match &self.selected {
+            Some(_) => widget::column::with_children(self.list_sections())
+                .spacing(spacing.space_xxs)
+                .into(),
+            None => self.tags_section(),
+        };
+
+        let content = widget::column::with_capacity(2)
+            .push(self.header_view())
+            .push(widget::scrollable(body).height(Length::Fill))
+            .spacing(spacing.space_s)
+            .padding([spacing.space_xxs, spacing.space_xxxs]);
+
+        widget::container(content)
+            .height(Length::Fill)
+            .width(Length::Fill)
+            .center_x(Length::Fill)
+            .max_width(800.)
+            .apply(widget::container)
+            .height(Length::Fill)
+            .width(Length::Fill)
+            .center(Length::Fill)
+            .into()
+    }
+
+    fn header_view(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let (leading, title): (Element<'_, Message>, String) = match &self.selected {
+            Some(tag) => (
+                widget::button::icon(widget::icon::from_name("go-previous-symbolic").size(16))
+                    .padding(spacing.space_xxs)
+                    .on_press(Message::Back)
+                    .into(),
+                tag.clone(),
+            ),
+            None => (
+                widget::icon::from_name("tag-symbolic")
+                    .size(spacing.space_m)
+                    .into(),
+                fl!("tags"),
+            ),
+        };
+
+        let rename = self.selected.as_ref().map(|tag| {
+            widget::button::icon(widget::icon::from_name("edit-symbolic").size(16))
+                .padding(spacing.space_xxs)
+                .on_press(Message::Rename(tag.clone()))
+        });
+
+        widget::row::with_capacity(3)
+            .align_y(Alignment::Center)
+            .spacing(spacing.space_s)
+            .padding([spacing.space_none, spacing.space_xxs])
+            .push(leading)
+            .push(widget::text::title4(title).width(Length::Fill))
+            .push_maybe(rename)
+            .into()
+    }
+
+    fn tags_section(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let mut list = widget::list_column();
+        for (tag, count) in &self.tags {
+            let rename = widget::button::icon(widget::icon::from_name("edit-symbolic").size(16))
+                .padding(spacing.space_xxs)
+                .on_press(Message::Rename(tag.clone()));
+            let open = widget::button::icon(widget::icon::from_name("go-next-symbolic").size(16))
+                .padding(spacing.space_xxs)
+                .on_press(Message::Select(tag.clone()));
+
+            list = list.add(
+                widget::row::with_capacity(4)
+                    .align_y(Alignment::Center)
+                    .spacing(spacing.space_s)
+                    .padding([spacing.space_xxs, spacing.space_xs])
+                    .push(
+                        widget::container(widgets::tag_chip(tag, None, &spacing))
+                            .width(Length::Fill),
+                    )
+                    .push(widget::text::caption(fl!("tag-task-count", count = count)))
+                    .push(rename)
+                    .push(open),
+            );
+        }
+
+        widget::container(list)
+            .class(cosmic::style::Container::List)
+            .into()
+    }
+
+    fn list_sections(&self) -> Vec<Element<'_, Message>> {
+        let mut list_ids: Vec<Uuid> = Vec::new();
+        for e in &self.entries {
+            if !list_ids.contains(&e.list_id) {
+                list_ids.push(e.list_id);
+            }
+        }
+
+        list_ids
+            .into_iter()
+            .map(|list_id| {
+                let entries: Vec<&TagEntry> = self
+                    .entries
+                    .iter()
+                    .filter(|e| e.list_id == list_id)
+                    .collect();
+                let name = entries
+                    .first()
+                    .map(|e| e.list_name.clone())
+                    .unwrap_or_else(|| fl!("unknown-list"));
+
+                self.list_section(list_id, name, entries)
+            })
+            .collect()
+    }
+
+    fn list_section<'a>(
+        &'a self,
+        list_id: Uuid,
+        name: String,
+        entries: Vec<&'a TagEntry>,
+    ) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let collapsed = self.collapsed_sections.contains(&list_id);
+        let count = entries.len();
+
+        let header = collapsible_section::section_header(
+            name,
+            None,
+            count,
+            collapsed,
+            Vec::new(),
+            Message::ToggleSection(list_id),
+            &spacing,
+        );
+
+        let rows = entries
+            .into_iter()
+            .map(|entry| self.entry_row(entry))
+            .collect();
+
+        collapsible_section::section(header, rows, collapsed)
+    }
+
+    fn entry_row<'a>(&'a self, entry: &'a TagEntry) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let title = widget::text::body(entry.task.title.as_str()).width(Length::Fill);
+
+        let open_button =
+            widget::button::icon(widget::icon::from_name("go-next-symbolic").size(16))
+                .padding(spacing.space_xxs)
+                .on_press(Message::Open(entry.task.id));
+
+        let row = widget::row::with_capacity(2)
+            .align_y(Alignment::Center)
+            .spacing(spacing.space_s)
+            .padding([spacing.space_xxs, spacing.space_xxs])
+            .push(title)
+            .push(open_button);
+
+        collapsible_section::row_item(row.into())
+    }
+
+    fn empty_view(&self) -> Element<'_, Message> {
+        widget::container(
+            widget::column::with_children(vec![
+                widget::icon::from_name("tag-symbolic").size(56).into(),
+                widget::text::title1(fl!("no-tags")).into(),
+                widget::text(fl!("no-tags-suggestion")).into(),
+            ])
+            .spacing(10)
+            .align_x(Alignment::Center),
+        )
+        .align_y(Vertical::Center)
+        .align_x(Horizontal::Center)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into()
+    }
+}
@@ -1,4 +1,8 @@
-use jiff::civil::Date;
+use jiff::{
+    civil::{Date, Time, Weekday},
+    tz::TimeZone,
+    Timestamp,
+};
 
 use cosmic::{
     iced::{Alignment, Length},
@@ -14,9 +18,20 @@ use slotmap::DefaultKey;
 use uuid::Uuid;
 
 use crate::{
-    features::tasks::task::{self as model, Priority},
+    features::tasks::{
+        due::DueDate,
+        recurrence::{Frequency, Recurrence},
+        state::TaskState,
+        task::{self as model, Priority},
+    },
     fl,
-    shared::store::Store,
+    shared::{
+        store::{
+            history::{Field, Revision},
+            Store,
+        },
+        widgets,
+    },
 };
 
 pub struct Details {
@@ -26,6 +41,16 @@ pub struct Details {
     pub priority_model: segmented_button::Model<segmented_button::SingleSelect>,
     pub text_editor_content: widget::text_editor::Content,
     pub store: Store,
+    pub revisions: Vec<Revision>,
+    /// Revisions ticked for comparison, at most two.
+    pub compared: Vec<Timestamp>,
+    pub states: Vec<TaskState>,
+    /// The recurrence as an RRULE string, as typed so far.
+    pub rule_input: String,
+    pub rule_invalid: bool,
+    /// Every tag in the store, offered while typing a new one.
+    pub all_tags: Vec<String>,
+    pub tag_input: String,
 }
 
 #[derive(Debug, Clone)]
@@ -34,19 +59,41 @@ pub enum Message {
     SetTitle(String),
     Editor(text_editor::Action),
     Favorite(bool),
+    Today(bool),
     PriorityActivate(Entity),
+    SetState(usize),
     Delete,
     OpenCalendarDialog,
     SetDueDate(Date),
+    ClearDueDate,
+    OpenDueTimeDialog,
+    SetDueTime(Time),
+    ClearDueTime,
+    PinTimeZone(bool),
     OpenReminderDialog,
     SetReminder(jiff::Timestamp),
     ClearReminder,
+    SetFrequency(usize),
+    ToggleRecurrence(Weekday),
+    FromCompletion(bool),
+    RuleInput(String),
+    RuleSubmit,
+    TagInput(String),
+    AddTag(String),
+    RemoveTag(String),
+    CompareRevision(Timestamp),
+    RestoreRevision(Timestamp),
 }
 
 pub enum Output {
     OpenCalendarDialog,
+    OpenDueTimeDialog,
     OpenReminderDialog,
     RefreshTask(model::Task),
+    /// Like [`Output::RefreshTask`], and the task's tags changed too.
+    TagsChanged(model::Task),
+    /// Tasks other than this one were added to the list.
+    ListChanged(Uuid),
     DeleteTask(DefaultKey),
 }
 
@@ -77,22 +124,68 @@ impl Details {
             priority_model,
             text_editor_content: widget::text_editor::Content::new(),
             store: storage,
+            revisions: Vec::new(),
+            compared: Vec::new(),
+            states: Vec::new(),
+            rule_input: String::new(),
+            rule_invalid: false,
+            all_tags: Vec::new(),
+            tag_input: String::new(),
+        }
+    }
+
+    fn show_task(&mut self, task: model::Task) {
+        let entity = self.priority_model.entity_at(task.priority as u16);
+        if let Some(entity) = entity {
+            self.priority_model.activate(entity);
+        }
+        self.text_editor_content = widget::text_editor::Content::with_text(&task.notes);
+        self.task = task;
+        self.show_recurrence();
+    }
+
+    fn show_recurrence(&mut self) {
+        self.rule_input = self
+            .task
+            .recurrence
+            .as_ref()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        self.rule_invalid = false;
+    }
+
+    fn load_tags(&mut self) {
+        match self.store.tags() {
+            Ok(tags) => self.all_tags = tags.into_iter().map(|(tag, _)| tag).collect(),
+            Err(e) => tracing::error!("Failed to load tags: {}", e),
+        }
+    }
+
+    fn load_history(&mut self) {
+        match self.store.history().load(self.task.id) {
+            Ok(revisions) => self.revisions = revisions,
+            Err(e) => tracing::error!("Failed to load task history: {}", e),
+        }
+        // Saves made within an edit session replace the newest revision, so
+        // a ticked one may be gone.
+        self.compared
+            .retain(|at| self.revisions.iter().any(|revision| revision.at == *at));
+        match self.store.states().load_all() {
+            Ok(states) => self.states = states,
+            Err(e) => tracing::error!("Failed to load task states: {}", e),
         }
     }
 
     pub fn update(&mut self, message: Message) -> Option<Output> {
+        let (task_id, tags) = (self.task.id, self.task.tags.clone());
         match message {
             Message::SetTask(key, task, list_id) => {
                 self.task_key = key;
-                self.task = task.clone();
                 self.selected_list = Some(list_id);
-
-                let entity = self.priority_model.entity_at(task.priority as u16);
-                if let Some(entity) = entity {
-                    self.priority_model.activate(entity);
-                }
-                self.task = task.clone();
-                self.text_editor_content = widget::text_editor::Content::with_text(&task.notes);
+                self.show_task(task);
+                self.compared.clear();
+                self.tag_input.clear();
+                self.load_tags();
             }
             Message::Editor(action) => {
                 self.text_editor_content.perform(action);
@@ -104,6 +197,9 @@ impl Details {
             Message::Favorite(favorite) => {
                 self.task.favorite = favorite;
             }
+            Message::Today(today) => {
+                self.task.today = today;
+            }
             Message::PriorityActivate(entity) => {
                 self.priority_model.activate(entity);
                 let priority = self.priority_model.data::<Priority>(entity);
@@ -111,6 +207,24 @@ impl Details {
                     self.task.priority = *priority;
                 }
             }
+            Message::SetState(index) => {
+                let list_id = self.selected_list?;
+                let state = self.sorted_states().get(index).copied()?.clone();
+                match self.store.tasks(list_id).set_state(self.task.id, &state) {
+                    Ok((task, next)) => {
+                        self.show_task(task);
+                        self.load_history();
+                        return Some(match next {
+                            Some(_) => Output::ListChanged(list_id),
+                            None => Output::RefreshTask(self.task.clone()),
+                        });
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to set task state: {}", e);
+                        return None;
+                    }
+                }
+            }
             Message::Delete => {
                 return Some(Output::DeleteTask(self.task_key));
             }
@@ -127,21 +241,153 @@ impl Details {
                 self.task.reminder_date = None;
             }
             Message::SetDueDate(date) => {
-                self.task.due_date = Some(date);
+                self.task.due_date = Some(match self.task.due_date.take() {
+                    Some(due) => DueDate { date, ..due },
+                    None => DueDate::on(date),
+                });
+            }
+            Message::ClearDueDate => {
+                self.task.due_date = None;
+            }
+            Message::OpenDueTimeDialog => {
+                return Some(Output::OpenDueTimeDialog);
+            }
+            Message::SetDueTime(time) => {
+                if let Some(due) = &mut self.task.due_date {
+                    due.time = Some(time);
+                }
+            }
+            Message::ClearDueTime => {
+                if let Some(due) = &mut self.task.due_date {
+                    due.time = None;
+                    due.time_zone = None;
+                }
+            }
+            Message::PinTimeZone(pin) => {
+                if let Some(due) = self.task.due_date.take() {
+                    self.task.due_date = Some(pin_time_zone(due, pin));
+                }
+            }
+            Message::SetFrequency(index) => {
+                self.task.recurrence = FREQUENCIES.get(index.wrapping_sub(1)).map(|frequency| {
+                    match &self.task.recurrence {
+                        Some(rule) => Recurrence {
+                            frequency: *frequency,
+                            ..rule.clone()
+                        },
+                        None => Recurrence::new(*frequency),
+                    }
+                });
+                self.show_recurrence();
+            }
+            Message::ToggleRecurrence(weekday) => {
+                self.task
+                    .recurrence
+                    .get_or_insert_with(|| Recurrence::new(Frequency::Weekly))
+                    .toggle_weekday(weekday);
+                self.show_recurrence();
+            }
+            Message::FromCompletion(from_completion) => {
+                if let Some(rule) = &mut self.task.recurrence {
+                    rule.from_completion = from_completion;
+                }
+                self.show_recurrence();
+            }
+            Message::RuleInput(input) => {
+                self.rule_input = input;
+                self.rule_invalid = false;
+                return None;
+            }
+            Message::RuleSubmit => {
+                let input = self.rule_input.trim();
+                if input.is_empty() {
+                    self.task.recurrence = None;
+                } else {
+                    match input.parse::<Recurrence>() {
+                        Ok(rule) => self.task.recurrence = Some(rule),
+                        Err(e) => {
+                            tracing::warn!("{}", e);
+                            self.rule_invalid = true;
+                            return None;
+                        }
+                    }
+                }
+                self.show_recurrence();
+            }
+            Message::TagInput(input) => {
+                self.tag_input = input;
+                return None;
+            }
+            Message::AddTag(tag) => {
+                let tag = tag.trim();
+                // Typing an existing tag in another case reuses it rather
+                // than starting a near-duplicate.
+                let tag = self
+                    .all_tags
+                    .iter()
+                    .find(|existing| existing.to_lowercase() == tag.to_lowercase())
+                    .map_or(tag, String::as_str)
+                    .to_string();
+                self.tag_input.clear();
+                if tag.is_empty() || self.task.tags.contains(&tag) {
+                    return None;
+                }
+                self.task.tags.push(tag);
+            }
+            Message::RemoveTag(tag) => {
+                self.task.tags.retain(|t| *t != tag);
+            }
+            Message::CompareRevision(at) => {
+                if let Some(index) = self.compared.iter().position(|compared| *compared == at) {
+                    self.compared.remove(index);
+                } else {
+                    if self.compared.len() == 2 {
+                        self.compared.remove(0);
+                    }
+                    self.compared.push(at);
+                }
+                return None;
+            }
+            Message::RestoreRevision(at) => {
+                let list_id = self.selected_list?;
+                match self.store.history().restore(list_id, self.task.id, at) {
+                    Ok(task) => {
+                        self.show_task(task);
+                        self.compared.clear();
+                        self.load_history();
+                        return Some(self.refreshed(task_id, &tags));
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to restore task revision: {}", e);
+                        return None;
+                    }
+                }
             }
         }
 
         if let Some(list_id) = self.selected_list {
-            if let Err(e) = self
+            match self
                 .store
                 .tasks(list_id)
                 .update(self.task.id, |t| *t = self.task.clone())
             {
-                tracing::error!("Failed to update task: {}", e);
+                Ok(task) => self.task.updated_at = task.updated_at,
+                Err(e) => tracing::error!("Failed to update task: {}", e),
             }
+            self.load_history();
         }
 
-        return Some(Output::RefreshTask(self.task.clone()));
+        return Some(self.refreshed(task_id, &tags));
+    }
+
+    /// What to tell the app after a save, given the task and its tags before
+    /// the message. Switching tasks leaves every tag where it was.
+    fn refreshed(&self, task_id: Uuid, tags: &[String]) -> Output {
+        if self.task.id == task_id && self.task.tags != tags {
+            Output::TagsChanged(self.task.clone())
+        } else {
+            Output::RefreshTask(self.task.clone())
+        }
     }
 
     pub fn view(&self) -> Element<'_, Message> {
@@ -169,6 +415,22 @@ impl Details {
                     widget::settings::item::builder(fl!("favorite"))
                         .control(widget::checkbox(self.task.favorite).on_toggle(Message::Favorite)),
                 )
+                .add(
+                    widget::settings::item::builder(fl!("my-day"))
+                        .control(widget::checkbox(self.task.today).on_toggle(Message::Today)),
+                )
+                .add({
+                    let states = self.sorted_states();
+                    let current = self.task.effective_state_id();
+                    widget::settings::item::builder(fl!("state")).control(widget::dropdown(
+                        states
+                            .iter()
+                            .map(|state| state.name.clone())
+                            .collect::<Vec<_>>(),
+                        states.iter().position(|state| state.id == current),
+                        Message::SetState,
+                    ))
+                })
                 .add(
                     widget::settings::item::builder(fl!("priority")).control(
                         widget::segmented_control::horizontal(&self.priority_model)
@@ -177,20 +439,57 @@ impl Details {
                             .on_activate(Message::PriorityActivate),
                     ),
                 )
-                .add(
-                    widget::settings::item::builder(fl!("due-date")).control(
-                        widget::button::text(if self.task.due_date.is_some() {
-                            self.task
-                                .due_date
-                                .as_ref()
-                                .unwrap()
-                                .strftime("%m-%d-%Y")
-                                .to_string()
-                        } else {
-                            fl!("select-date")
-                        })
-                        .on_press(Message::OpenCalendarDialog),
-                    ),
+                .add({
+                    let due_label = match &self.task.due_date {
+                        Some(due) => due.date.strftime("%m-%d-%Y").to_string(),
+                        None => fl!("select-date"),
+                    };
+                    let mut due_row =
+                        widget::row::with_children(vec![widget::button::text(due_label)
+                            .on_press(Message::OpenCalendarDialog)
+                            .into()]);
+                    if self.task.due_date.is_some() {
+                        due_row = due_row.push(
+                            widget::button::icon(
+                                widget::icon::from_name("edit-clear-symbolic").size(14),
+                            )
+                            .on_press(Message::ClearDueDate),
+                        );
+                    }
+                    widget::settings::item::builder(fl!("due-date")).control(due_row)
+                })
+                .add_maybe(self.task.due_date.as_ref().map(|due| {
+                    let time_label = match due.time {
+                        Some(time) => time.strftime("%H:%M").to_string(),
+                        None => fl!("set-due-time"),
+                    };
+                    let mut time_row =
+                        widget::row::with_children(vec![widget::button::text(time_label)
+                            .on_press(Message::OpenDueTimeDialog)
+                            .into()]);
+                    if due.time.is_some() {
+                        time_row = time_row.push(
+                            widget::button::icon(
+                                widget::icon::from_name("edit-clear-symbolic").size(14),
+                            )
+                            .on_press(Message::ClearDueTime),
+                        );
+                    }
+                    widget::settings::item::builder(fl!("due-time")).control(time_row)
+                }))
+                .add_maybe(
+                    self.task
+                        .due_date
+                        .as_ref()
+                        .and_then(|due| due.time.map(|time| (due, time)))
+                        .map(|(due, time)| {
+                            widget::settings::item::builder(fl!("due-time-zone"))
+                                .description(time_zone_description(due, time))
+                                .control(
+                                    widget::toggler(due.time_zone.is_some())
+                                        .on_toggle(Message::PinTimeZone),
+                                )
+                        }),
                 )
                 .add({
                     let reminder_label = if let Some(ts) = &self.task.reminder_date {
@@ -212,6 +511,33 @@ impl Details {
                     }
                     widget::settings::item::builder(fl!("reminder")).control(reminder_row)
                 })
+                .add(
+                    widget::column::with_children(vec![
+                        widget::text::body(fl!("repeat")).into(),
+                        self.recurrence_view(),
+                        widget::text_input(fl!("repeat-rule"), &self.rule_input)
+                            .on_input(Message::RuleInput)
+                            .on_submit(|_| Message::RuleSubmit)
+                            .size(13)
+                            .into(),
+                        widget::text::caption(if self.rule_invalid {
+                            fl!("repeat-invalid")
+                        } else if self.task.recurrence.is_some() {
+                            fl!("repeat-description")
+                        } else {
+                            fl!("repeat-never")
+                        })
+                        .into(),
+                    ])
+                    .spacing(spacing.space_xxs)
+                    .padding([
+                        spacing.space_s,
+                        spacing.space_none,
+                        spacing.space_s,
+                        spacing.space_none,
+                    ]),
+                )
+                .add(self.tags_view())
                 .add(
                     widget::column::with_children(vec![
                         widget::text::body(fl!("notes")).into(),
@@ -238,11 +564,16 @@ impl Details {
                     widget::settings::item::builder(fl!("created-at"))
                         .control(widget::text::caption(self.task.creation_date_local())),
                 )
+                .add(
+                    widget::settings::item::builder(fl!("updated-at"))
+                        .control(widget::text::caption(time_ago(&self.task.updated_at))),
+                )
                 .add_maybe(self.task.completion_date_local().map(|completion_date| {
                     widget::settings::item::builder(fl!("completed-at"))
                         .control(widget::text::caption(completion_date))
                 }))
                 .into(),
+            self.history_view(),
             widget::button::destructive(fl!("delete"))
                 .on_press(Message::Delete)
                 .into(),
@@ -255,4 +586,303 @@ impl Details {
         ])
         .into()
     }
+
+    fn sorted_states(&self) -> Vec<&TaskState> {
+        let mut states: Vec<&TaskState> = self.states.iter().collect();
+        states.sort_by_key(|state| state.position);
+        states
+    }
+
+    fn recurrence_view(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let frequency = self.task.recurrence.as_ref().and_then(|rule| {
+            FREQUENCIES
+                .iter()
+                .position(|frequency| *frequency == rule.frequency)
+                .map(|index| index + 1)
+        });
+        let mut column = widget::column::with_capacity(3)
+            .spacing(spacing.space_xxs)
+            .push(widget::dropdown(
+                vec![
+                    fl!("repeat-never"),
+                    fl!("repeat-daily"),
+                    fl!("repeat-weekly"),
+                    fl!("repeat-monthly"),
+                    fl!("repeat-yearly"),
+                ],
+                Some(frequency.unwrap_or(0)),
+                Message::SetFrequency,
+            ));
+        let Some(rule) = &self.task.recurrence else {
+            return column.into();
+        };
+
+        if rule.frequency == Frequency::Weekly {
+            let days = [
+                (Weekday::Monday, fl!("weekday-monday")),
+                (Weekday::Tuesday, fl!("weekday-tuesday")),
+                (Weekday::Wednesday, fl!("weekday-wednesday")),
+                (Weekday::Thursday, fl!("weekday-thursday")),
+                (Weekday::Friday, fl!("weekday-friday")),
+                (Weekday::Saturday, fl!("weekday-saturday")),
+                (Weekday::Sunday, fl!("weekday-sunday")),
+            ];
+            column = column.push(
+                widget::row::with_children(
+                    days.into_iter()
+                        .map(|(weekday, label)| {
+                            widget::button::text(label)
+                                .class(if rule.includes_weekday(weekday) {
+                                    theme::Button::Suggested
+                                } else {
+                                    theme::Button::Standard
+                                })
+                                .on_press(Message::ToggleRecurrence(weekday))
+                                .into()
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .spacing(spacing.space_xxxs),
+            );
+        }
+        column
+            .push(
+                widget::settings::item::builder(fl!("repeat-from-completion")).control(
+                    widget::toggler(rule.from_completion).on_toggle(Message::FromCompletion),
+                ),
+            )
+            .into()
+    }
+
+    fn tags_view(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let chips: Vec<Element<'_, Message>> = self
+            .task
+            .tags
+            .iter()
+            .map(|tag| widgets::tag_chip(tag, Some(Message::RemoveTag(tag.clone())), &spacing))
+            .collect();
+
+        let input = self.tag_input.trim().to_lowercase();
+        let suggestions: Vec<Element<'_, Message>> = if input.is_empty() {
+            Vec::new()
+        } else {
+            self.all_tags
+                .iter()
+                .filter(|tag| !self.task.tags.contains(tag))
+                .filter(|tag| tag.to_lowercase().contains(&input))
+                .take(5)
+                .map(|tag| {
+                    widget::button::text(tag.as_str())
+                        .on_press(Message::AddTag(tag.clone()))
+                        .into()
+                })
+                .collect()
+        };
+
+        let mut column = widget::column::with_capacity(4)
+            .spacing(spacing.space_xxs)
+            .padding([
+                spacing.space_s,
+                spacing.space_none,
+                spacing.space_s,
+                spacing.space_none,
+            ])
+            .push(widget::text::body(fl!("tags")));
+        if !chips.is_empty() {
+            column = column.push(
+                widget::flex_row(chips)
+                    .row_spacing(spacing.space_xxxs)
+                    .column_spacing(spacing.space_xxxs),
+            );
+        }
+        column = column.push(
+            widget::text_input(fl!("add-tag"), &self.tag_input)
+                .on_input(Message::TagInput)
+                .on_submit(Message::AddTag)
+                .size(13),
+        );
+        if !suggestions.is_empty() {
+            column = column.push(widget::flex_row(suggestions).column_spacing(spacing.space_xxxs));
+        }
+        column.into()
+    }
+
+    fn history_view(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let mut section = widget::settings::section().title(fl!("history"));
+        if self.revisions.is_empty() {
+            section = section.add(widget::text::caption(fl!("no-history")));
+        }
+        for (index, revision) in self.revisions.iter().enumerate().rev() {
+            let at = revision.at;
+            let description = match index.checked_sub(1) {
+                Some(previous) => self.revisions[previous]
+                    .diff(revision)
+                    .into_iter()
+                    .map(field_label)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                None => fl!("history-first"),
+            };
+            let mut controls = widget::row::with_capacity(2)
+                .align_y(Alignment::Center)
+                .spacing(spacing.space_xxs)
+                .push(
+                    widget::checkbox(self.compared.contains(&at))
+                        .on_toggle(move |_| Message::CompareRevision(at)),
+                );
+            // The newest revision is what the task holds right now.
+            if index + 1 < self.revisions.len() {
+                controls = controls.push(
+                    widget::button::text(fl!("restore")).on_press(Message::RestoreRevision(at)),
+                );
+            }
+            section = section.add(
+                widget::settings::item::builder(model::Task::format_timestamp(&at))
+                    .description(description)
+                    .control(controls),
+            );
+        }
+
+        let mut column = widget::column::with_capacity(2)
+            .spacing(spacing.space_m)
+            .push(section);
+        if let Some((older, newer)) = self.compared_revisions() {
+            let mut comparison = widget::settings::section().title(format!(
+                "{} → {}",
+                model::Task::format_timestamp(&older.at),
+                model::Task::format_timestamp(&newer.at)
+            ));
+            let fields = older.diff(newer);
+            if fields.is_empty() {
+                comparison = comparison.add(widget::text::caption(fl!("no-revision-changes")));
+            }
+            for field in fields {
+                comparison = comparison.add(
+                    widget::settings::item::builder(field_label(field)).description(format!(
+                        "{} → {}",
+                        self.field_value(older, field),
+                        self.field_value(newer, field)
+                    )),
+                );
+            }
+            column = column.push(comparison);
+        }
+        column.into()
+    }
+
+    /// The two revisions to compare, older first. A single ticked revision
+    /// is compared with the newest one.
+    fn compared_revisions(&self) -> Option<(&Revision, &Revision)> {
+        let mut compared: Vec<&Revision> = self
+            .revisions
+            .iter()
+            .filter(|revision| self.compared.contains(&revision.at))
+            .collect();
+        if compared.len() == 1 {
+            compared.extend(self.revisions.last());
+        }
+        match compared[..] {
+            [older, newer] if older.at != newer.at => Some((older, newer)),
+            _ => None,
+        }
+    }
+
+    fn field_value(&self, revision: &Revision, field: Field) -> String {
+        let value = match field {
+            Field::Title => Some(revision.title.clone()),
+            Field::Notes => Some(revision.notes.clone()).filter(|notes| !notes.is_empty()),
+            Field::Priority => Some(match revision.priority {
+                Priority::Low => fl!("priority-low"),
+                Priority::Normal => fl!("priority-normal"),
+                Priority::High => fl!("priority-high"),
+            }),
+            Field::State => revision.state_id.map(|state_id| {
+                self.states
+                    .iter()
+                    .find(|state| state.id == state_id)
+                    .map_or_else(|| fl!("unknown-state"), |state| state.name.clone())
+            }),
+            Field::Completed => revision
+                .completion_date
+                .as_ref()
+                .map(model::Task::format_timestamp),
+            Field::DueDate => revision.due_date.as_ref().map(ToString::to_string),
+            Field::Reminder => revision
+                .reminder_date
+                .as_ref()
+                .map(model::Task::format_timestamp),
+        };
+        value.unwrap_or_else(|| fl!("history-none"))
+    }
+}
+
+/// The frequencies offered by the dropdown, after "does not repeat".
+const FREQUENCIES: [Frequency; 4] = [
+    Frequency::Daily,
+    Frequency::Weekly,
+    Frequency::Monthly,
+    Frequency::Yearly,
+];
+
+/// Where the time of `due` is read, with the local time when pinned to a
+/// zone other than the system's.
+fn time_zone_description(due: &DueDate, time: Time) -> String {
+    let Some(time_zone) = &due.time_zone else {
+        return fl!("due-time-floating");
+    };
+    match due.local() {
+        Some(local) if local.datetime() != due.date.to_datetime(time) => fl!(
+            "due-time-zone-local",
+            zone = time_zone.as_str(),
+            local = local.strftime("%H:%M").to_string()
+        ),
+        _ => time_zone.clone(),
+    }
+}
+
+/// Pins the time of `due` to the system zone, or lets it float, keeping the
+/// instant it falls on.
+fn pin_time_zone(due: DueDate, pin: bool) -> DueDate {
+    if pin {
+        let Some(name) = TimeZone::system().iana_name().map(str::to_string) else {
+            tracing::warn!("The system time zone has no name to pin due times to");
+            return due;
+        };
+        due.in_zone(Some(name))
+    } else {
+        match due.local() {
+            Some(local) => DueDate::on(local.date()).at(Some(local.time())),
+            None => due.in_zone(None),
+        }
+    }
+}
+
+fn field_label(field: Field) -> String {
+    match field {
+        Field::Title => fl!("title"),
+        Field::Notes => fl!("notes"),
+        Field::Priority => fl!("priority"),
+        Field::State => fl!("state"),
+        Field::Completed => fl!("completed-at"),
+        Field::DueDate => fl!("due-date"),
+        Field::Reminder => fl!("reminder"),
+    }
+}
+
+/// How long ago `ts` was, falling back to the date after a week.
+fn time_ago(ts: &Timestamp) -> String {
+    let minutes = Timestamp::now().duration_since(*ts).as_mins();
+    match minutes {
+        ..1 => fl!("just-now"),
+        1..60 => fl!("minutes-ago", minutes = minutes),
+        60..1440 => fl!("hours-ago", hours = minutes / 60),
+        1440..10080 => fl!("days-ago", days = minutes / 1440),
+        _ => model::Task::format_timestamp(ts),
+    }
 }
@@ -0,0 +1,161 @@
+//! When a task is due.
+//!
+//! A [`DueDate`] is a calendar day, optionally with a time on it. The time
+//! is either pinned to a time zone, for deadlines set somewhere else ("by
+//! 15:00 New York time"), or floating: read in whatever zone the system is
+//! in, so a 09:00 deadline stays at 09:00 after travelling.
+//!
+//! Only the civil date and time are stored. The instant a task is due by is
+//! worked out with jiff whenever it is needed, so daylight-saving changes
+//! and moves between zones never leave a stale offset behind.
+
+use std::fmt;
+
+use jiff::civil::{Date, Time};
+use jiff::tz::TimeZone;
+use jiff::{Timestamp, Zoned};
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DueDate {
+    pub date: Date,
+    #[serde(default)]
+    pub time: Option<Time>,
+    /// IANA name of the zone `time` is in. Floating when unset.
+    #[serde(default)]
+    pub time_zone: Option<String>,
+}
+
+impl DueDate {
+    /// Due at some point on `date`, with no particular time.
+    pub fn on(date: Date) -> Self {
+        Self {
+            date,
+            time: None,
+            time_zone: None,
+        }
+    }
+
+    pub fn at(mut self, time: Option<Time>) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Pins the time to the zone named `time_zone`, or lets it float.
+    pub fn in_zone(mut self, time_zone: Option<String>) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
+    /// The zone the time is read in: the pinned one, or the system's when
+    /// floating or when this system does not know the pinned zone.
+    pub fn zone(&self) -> TimeZone {
+        self.time_zone
+            .as_deref()
+            .and_then(|name| {
+                TimeZone::get(name)
+                    .inspect_err(|err| tracing::warn!("Unknown time zone {name}: {err}"))
+                    .ok()
+            })
+            .unwrap_or_else(TimeZone::system)
+    }
+
+    /// The instant the task is due by. Without a time, that is the end of
+    /// the day where the user is.
+    ///
+    /// A time skipped by a daylight-saving change resolves to the same
+    /// wall-clock time after it, and a repeated one to its first occurrence.
+    pub fn deadline(&self) -> Timestamp {
+        let zoned = match self.time {
+            Some(time) => self.date.to_datetime(time).to_zoned(self.zone()),
+            None => self
+                .date
+                .tomorrow()
+                .and_then(|date| date.to_zoned(TimeZone::system())),
+        };
+        zoned.map_or(Timestamp::MAX, |zoned| zoned.timestamp())
+    }
+
+    /// The deadline in the system zone, when there is a time.
+    pub fn local(&self) -> Option<Zoned> {
+        self.time
+            .map(|_| self.deadline().to_zoned(TimeZone::system()))
+    }
+
+    /// The day the task is due on where the user is. Differs from `date`
+    /// only for a time pinned to another zone.
+    pub fn local_date(&self) -> Date {
+        self.local().map_or(self.date, |local| local.date())
+    }
+
+    pub fn is_overdue(&self, now: Timestamp) -> bool {
+        now >= self.deadline()
+    }
+}
+
+/// The date and time as set, with the zone abbreviation when pinned.
+impl fmt::Display for DueDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Some(time) = self.time else {
+            return write!(f, "{}", self.date.strftime("%m-%d-%Y"));
+        };
+        let datetime = self.date.to_datetime(time);
+        match self.time_zone {
+            Some(_) => match datetime.to_zoned(self.zone()) {
+                Ok(zoned) => write!(f, "{}", zoned.strftime("%m-%d-%Y %H:%M %Z")),
+                Err(_) => write!(f, "{}", datetime.strftime("%m-%d-%Y %H:%M")),
+            },
+            None => write!(f, "{}", datetime.strftime("%m-%d-%Y %H:%M")),
+        }
+    }
+}
+
+/// Reads the due date of a [`Revision`](crate::shared::store::history::Revision),
+/// which keeps the bare date it was recorded with if that was before due
+/// dates had times. Tasks themselves are upgraded and always hold a
+/// [`DueDate`].
+pub fn deserialize<'de, D>(deserializer: D) -> std::result::Result<Option<DueDate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct StoredDueDate;
+
+    impl<'de> Visitor<'de> for StoredDueDate {
+        type Value = Option<DueDate>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a due date or a bare date")
+        }
+
+        fn visit_none<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> std::result::Result<Self::Value, D::Error> {
+            deserializer.deserialize_any(self)
+        }
+
+        fn visit_str<E: de::Error>(self, date: &str) -> std::result::Result<Self::Value, E> {
+            date.parse::<Date>()
+                .map(|date| Some(DueDate::on(date)))
+                .map_err(E::custom)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(
+            self,
+            map: A,
+        ) -> std::result::Result<Self::Value, A::Error> {
+            DueDate::deserialize(de::value::MapAccessDeserializer::new(map)).map(Some)
+        }
+    }
+
+    deserializer.deserialize_any(StoredDueDate)
+}
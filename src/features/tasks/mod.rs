@@ -1,3 +1,5 @@
 pub mod details;
+pub mod due;
+pub mod recurrence;
 pub mod state;
 pub mod task;
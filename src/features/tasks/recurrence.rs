@@ -0,0 +1,550 @@
+//! When a task repeats.
+//!
+//! [`Recurrence`] covers the part of the iCalendar RRULE (RFC 5545) that
+//! makes sense for tasks: a daily, weekly, monthly or yearly frequency with
+//! an interval, narrowed down by weekday, day of month and month, and ending
+//! after a number of occurrences or on a date. It is stored as its RRULE
+//! string, so rules can be read and written by other iCalendar software.
+//!
+//! Two things differ from RFC 5545. Weeks always start on Monday, so `WKST`
+//! is accepted but ignored. And the non-standard `X-FROM=COMPLETION` part
+//! counts the interval from the day a task is completed rather than from its
+//! due date, for chores like "3 days after last time".
+
+use std::fmt;
+use std::str::FromStr;
+
+use jiff::civil::{Date, Weekday};
+use jiff::{Span, ToSpan};
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::{Error, Result};
+
+/// Periods searched for the next occurrence before giving up on a rule that
+/// cannot match, such as the 31st of every February.
+const MAX_PERIODS: i64 = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    /// Repeats every this many days, weeks, months or years.
+    pub interval: u32,
+    pub by_day: Vec<ByDay>,
+    /// Days of the month, counted from the end when negative.
+    pub by_month_day: Vec<i8>,
+    pub by_month: Vec<i8>,
+    pub end: End,
+    pub from_completion: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A weekday, optionally the nth one of the month, counted from the end
+/// when negative: `2TU` is the second Tuesday, `-1FR` the last Friday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByDay {
+    pub nth: Option<i8>,
+    pub weekday: Weekday,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum End {
+    Never,
+    /// Occurrences left, this one included. Each roll-forward counts one
+    /// down, so the series stops once the last one is completed.
+    Count(u32),
+    Until(Date),
+}
+
+impl Recurrence {
+    pub fn new(frequency: Frequency) -> Self {
+        Self {
+            frequency,
+            interval: 1,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            end: End::Never,
+            from_completion: false,
+        }
+    }
+
+    /// Every week on `weekdays`.
+    pub fn weekly(weekdays: impl IntoIterator<Item = Weekday>) -> Self {
+        Self {
+            by_day: weekdays
+                .into_iter()
+                .map(|weekday| ByDay { nth: None, weekday })
+                .collect(),
+            ..Self::new(Frequency::Weekly)
+        }
+    }
+
+    pub fn includes_weekday(&self, weekday: Weekday) -> bool {
+        self.by_day.iter().any(|day| day.weekday == weekday)
+    }
+
+    pub fn toggle_weekday(&mut self, weekday: Weekday) {
+        if self.includes_weekday(weekday) {
+            self.by_day.retain(|day| day.weekday != weekday);
+        } else {
+            self.by_day.push(ByDay { nth: None, weekday });
+            self.by_day
+                .sort_by_key(|day| day.weekday.to_monday_zero_offset());
+        }
+    }
+
+    /// Whether another occurrence follows the current one.
+    pub fn continues(&self) -> bool {
+        !matches!(self.end, End::Count(count) if count <= 1)
+    }
+
+    /// The rule for the occurrence after this one.
+    pub fn advanced(&self) -> Self {
+        let end = match self.end {
+            End::Count(count) => End::Count(count.saturating_sub(1).max(1)),
+            end => end,
+        };
+        Self {
+            end,
+            ..self.clone()
+        }
+    }
+
+    /// The next date the rule falls on after `after`, for a series whose
+    /// current occurrence is on `start`. A task repeating from completion
+    /// is next due one interval after `after`, the day it was completed.
+    pub fn next_after(&self, start: Date, after: Date) -> Option<Date> {
+        let next = if self.from_completion {
+            after.checked_add(self.period_span(1)).ok()?
+        } else {
+            let first = self.first_period(start, after);
+            (first..first + MAX_PERIODS)
+                .flat_map(|period| self.occurrences(start, period))
+                .find(|date| *date > after && *date >= start)?
+        };
+        match self.end {
+            End::Until(until) if next > until => None,
+            _ => Some(next),
+        }
+    }
+
+    /// One interval of the rule's frequency, `periods` times over.
+    fn period_span(&self, periods: i64) -> Span {
+        let count = periods * i64::from(self.interval);
+        match self.frequency {
+            Frequency::Daily => count.days(),
+            Frequency::Weekly => count.weeks(),
+            Frequency::Monthly => count.months(),
+            Frequency::Yearly => count.years(),
+        }
+    }
+
+    /// The last period starting no later than `after`, so the search does
+    /// not walk through every period of a long overdue series.
+    fn first_period(&self, start: Date, after: Date) -> i64 {
+        let Ok(elapsed) = start.until((jiff::Unit::Year, after)) else {
+            return 0;
+        };
+        let days = (after - start).get_days() as i64;
+        let periods = match self.frequency {
+            Frequency::Daily => days,
+            Frequency::Weekly => days / 7,
+            Frequency::Monthly => {
+                i64::from(elapsed.get_years()) * 12 + i64::from(elapsed.get_months())
+            }
+            Frequency::Yearly => i64::from(elapsed.get_years()),
+        };
+        (periods / i64::from(self.interval.max(1)) - 1).max(0)
+    }
+
+    /// The dates the rule falls on in the `period`-th interval after the one
+    /// holding `start`, in order.
+    fn occurrences(&self, start: Date, period: i64) -> Vec<Date> {
+        let mut dates = match self.frequency {
+            Frequency::Daily => start
+                .checked_add(self.period_span(period))
+                .ok()
+                .filter(|date| self.by_day.is_empty() || self.includes_weekday(date.weekday()))
+                .filter(|date| {
+                    self.by_month_day.is_empty()
+                        || self
+                            .by_month_day
+                            .iter()
+                            .any(|day| month_day(*date, *day) == Some(*date))
+                })
+                .into_iter()
+                .collect(),
+            Frequency::Weekly => {
+                let monday = start
+                    .checked_sub(i64::from(start.weekday().to_monday_zero_offset()).days())
+                    .and_then(|monday| monday.checked_add(self.period_span(period)));
+                let Ok(monday) = monday else {
+                    return Vec::new();
+                };
+                (0..7)
+                    .filter_map(|offset| monday.checked_add(offset.days()).ok())
+                    .filter(|date| {
+                        if self.by_day.is_empty() {
+                            date.weekday() == start.weekday()
+                        } else {
+                            self.includes_weekday(date.weekday())
+                        }
+                    })
+                    .collect()
+            }
+            Frequency::Monthly => {
+                match start.first_of_month().checked_add(self.period_span(period)) {
+                    Ok(month) => self.days_in(month, start),
+                    Err(_) => Vec::new(),
+                }
+            }
+            Frequency::Yearly => {
+                let Ok(year) = start.first_of_month().checked_add(self.period_span(period)) else {
+                    return Vec::new();
+                };
+                let months = if self.by_month.is_empty() {
+                    vec![start.month()]
+                } else {
+                    self.by_month.clone()
+                };
+                months
+                    .into_iter()
+                    .filter_map(|month| Date::new(year.year(), month, 1).ok())
+                    .flat_map(|month| self.days_in(month, start))
+                    .collect()
+            }
+        };
+        if !self.by_month.is_empty() {
+            dates.retain(|date| self.by_month.contains(&date.month()));
+        }
+        dates.sort();
+        dates.dedup();
+        dates
+    }
+
+    /// The days of the month starting on `month` the rule falls on. Without
+    /// a day of the month or weekday to go by, that is the day of `start`,
+    /// and months too short for it are skipped.
+    fn days_in(&self, month: Date, start: Date) -> Vec<Date> {
+        let by_weekday = |date: &Date| {
+            self.by_day.iter().any(|day| match day.nth {
+                None => day.weekday == date.weekday(),
+                Some(nth) => month.nth_weekday_of_month(nth, day.weekday).ok() == Some(*date),
+            })
+        };
+
+        if !self.by_month_day.is_empty() {
+            self.by_month_day
+                .iter()
+                .filter_map(|day| month_day(month, *day))
+                .filter(|date| self.by_day.is_empty() || by_weekday(date))
+                .collect()
+        } else if !self.by_day.is_empty() {
+            (0..i64::from(month.days_in_month()))
+                .filter_map(|offset| month.checked_add(offset.days()).ok())
+                .filter(by_weekday)
+                .collect()
+        } else {
+            Date::new(month.year(), month.month(), start.day())
+                .ok()
+                .into_iter()
+                .collect()
+        }
+    }
+}
+
+/// Day `day` of the month `date` is in, counted from the end when negative.
+fn month_day(date: Date, day: i8) -> Option<Date> {
+    let day = if day < 0 {
+        date.days_in_month() + 1 + day
+    } else {
+        day
+    };
+    Date::new(date.year(), date.month(), day).ok()
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={frequency}")?;
+        if self.interval > 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_month.is_empty() {
+            write!(f, ";BYMONTH={}", join(&self.by_month))?;
+        }
+        if !self.by_month_day.is_empty() {
+            write!(f, ";BYMONTHDAY={}", join(&self.by_month_day))?;
+        }
+        if !self.by_day.is_empty() {
+            write!(f, ";BYDAY={}", join(&self.by_day))?;
+        }
+        match self.end {
+            End::Never => {}
+            End::Count(count) => write!(f, ";COUNT={count}")?,
+            End::Until(until) => write!(f, ";UNTIL={}", until.strftime("%Y%m%d"))?,
+        }
+        if self.from_completion {
+            write!(f, ";X-FROM=COMPLETION")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ByDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(nth) = self.nth {
+            write!(f, "{nth}")?;
+        }
+        let code = WEEKDAYS
+            .iter()
+            .find(|(_, weekday)| *weekday == self.weekday)
+            .map_or("", |(code, _)| code);
+        f.write_str(code)
+    }
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+const WEEKDAYS: [(&str, Weekday); 7] = [
+    ("MO", Weekday::Monday),
+    ("TU", Weekday::Tuesday),
+    ("WE", Weekday::Wednesday),
+    ("TH", Weekday::Thursday),
+    ("FR", Weekday::Friday),
+    ("SA", Weekday::Saturday),
+    ("SU", Weekday::Sunday),
+];
+
+impl FromStr for Recurrence {
+    type Err = Error;
+
+    /// Parses an RRULE value, with or without the `RRULE:` prefix.
+    fn from_str(rule: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::InvalidRecurrence(format!("{reason} in {rule:?}"));
+        let body = rule.trim();
+        let body = match body.get(..6) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("RRULE:") => &body[6..],
+            _ => body,
+        };
+
+        let mut frequency = None;
+        let mut recurrence = Self::new(Frequency::Daily);
+        for part in body.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| invalid(&format!("missing value for {part}")))?;
+            match name.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(invalid(&format!("unsupported frequency {value}"))),
+                    })
+                }
+                "INTERVAL" => {
+                    recurrence.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or_else(|| invalid(&format!("invalid interval {value}")))?
+                }
+                "COUNT" => {
+                    if recurrence.end != End::Never {
+                        return Err(invalid("both COUNT and UNTIL"));
+                    }
+                    let count = value
+                        .parse()
+                        .ok()
+                        .filter(|count| *count > 0)
+                        .ok_or_else(|| invalid(&format!("invalid count {value}")))?;
+                    recurrence.end = End::Count(count);
+                }
+                "UNTIL" => {
+                    if recurrence.end != End::Never {
+                        return Err(invalid("both COUNT and UNTIL"));
+                    }
+                    // A date-time is cut down to its date, as tasks are due
+                    // on days.
+                    let until = value
+                        .get(..8)
+                        .and_then(|date| Date::strptime("%Y%m%d", date).ok())
+                        .ok_or_else(|| invalid(&format!("invalid end date {value}")))?;
+                    recurrence.end = End::Until(until);
+                }
+                "BYDAY" => {
+                    recurrence.by_day = value
+                        .split(',')
+                        .map(|day| {
+                            parse_by_day(day)
+                                .ok_or_else(|| invalid(&format!("invalid weekday {day}")))
+                        })
+                        .collect::<Result<_>>()?
+                }
+                "BYMONTHDAY" => {
+                    recurrence.by_month_day = parse_list(value, 31)
+                        .ok_or_else(|| invalid(&format!("invalid day of month {value}")))?
+                }
+                "BYMONTH" => {
+                    recurrence.by_month = parse_list(value, 12)
+                        .filter(|months| months.iter().all(|month| *month > 0))
+                        .ok_or_else(|| invalid(&format!("invalid month {value}")))?
+                }
+                "WKST" => {}
+                "X-FROM" if value.eq_ignore_ascii_case("COMPLETION") => {
+                    recurrence.from_completion = true
+                }
+                _ => return Err(invalid(&format!("unsupported part {name}"))),
+            }
+        }
+        recurrence.frequency = frequency.ok_or_else(|| invalid("missing FREQ"))?;
+        Ok(recurrence)
+    }
+}
+
+fn parse_by_day(day: &str) -> Option<ByDay> {
+    let day = day.trim();
+    let split = day.len().checked_sub(2)?;
+    let (nth, code) = day.split_at_checked(split)?;
+    let weekday = WEEKDAYS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(code))
+        .map(|(_, weekday)| *weekday)?;
+    let nth = match nth {
+        "" => None,
+        nth => Some(
+            nth.parse::<i8>()
+                .ok()
+                .filter(|nth| (1..=53).contains(&nth.abs()))?,
+        ),
+    };
+    Some(ByDay { nth, weekday })
+}
+
+/// A comma separated list of non-zero numbers up to `max` either way.
+fn parse_list(value: &str, max: i8) -> Option<Vec<i8>> {
+    value
+        .split(',')
+        .map(|number| {
+            number
+                .trim()
+                .parse::<i8>()
+                .ok()
+                .filter(|number| *number != 0 && number.abs() <= max)
+        })
+        .collect()
+}
+
+impl From<Recurrence> for String {
+    fn from(recurrence: Recurrence) -> Self {
+        recurrence.to_string()
+    }
+}
+
+impl TryFrom<String> for Recurrence {
+    type Error = Error;
+
+    fn try_from(rule: String) -> Result<Self> {
+        rule.parse()
+    }
+}
+
+/// The weekday flags tasks used to store before they had rules.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Weekdays {
+    monday: bool,
+    tuesday: bool,
+    wednesday: bool,
+    thursday: bool,
+    friday: bool,
+    saturday: bool,
+    sunday: bool,
+}
+
+impl From<Weekdays> for Option<Recurrence> {
+    fn from(days: Weekdays) -> Self {
+        let weekdays: Vec<Weekday> = [
+            (days.monday, Weekday::Monday),
+            (days.tuesday, Weekday::Tuesday),
+            (days.wednesday, Weekday::Wednesday),
+            (days.thursday, Weekday::Thursday),
+            (days.friday, Weekday::Friday),
+            (days.saturday, Weekday::Saturday),
+            (days.sunday, Weekday::Sunday),
+        ]
+        .into_iter()
+        .filter_map(|(set, weekday)| set.then_some(weekday))
+        .collect();
+        (!weekdays.is_empty()).then(|| Recurrence::weekly(weekdays))
+    }
+}
+
+/// Reads the recurrence of a task from the previous app's files, either as
+/// a rule or in the weekday flags form, which becomes a weekly rule on the
+/// same days. Tasks in the store always hold a rule.
+pub fn deserialize<'de, D>(deserializer: D) -> std::result::Result<Option<Recurrence>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct StoredRecurrence;
+
+    impl<'de> Visitor<'de> for StoredRecurrence {
+        type Value = Option<Recurrence>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an RRULE string or weekday flags")
+        }
+
+        fn visit_none<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> std::result::Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> std::result::Result<Self::Value, D::Error> {
+            deserializer.deserialize_any(self)
+        }
+
+        fn visit_str<E: de::Error>(self, rule: &str) -> std::result::Result<Self::Value, E> {
+            rule.parse().map(Some).map_err(E::custom)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(
+            self,
+            map: A,
+        ) -> std::result::Result<Self::Value, A::Error> {
+            let days = Weekdays::deserialize(de::value::MapAccessDeserializer::new(map))?;
+            Ok(days.into())
+        }
+    }
+
+    deserializer.deserialize_any(StoredRecurrence)
+}
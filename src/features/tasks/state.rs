@@ -13,7 +13,6 @@ pub struct TaskState {
 }
 
 impl TaskState {
-    #[allow(dead_code)]
     pub fn new(name: impl ToString, is_completed: bool, position: u32) -> Self {
         Self {
             id: Uuid::new_v4(),
@@ -22,6 +21,16 @@ impl TaskState {
             position,
         }
     }
+
+    /// Pending and Completed, which tasks without a state of their own fall
+    /// back to.
+    pub fn is_built_in(&self) -> bool {
+        is_built_in(self.id)
+    }
+}
+
+pub fn is_built_in(state_id: Uuid) -> bool {
+    state_id == PENDING_STATE_ID || state_id == COMPLETED_STATE_ID
 }
 
 pub fn default_states() -> Vec<TaskState> {
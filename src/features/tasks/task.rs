@@ -1,7 +1,9 @@
-use jiff::{civil::Date, Timestamp};
+use jiff::{civil::Date, tz::TimeZone, Timestamp, Zoned};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::features::tasks::due::DueDate;
+use crate::features::tasks::recurrence::Recurrence;
 use crate::features::tasks::state::{COMPLETED_STATE_ID, PENDING_STATE_ID};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,14 +17,18 @@ pub struct Task {
     #[serde(default)]
     pub state_id: Option<Uuid>,
     pub priority: Priority,
-    pub recurrence: Recurrence,
+    pub recurrence: Option<Recurrence>,
+    /// First task of the recurring series this one belongs to.
+    pub series_id: Option<Uuid>,
     pub tags: Vec<String>,
     pub parent_id: Option<Uuid>,
     pub sub_task_ids: Vec<Uuid>,
     pub completion_date: Option<Timestamp>,
-    pub due_date: Option<Date>,
+    pub due_date: Option<DueDate>,
     pub reminder_date: Option<Timestamp>,
     pub creation_date: Timestamp,
+    /// Last time the task was changed through `TaskStore::update`.
+    pub updated_at: Timestamp,
     #[serde(default)]
     pub sort_order: u32,
 }
@@ -38,7 +44,8 @@ impl Default for Task {
             expanded: false,
             state_id: None,
             priority: Priority::Normal,
-            recurrence: Recurrence::default(),
+            recurrence: None,
+            series_id: None,
             tags: Vec::new(),
             parent_id: None,
             sub_task_ids: Vec::new(),
@@ -46,6 +53,7 @@ impl Default for Task {
             due_date: None,
             reminder_date: None,
             creation_date: Timestamp::now(),
+            updated_at: Timestamp::now(),
             sort_order: 0,
         }
     }
@@ -70,11 +78,75 @@ impl Task {
         self.completion_date.is_some()
     }
 
+    /// When the task is due relative to `now`, if it has a due date. A task
+    /// due at a time is overdue as soon as that time has passed.
+    pub fn due(&self, now: &Zoned) -> Option<Due> {
+        self.due_date.as_ref().map(|due| {
+            if due.is_overdue(now.timestamp()) {
+                Due::Overdue
+            } else {
+                Due::of(due.local_date(), now.date())
+            }
+        })
+    }
+
     pub fn effective_state_id(&self) -> Uuid {
-        self.state_id.unwrap_or(if self.is_completed() {
+        self.state_id.unwrap_or(self.default_state_id())
+    }
+
+    /// The built-in state matching whether the task is completed, used when
+    /// it has no state of its own.
+    pub fn default_state_id(&self) -> Uuid {
+        if self.is_completed() {
             COMPLETED_STATE_ID
         } else {
             PENDING_STATE_ID
+        }
+    }
+
+    /// The task to do next once this recurring one is completed on `today`:
+    /// a fresh, open copy due on the next date of its rule, at the same time
+    /// of day. Missed dates are skipped rather than piling up as overdue
+    /// copies, and the reminder moves by as many days as the due date.
+    pub fn next_occurrence(&self, today: Date) -> Option<Task> {
+        let rule = self.recurrence.as_ref().filter(|rule| rule.continues())?;
+        let tz = TimeZone::system();
+        let base = self
+            .due_date
+            .as_ref()
+            .map(|due| due.date)
+            .or_else(|| self.reminder_date.map(|ts| ts.to_zoned(tz.clone()).date()))
+            .unwrap_or(today);
+        let after = if rule.from_completion {
+            today
+        } else {
+            base.max(today)
+        };
+        let next = rule.next_after(base, after)?;
+        let shift = base.until(next).ok()?;
+        let reminder_date = match self.reminder_date {
+            Some(ts) => Some(ts.to_zoned(tz).checked_add(shift).ok()?.timestamp()),
+            None => None,
+        };
+
+        let now = Timestamp::now();
+        Some(Task {
+            id: Uuid::new_v4(),
+            today: false,
+            expanded: false,
+            state_id: None,
+            recurrence: Some(rule.advanced()),
+            series_id: Some(self.series_id.unwrap_or(self.id)),
+            sub_task_ids: Vec::new(),
+            completion_date: None,
+            due_date: self.due_date.as_ref().map(|due| DueDate {
+                date: next,
+                ..due.clone()
+            }),
+            reminder_date,
+            creation_date: now,
+            updated_at: now,
+            ..self.clone()
         })
     }
 
@@ -88,7 +160,8 @@ impl Task {
             expanded: false,
             state_id: None,
             priority: Priority::Normal,
-            recurrence: Recurrence::default(),
+            recurrence: None,
+            series_id: None,
             tags: Vec::new(),
             parent_id: None,
             sub_task_ids: Vec::new(),
@@ -96,12 +169,46 @@ impl Task {
             due_date: None,
             reminder_date: None,
             creation_date: Timestamp::now(),
+            updated_at: Timestamp::now(),
             sort_order: 0,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+/// How soon a due date comes, in the groups the views show. Weeks start
+/// on Monday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Due {
+    Overdue,
+    Today,
+    Tomorrow,
+    ThisWeek,
+    Later,
+}
+
+impl Due {
+    pub const ALL: [Due; 5] = [
+        Due::Overdue,
+        Due::Today,
+        Due::Tomorrow,
+        Due::ThisWeek,
+        Due::Later,
+    ];
+
+    pub fn of(date: Date, today: Date) -> Self {
+        let days = (date - today).get_days();
+        let days_left_in_week = 6 - i32::from(today.weekday().to_monday_zero_offset());
+        match days {
+            ..0 => Due::Overdue,
+            0 => Due::Today,
+            1 => Due::Tomorrow,
+            days if days <= days_left_in_week => Due::ThisWeek,
+            _ => Due::Later,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Priority {
     #[default]
     Low,
@@ -128,14 +235,3 @@ impl TrashedTask {
         Task::format_timestamp(&self.deleted_at)
     }
 }
-
-#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
-pub struct Recurrence {
-    pub monday: bool,
-    pub tuesday: bool,
-    pub wednesday: bool,
-    pub thursday: bool,
-    pub friday: bool,
-    pub saturday: bool,
-    pub sunday: bool,
-}
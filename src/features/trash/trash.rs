@@ -135,15 +135,9 @@ impl Trash {
             }
             Message::RestoreTask(task_id) => {
                 if let Some(pos) = self.tasks.iter().position(|t| t.task.id == task_id) {
-                    let trashed = self.tasks.remove(pos);
-                    if let Err(e) = self
-                        .store
-                        .tasks(trashed.original_list_id)
-                        .save(&trashed.task)
-                    {
+                    self.tasks.remove(pos);
+                    if let Err(e) = self.store.trash().restore_task(task_id) {
                         tracing::error!("Failed to restore task: {e}");
-                    } else if let Err(e) = self.store.trash().delete(task_id) {
-                        tracing::error!("Failed to remove task from trash after restore: {e}");
                     }
                 }
             }
@@ -265,15 +259,8 @@ impl Trash {
                 self.pending_deletion.take();
                 let tasks = std::mem::take(&mut self.tasks);
                 for trashed in tasks {
-                    let task_id = trashed.task.id;
-                    if let Err(e) = self
-                        .store
-                        .tasks(trashed.original_list_id)
-                        .save(&trashed.task)
-                    {
+                    if let Err(e) = self.store.trash().restore_task(trashed.task.id) {
                         tracing::error!("Failed to restore task during RestoreAll: {e}");
-                    } else if let Err(e) = self.store.trash().delete(task_id) {
-                        tracing::error!("Failed to remove task from trash during RestoreAll: {e}");
                     }
                 }
             }
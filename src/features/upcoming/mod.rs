@@ -0,0 +1,4 @@
+pub mod nav;
+pub mod upcoming;
+
+pub use nav::UpcomingMarker;
@@ -0,0 +1,24 @@
+use cosmic::widget;
+
+use crate::{app::AppModel, fl};
+
+pub struct UpcomingMarker;
+
+impl AppModel {
+    pub fn show_upcoming_nav_item(&mut self) {
+        let icon = widget::icon::from_name("x-office-calendar-symbolic").size(16);
+        self.upcoming_entity = self
+            .nav
+            .insert()
+            .text(fl!("upcoming"))
+            .icon(icon)
+            .data(UpcomingMarker)
+            .id();
+        self.reposition_special_items();
+    }
+
+    pub fn hide_upcoming_nav_item(&mut self) {
+        self.nav.remove(self.upcoming_entity);
+        self.reposition_special_items();
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::HashSet;
+
+use cosmic::{
+    iced::{
+        alignment::{Horizontal, Vertical},
+        Alignment, Length,
+    },
+    theme, widget, Apply, Element,
+};
+use jiff::{civil::Date, Zoned};
+use uuid::Uuid;
+
+use crate::{
+    features::{
+        lists::list::List,
+        tasks::task::{Due, Task},
+    },
+    fl,
+    shared::{
+        store::{
+            query::{TaskQuery, TaskSort},
+            Store,
+        },
+        widgets::{self, collapsible_section},
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct UpcomingEntry {
+    pub task: Task,
+    pub list_id: Uuid,
+    pub list_name: String,
+}
+
+/// Open tasks with a due date from every list, soonest first.
+pub struct Upcoming {
+    entries: Vec<UpcomingEntry>,
+    now: Zoned,
+    collapsed_sections: HashSet<Due>,
+    store: Store,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Load,
+    Complete(Uuid),
+    Open(Uuid),
+    ToggleSection(Due),
+}
+
+pub enum Output {
+    OpenTask { task: Task, list_id: Uuid },
+}
+
+impl Upcoming {
+    pub fn new(store: Store) -> Self {
+        Self {
+            entries: Vec::new(),
+            now: Zoned::now(),
+            collapsed_sections: HashSet::new(),
+            store,
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Option<Output> {
+        match message {
+            Message::Load => {
+                self.now = Zoned::now();
+                let lists: Vec<List> = self.store.lists().load_all().unwrap_or_else(|e| {
+                    tracing::error!("Failed to load lists for upcoming: {e}");
+                    vec![]
+                });
+
+                let tasks = self
+                    .store
+                    .query(
+                        &TaskQuery::new()
+                            .completed(false)
+                            .due_between(Date::MIN, Date::MAX)
+                            .sort_by(TaskSort::Due),
+                    )
+                    .unwrap_or_else(|e| {
+                        tracing::error!("Failed to load upcoming tasks: {e}");
+                        vec![]
+                    });
+
+                self.entries = tasks
+                    .into_iter()
+                    .filter_map(|(list_id, task)| {
+                        let list = lists.iter().find(|l| l.id == list_id)?;
+                        Some(UpcomingEntry {
+                            task,
+                            list_id,
+                            list_name: list.name.clone(),
+                        })
+                    })
+                    .collect();
+            }
+            Message::Complete(task_id) => {
+                if let Some(entry) = self.entries.iter().find(|e| e.task.id == task_id) {
+                    if let Err(e) = self.store.tasks(entry.list_id).complete(task_id, true) {
+                        tracing::error!("Failed to complete task: {e}");
+                    }
+                    return self.update(Message::Load);
+                }
+            }
+            Message::Open(task_id) => {
+                if let Some(entry) = self.entries.iter().find(|e| e.task.id == task_id) {
+                    return Some(Output::OpenTask {
+                        task: entry.task.clone(),
+                        list_id: entry.list_id,
+                    });
+                }
+            }
+            Message::ToggleSection(due) => {
+                if !self.collapsed_sections.remove(&due) {
+                    self.collapsed_sections.insert(due);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        if self.entries.is_empty() {
+            return self.empty_view();
+        }
+
+        let spacing = theme::active().cosmic().spacing;
+
+        let sections: Vec<Element<'_, Message>> = Due::ALL
+            .into_iter()
+            .filter_map(|due| {
+                let entries: Vec<&UpcomingEntry> = self
+                    .entries
+                    .iter()
+                    .filter(|e| e.task.due(&self.now) == Some(due))
+                    .collect();
+                (!entries.is_empty()).then(|| self.section(due, entries))
+            })
+            .collect();
+        let list = widget::column::with_children(sections).spacing(spacing.space_xxs);
+
+        let content = widget::column::with_capacity(2)
+            .push(self.header_view())
+            .push(widget::scrollable(list).height(Length::Fill))
+            .spacing(spacing.space_s)
+            .padding([spacing.space_xxs, spacing.space_xxxs]);
+
+        widget::container(content)
+            .height(Length::Fill)
+            .width(Length::Fill)
+            .center_x(Length::Fill)
+            .max_width(800.)
+            .apply(widget::container)
+            .height(Length::Fill)
+            .width(Length::Fill)
+            .center(Length::Fill)
+            .into()
+    }
+
+    fn header_view(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let icon = widget::icon::from_name("x-office-calendar-symbolic").size(spacing.space_m);
+        let title = widget::text::title4(fl!("upcoming")).width(Length::Fill);
+
+        widget::row::with_capacity(2)
+            .align_y(Alignment::Center)
+            .spacing(spacing.space_s)
+            .padding([spacing.space_none, spacing.space_xxs])
+            .push(icon)
+            .push(title)
+            .into()
+    }
+
+    fn section<'a>(&'a self, due: Due, entries: Vec<&'a UpcomingEntry>) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let collapsed = self.collapsed_sections.contains(&due);
+
+        let title = match due {
+            Due::Overdue => fl!("overdue"),
+            Due::Today => fl!("due-today"),
+            Due::Tomorrow => fl!("due-tomorrow"),
+            Due::ThisWeek => fl!("due-this-week"),
+            Due::Later => fl!("due-later"),
+        };
+        let header = collapsible_section::section_header(
+            title,
+            None,
+            entries.len(),
+            collapsed,
+            Vec::new(),
+            Message::ToggleSection(due),
+            &spacing,
+        );
+
+        let rows = entries
+            .into_iter()
+            .map(|entry| self.entry_row(entry))
+            .collect();
+
+        collapsible_section::section(header, rows, collapsed)
+    }
+
+    fn entry_row<'a>(&'a self, entry: &'a UpcomingEntry) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let task_id = entry.task.id;
+        let checkbox = widget::checkbox(false).on_toggle(move |_| Message::Complete(task_id));
+
+        let title = widget::column::with_capacity(2)
+            .push(widget::text::body(entry.task.title.as_str()))
+            .push(widget::text::caption(entry.list_name.as_str()))
+            .width(Length::Fill);
+
+        let badge = entry
+            .task
+            .due_date
+            .as_ref()
+            .map(|due| widgets::due_badge(due, &self.now, &spacing));
+
+        let open_button =
+            widget::button::icon(widget::icon::from_name("go-next-symbolic").size(16))
+                .padding(spacing.space_xxs)
+                .on_press(Message::Open(task_id));
+
+        let row = widget::row::with_capacity(4)
+            .align_y(Alignment::Center)
+            .spacing(spacing.space_s)
+            .padding([spacing.space_xxs, spacing.space_xs])
+            .push(checkbox)
+            .push(title)
+            .push_maybe(badge)
+            .push(open_button);
+
+        collapsible_section::row_item(row.into())
+    }
+
+    fn empty_view(&self) -> Element<'_, Message> {
+        widget::container(
+            widget::column::with_children(vec![
+                widget::icon::from_name("x-office-calendar-symbolic")
+                    .size(56)
+                    .into(),
+                widget::text::title1(fl!("no-upcoming")).into(),
+                widget::text(fl!("no-upcoming-suggestion")).into(),
+            ])
+            .spacing(10)
+            .align_x(Alignment::Center),
+        )
+        .align_y(Vertical::Center)
+        .align_x(Horizontal::Center)
+        .height(Length::Fill)
+        .width(Length::Fill)
+        .into()
+    }
+}
@@ -20,7 +20,7 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use crate::{
     app::AppModel,
     config::{AppConfig, CONFIG_VERSION},
-    shared::store::Store,
+    shared::store::{snapshot::SnapshotArchive, Store},
 };
 
 pub fn main() -> Result<()> {
@@ -52,18 +52,45 @@ pub fn main() -> Result<()> {
 
     let new_base_dir = project.data_dir();
 
+    // The schema is upgraded first: converting old data opens the store, which
+    // reads everything already in it as the current schema.
+    shared::store::migrations::upgrade(new_base_dir)?;
     shared::store::migrations::migrate(old_base_dir, new_base_dir)?;
 
     // Store is used for persistent storage of tasks and app state.
+    #[cfg(not(feature = "sqlite"))]
     let store = Store::open(project.data_dir())?;
+    #[cfg(feature = "sqlite")]
+    let store = Store::with_backend(shared::store::migrations::open_sqlite(project.data_dir())?);
 
     // Ensure the task state registry exists, seeding the built-in states on first run.
     if let Err(err) = store.states().load_all() {
         tracing::error!("Error loading task states: {err}");
     }
 
+    // Fix broken references between lists, tasks and states before the UI sees them.
+    if let Err(err) = shared::store::check::check_and_repair(&store) {
+        tracing::error!("Error checking store: {err}");
+    }
+
     tracing::info!("Project data directory: {:?}", project.data_dir());
 
+    // Snapshots are kept away from the data directory, so they survive whatever happens to it.
+    #[cfg(not(feature = "sqlite"))]
+    let snapshots = {
+        let snapshot_dir = project
+            .state_dir()
+            .unwrap_or_else(|| project.data_local_dir())
+            .join("snapshots");
+        SnapshotArchive::open(store.clone(), snapshot_dir)
+            .inspect_err(|err| tracing::error!("Error opening snapshots: {err}"))
+            .ok()
+    };
+    // Snapshots copy the RON directory layout, so a database has none, and
+    // the settings leave them out.
+    #[cfg(feature = "sqlite")]
+    let snapshots: Option<SnapshotArchive> = None;
+
     // Config handler for managing the app's configuration.
     let handler = Config::new(AppModel::APP_ID, CONFIG_VERSION)?;
 
@@ -85,6 +112,7 @@ pub fn main() -> Result<()> {
         handler,
         config,
         store,
+        snapshots,
     };
 
     // Run the application.
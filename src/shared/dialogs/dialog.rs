@@ -6,9 +6,10 @@ use cosmic::{
         Length,
     },
     widget::{self, calendar::CalendarModel, segmented_button},
+    Element,
 };
 
-use crate::{app::Message, fl};
+use crate::{app::Message, features::tasks::state::TaskState, fl};
 
 #[derive(Debug, Clone)]
 pub enum DialogAction {
@@ -25,10 +26,20 @@ pub enum DialogPage {
     SetListIcon(Option<segmented_button::Entity>, String, String),
     RenameList(Option<segmented_button::Entity>, String),
     DeleteList(Option<segmented_button::Entity>, String),
+    /// The tag being renamed, and the new name typed so far.
+    RenameTag(String, String),
     DeleteTaskPermanently(uuid::Uuid, String),
     DeleteTaskFromListPermanently(uuid::Uuid, uuid::Uuid, String),
     DeleteListPermanently(uuid::Uuid, String),
     EmptyTrash,
+    /// Tasks left in My Day from a previous day.
+    NewDay(usize),
+    DiscardDamagedItem(uuid::Uuid, String),
+    /// The state, how many tasks it holds, the states they can move to, and
+    /// the choice picked: 0 for Pending or Completed, then one per state.
+    DeleteState(TaskState, usize, Vec<TaskState>, usize),
+    RestoreSnapshot(String, String),
+    RestoreSnapshotList(String, uuid::Uuid, String),
     Calendar(CalendarModel),
     Export(String),
     ReminderDateTime {
@@ -36,6 +47,10 @@ pub enum DialogPage {
         hour: u32,
         minute: u32,
     },
+    DueTime {
+        hour: u32,
+        minute: u32,
+    },
 }
 
 pub fn get_all_icon_handles(size: u16) -> Vec<(String, widget::icon::Handle)> {
@@ -126,6 +141,33 @@ impl DialogPage {
                     ])
                     .spacing(spacing.space_xxs),
                 ),
+            DialogPage::RenameTag(tag, name) => widget::dialog()
+                .title(fl!("rename-tag"))
+                .body(fl!("rename-tag-merge"))
+                .primary_action(
+                    widget::button::suggested(fl!("save"))
+                        .on_press_maybe(Some(Message::Dialog(DialogAction::Complete))),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel"))
+                        .on_press(Message::Dialog(DialogAction::Close)),
+                )
+                .control(
+                    widget::column::with_children(vec![
+                        widget::text::body(fl!("tag-name")).into(),
+                        widget::text_input("", name.as_str())
+                            .id(text_input_id.clone())
+                            .on_input(move |name| {
+                                Message::Dialog(DialogAction::Update(DialogPage::RenameTag(
+                                    tag.clone(),
+                                    name,
+                                )))
+                            })
+                            .on_submit(|_| Message::Dialog(DialogAction::Complete))
+                            .into(),
+                    ])
+                    .spacing(spacing.space_xxs),
+                ),
             DialogPage::DeleteList(_, name) => widget::dialog()
                 .title(fl!("delete-list"))
                 .body(fl!("delete-list-confirm", name = name.as_str()))
@@ -187,6 +229,90 @@ impl DialogPage {
                     widget::button::standard(fl!("cancel"))
                         .on_press(Message::Dialog(DialogAction::Close)),
                 ),
+            DialogPage::NewDay(count) => widget::dialog()
+                .title(fl!("new-day"))
+                .body(fl!("new-day-confirm", count = count))
+                .primary_action(
+                    widget::button::suggested(fl!("my-day-clear"))
+                        .on_press(Message::Dialog(DialogAction::Complete)),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("my-day-keep"))
+                        .on_press(Message::Dialog(DialogAction::Close)),
+                ),
+            DialogPage::DeleteState(state, task_count, others, selected) => {
+                let dialog = widget::dialog()
+                    .title(fl!("delete-state"))
+                    .body(fl!(
+                        "delete-state-confirm",
+                        name = state.name.as_str(),
+                        count = task_count
+                    ))
+                    .primary_action(
+                        widget::button::destructive(fl!("delete"))
+                            .on_press(Message::Dialog(DialogAction::Complete)),
+                    )
+                    .secondary_action(
+                        widget::button::standard(fl!("cancel"))
+                            .on_press(Message::Dialog(DialogAction::Close)),
+                    );
+                if *task_count == 0 {
+                    dialog
+                } else {
+                    let choices: Vec<String> = std::iter::once(fl!("state-by-completion"))
+                        .chain(others.iter().map(|other| other.name.clone()))
+                        .collect();
+                    let (state, task_count, others) = (state.clone(), *task_count, others.clone());
+                    dialog.control(
+                        widget::column::with_children(vec![
+                            widget::text::body(fl!("move-tasks-to")).into(),
+                            widget::dropdown(choices, Some(*selected), move |selected| {
+                                Message::Dialog(DialogAction::Update(DialogPage::DeleteState(
+                                    state.clone(),
+                                    task_count,
+                                    others.clone(),
+                                    selected,
+                                )))
+                            })
+                            .into(),
+                        ])
+                        .spacing(spacing.space_xxs),
+                    )
+                }
+            }
+            DialogPage::DiscardDamagedItem(_, name) => widget::dialog()
+                .title(fl!("discard-damaged-item"))
+                .body(fl!("discard-damaged-item-confirm", name = name.as_str()))
+                .primary_action(
+                    widget::button::destructive(fl!("discard"))
+                        .on_press(Message::Dialog(DialogAction::Complete)),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel"))
+                        .on_press(Message::Dialog(DialogAction::Close)),
+                ),
+            DialogPage::RestoreSnapshot(_, date) => widget::dialog()
+                .title(fl!("restore-snapshot"))
+                .body(fl!("restore-snapshot-confirm", date = date.as_str()))
+                .primary_action(
+                    widget::button::destructive(fl!("restore"))
+                        .on_press(Message::Dialog(DialogAction::Complete)),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel"))
+                        .on_press(Message::Dialog(DialogAction::Close)),
+                ),
+            DialogPage::RestoreSnapshotList(_, _, name) => widget::dialog()
+                .title(fl!("restore-list"))
+                .body(fl!("restore-snapshot-list-confirm", name = name.as_str()))
+                .primary_action(
+                    widget::button::destructive(fl!("restore"))
+                        .on_press(Message::Dialog(DialogAction::Complete)),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel"))
+                        .on_press(Message::Dialog(DialogAction::Close)),
+                ),
             DialogPage::SetListIcon(entity, icon, search) => {
                 let search_lower = search.to_lowercase();
                 let icon_buttons = get_all_icon_handles(20)
@@ -293,6 +419,19 @@ impl DialogPage {
 
                 dialog
             }
+            DialogPage::DueTime { hour, minute } => widget::dialog()
+                .title(fl!("select-due-time"))
+                .primary_action(
+                    widget::button::suggested(fl!("ok"))
+                        .on_press_maybe(Some(Message::Dialog(DialogAction::Complete))),
+                )
+                .secondary_action(
+                    widget::button::standard(fl!("cancel"))
+                        .on_press(Message::Dialog(DialogAction::Close)),
+                )
+                .control(time_picker(*hour, *minute, |hour, minute| {
+                    Message::Dialog(DialogAction::Update(DialogPage::DueTime { hour, minute }))
+                })),
             DialogPage::ReminderDateTime {
                 calendar,
                 hour,
@@ -301,73 +440,16 @@ impl DialogPage {
                 let hour = *hour;
                 let minute = *minute;
 
-                let hour_col = widget::column::with_children(vec![
-                    widget::text::body(fl!("hour")).into(),
-                    widget::row::with_children(vec![
-                        widget::button::text("<")
-                            .on_press(Message::Dialog(DialogAction::Update(
-                                DialogPage::ReminderDateTime {
-                                    calendar: calendar.clone(),
-                                    hour: hour.saturating_sub(1),
-                                    minute,
-                                },
-                            )))
-                            .into(),
-                        widget::text::body(format!("{:02}", hour)).into(),
-                        widget::button::text(">")
-                            .on_press(Message::Dialog(DialogAction::Update(
-                                DialogPage::ReminderDateTime {
-                                    calendar: calendar.clone(),
-                                    hour: (hour + 1).min(23),
-                                    minute,
-                                },
-                            )))
-                            .into(),
-                    ])
-                    .align_y(cosmic::iced::Alignment::Center)
-                    .spacing(spacing.space_xs)
-                    .into(),
-                ])
-                .align_x(cosmic::iced::alignment::Horizontal::Center)
-                .spacing(spacing.space_xxs);
-
-                let minute_col = widget::column::with_children(vec![
-                    widget::text::body(fl!("minute")).into(),
-                    widget::row::with_children(vec![
-                        widget::button::text("<")
-                            .on_press(Message::Dialog(DialogAction::Update(
-                                DialogPage::ReminderDateTime {
-                                    calendar: calendar.clone(),
-                                    hour,
-                                    minute: minute.saturating_sub(1),
-                                },
-                            )))
-                            .into(),
-                        widget::text::body(format!("{:02}", minute)).into(),
-                        widget::button::text(">")
-                            .on_press(Message::Dialog(DialogAction::Update(
-                                DialogPage::ReminderDateTime {
-                                    calendar: calendar.clone(),
-                                    hour,
-                                    minute: (minute + 1).min(59),
-                                },
-                            )))
-                            .into(),
-                    ])
-                    .align_y(cosmic::iced::Alignment::Center)
-                    .spacing(spacing.space_xs)
-                    .into(),
-                ])
-                .align_x(cosmic::iced::alignment::Horizontal::Center)
-                .spacing(spacing.space_xxs);
-
-                let time_row = widget::container(
-                    widget::row::with_children(vec![hour_col.into(), minute_col.into()])
-                        .align_y(cosmic::iced::Alignment::Center)
-                        .spacing(spacing.space_l),
-                )
-                .width(Length::Fill)
-                .align_x(Horizontal::Center);
+                let time_row = time_picker(hour, minute, {
+                    let calendar = calendar.clone();
+                    move |hour, minute| {
+                        Message::Dialog(DialogAction::Update(DialogPage::ReminderDateTime {
+                            calendar: calendar.clone(),
+                            hour,
+                            minute,
+                        }))
+                    }
+                });
 
                 widget::dialog()
                     .title(fl!("select-date-time"))
@@ -407,7 +489,7 @@ impl DialogPage {
                             .align_x(Horizontal::Center)
                             .align_y(Vertical::Center)
                             .into(),
-                            time_row.into(),
+                            time_row,
                         ])
                         .spacing(spacing.space_s),
                     )
@@ -415,3 +497,57 @@ impl DialogPage {
         }
     }
 }
+
+/// Hour and minute steppers for the dialogs that pick a time of day.
+fn time_picker<'a>(
+    hour: u32,
+    minute: u32,
+    on_change: impl Fn(u32, u32) -> Message,
+) -> Element<'a, Message> {
+    let spacing = cosmic::theme::active().cosmic().spacing;
+
+    let hour_col = widget::column::with_children(vec![
+        widget::text::body(fl!("hour")).into(),
+        widget::row::with_children(vec![
+            widget::button::text("<")
+                .on_press(on_change(hour.saturating_sub(1), minute))
+                .into(),
+            widget::text::body(format!("{:02}", hour)).into(),
+            widget::button::text(">")
+                .on_press(on_change((hour + 1).min(23), minute))
+                .into(),
+        ])
+        .align_y(cosmic::iced::Alignment::Center)
+        .spacing(spacing.space_xs)
+        .into(),
+    ])
+    .align_x(cosmic::iced::alignment::Horizontal::Center)
+    .spacing(spacing.space_xxs);
+
+    let minute_col = widget::column::with_children(vec![
+        widget::text::body(fl!("minute")).into(),
+        widget::row::with_children(vec![
+            widget::button::text("<")
+                .on_press(on_change(hour, minute.saturating_sub(1)))
+                .into(),
+            widget::text::body(format!("{:02}", minute)).into(),
+            widget::button::text(">")
+                .on_press(on_change(hour, (minute + 1).min(59)))
+                .into(),
+        ])
+        .align_y(cosmic::iced::Alignment::Center)
+        .spacing(spacing.space_xs)
+        .into(),
+    ])
+    .align_x(cosmic::iced::alignment::Horizontal::Center)
+    .spacing(spacing.space_xxs);
+
+    widget::container(
+        widget::row::with_children(vec![hour_col.into(), minute_col.into()])
+            .align_y(cosmic::iced::Alignment::Center)
+            .spacing(spacing.space_l),
+    )
+    .width(Length::Fill)
+    .align_x(Horizontal::Center)
+    .into()
+}
@@ -91,6 +91,11 @@ impl AppModel {
                                 }
                             }
                         }
+                        DialogPage::RenameTag(tag, name) => {
+                            return cosmic::task::message(Message::Tags(
+                                crate::features::tags::tags::Message::RenameConfirmed(tag, name),
+                            ));
+                        }
                         DialogPage::DeleteList(entity, _) => {
                             return cosmic::task::message(Message::Tasks(TasksAction::DeleteList(
                                 entity,
@@ -144,15 +149,56 @@ impl AppModel {
                                 ),
                             ));
                         }
+                        DialogPage::NewDay(_) => {
+                            return cosmic::task::message(Message::MyDay(
+                                crate::features::my_day::my_day::Message::Clear,
+                            ));
+                        }
                         DialogPage::EmptyTrash => {
                             return cosmic::task::message(Message::Trash(
                                 crate::features::trash::trash::Message::EmptyTrashConfirmed,
                             ));
                         }
+                        DialogPage::DiscardDamagedItem(id, _) => {
+                            return cosmic::task::message(Message::Damaged(
+                                crate::features::damaged::damaged::Message::Discard(id),
+                            ));
+                        }
+                        DialogPage::DeleteState(state, _, others, selected) => {
+                            let into = selected
+                                .checked_sub(1)
+                                .and_then(|index| others.get(index))
+                                .map(|other| other.id);
+                            return cosmic::task::message(Message::States(
+                                crate::features::states::states::Message::DeleteConfirmed(
+                                    state.id, into,
+                                ),
+                            ));
+                        }
+                        DialogPage::RestoreSnapshot(id, _) => {
+                            return cosmic::task::message(Message::Snapshots(
+                                crate::features::snapshots::snapshots::Message::Restore(id),
+                            ));
+                        }
+                        DialogPage::RestoreSnapshotList(id, list_id, _) => {
+                            return cosmic::task::message(Message::Snapshots(
+                                crate::features::snapshots::snapshots::Message::RestoreList(
+                                    id, list_id,
+                                ),
+                            ));
+                        }
                         DialogPage::Calendar(date) => {
                             self.details
                                 .update(details::Message::SetDueDate(date.selected));
                         }
+                        DialogPage::DueTime { hour, minute } => {
+                            match jiff::civil::Time::new(hour as i8, minute as i8, 0, 0) {
+                                Ok(time) => {
+                                    self.details.update(details::Message::SetDueTime(time));
+                                }
+                                Err(err) => tracing::error!("due time: invalid time: {err}"),
+                            }
+                        }
                         DialogPage::ReminderDateTime {
                             calendar,
                             hour,
@@ -5,6 +5,8 @@ pub enum ContextPage {
     About,
     TaskDetails,
     Settings,
+    Snapshots,
+    States,
 }
 
 impl ContextPage {
@@ -12,6 +14,8 @@ impl ContextPage {
         match self {
             Self::About => fl!("about"),
             Self::Settings => fl!("settings"),
+            Self::Snapshots => fl!("snapshots"),
+            Self::States => fl!("states"),
             Self::TaskDetails => fl!("details"),
         }
     }
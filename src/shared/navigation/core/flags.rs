@@ -1,4 +1,7 @@
-use crate::{config::AppConfig, shared::store::Store};
+use crate::{
+    config::AppConfig,
+    shared::store::{snapshot::SnapshotArchive, Store},
+};
 use cosmic::cosmic_config::Config;
 
 #[derive(Clone, Debug)]
@@ -6,4 +9,5 @@ pub struct Flags {
     pub handler: Config,
     pub config: AppConfig,
     pub store: Store,
+    pub snapshots: Option<SnapshotArchive>,
 }
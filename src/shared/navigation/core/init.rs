@@ -12,8 +12,10 @@ use cosmic::{
 
 use crate::{
     features::{
-        favorites::favorites::Favorites, lists::content::Content, search::search::Search,
-        tasks::details::Details, trash::trash::Trash,
+        damaged::damaged::Damaged, favorites::favorites::Favorites, lists::content::Content,
+        my_day::my_day::MyDay, search::search::Search, snapshots::snapshots::Snapshots,
+        states::states::States, tags::tags::Tags, tasks::details::Details, trash::trash::Trash,
+        upcoming::upcoming::Upcoming,
     },
     fl,
     shared::navigation::{nav::TasksAction, ui::MenuAction},
@@ -36,6 +38,10 @@ impl AppModel {
             handler: flags.handler,
             config: flags.config.clone(),
             store: flags.store.clone(),
+            can_undo: flags.store.can_undo(),
+            can_redo: flags.store.can_redo(),
+            snapshots: Snapshots::new(flags.snapshots, flags.config.snapshot_retention),
+            states: States::new(flags.store.clone()),
             content: Content::new(flags.store.clone(), flags.config),
             details: Details::new(flags.store.clone()),
             trash: Trash::new(flags.store.clone()),
@@ -43,8 +49,16 @@ impl AppModel {
             dialog_pages: VecDeque::new(),
             dialog_text_input: widget::Id::unique(),
             trash_entity: widget::segmented_button::Entity::default(),
+            my_day: MyDay::new(flags.store.clone()),
+            my_day_entity: widget::segmented_button::Entity::default(),
+            upcoming: Upcoming::new(flags.store.clone()),
+            upcoming_entity: widget::segmented_button::Entity::default(),
             favorites: Favorites::new(flags.store.clone()),
             favorites_entity: widget::segmented_button::Entity::default(),
+            tags: Tags::new(flags.store.clone()),
+            tags_entity: widget::segmented_button::Entity::default(),
+            damaged: Damaged::new(flags.store.clone()),
+            damaged_entity: widget::segmented_button::Entity::default(),
             sent_reminders: std::collections::HashSet::new(),
             toasts: widget::Toasts::new(Message::CloseToast),
             search: Search::new(flags.store.clone()),
@@ -56,6 +70,13 @@ impl AppModel {
             cosmic::task::message(Message::Search(
                 crate::features::search::search::Message::Load,
             )),
+            cosmic::task::message(Message::Tags(crate::features::tags::tags::Message::Load)),
+            cosmic::task::message(Message::Damaged(
+                crate::features::damaged::damaged::Message::Load,
+            )),
+            cosmic::task::message(Message::Snapshots(
+                crate::features::snapshots::snapshots::Message::Tick,
+            )),
         ];
 
         if let Some(id) = app.core.main_window_id() {
@@ -72,6 +93,16 @@ impl AppModel {
             app.show_favorites_nav_item();
         }
 
+        if app.config.show_my_day {
+            app.show_my_day_nav_item();
+        }
+
+        if app.config.show_upcoming {
+            app.show_upcoming_nav_item();
+        }
+
+        app.start_my_day();
+
         (app, app::Task::batch(tasks))
     }
 
@@ -109,6 +140,8 @@ pub fn key_binds() -> HashMap<KeyBind, MenuAction> {
         }};
     }
 
+    bind!([Ctrl], Key::Character("z".into()), Undo);
+    bind!([Ctrl, Shift], Key::Character("z".into()), Redo);
     bind!([Ctrl], Key::Character("n".into()), NewList);
     bind!([Ctrl], Key::Named(Named::Delete), DeleteList);
     bind!([Ctrl], Key::Character("r".into()), RenameList);
@@ -123,6 +156,8 @@ pub fn key_binds() -> HashMap<KeyBind, MenuAction> {
         Key::Character("h".into()),
         ToggleHideCompletedShortcut
     );
+    bind!([], Key::Named(Named::Tab), IndentTask);
+    bind!([Shift], Key::Named(Named::Tab), OutdentTask);
 
     key_binds
 }
@@ -1,8 +1,9 @@
 use crate::{
     config::AppConfig,
     features::{
-        favorites::favorites, lists::content, reminders::reminder::ReminderMessage, search::search,
-        tasks::details, trash::trash,
+        damaged::damaged, favorites::favorites, lists::content, my_day::my_day,
+        reminders::reminder::ReminderMessage, search::search, snapshots::snapshots, states::states,
+        tags::tags, tasks::details, trash::trash, upcoming::upcoming,
     },
     shared::{
         dialogs::DialogAction,
@@ -29,8 +30,14 @@ pub enum Message {
     Open(String),
     UpdateConfig(AppConfig),
     Trash(trash::Message),
+    MyDay(my_day::Message),
+    Upcoming(upcoming::Message),
     Favorites(favorites::Message),
     Reminder(ReminderMessage),
     CloseToast(cosmic::widget::ToastId),
     Search(search::Message),
+    Tags(tags::Message),
+    Damaged(damaged::Message),
+    Snapshots(snapshots::Message),
+    States(states::Message),
 }
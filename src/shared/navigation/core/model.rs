@@ -12,8 +12,10 @@ use uuid::Uuid;
 use crate::{
     config,
     features::{
-        favorites::favorites::Favorites, lists::content::Content, search::search::Search,
-        tasks::details::Details, trash::trash::Trash,
+        damaged::damaged::Damaged, favorites::favorites::Favorites, lists::content::Content,
+        my_day::my_day::MyDay, search::search::Search, snapshots::snapshots::Snapshots,
+        states::states::States, tags::tags::Tags, tasks::details::Details, trash::trash::Trash,
+        upcoming::upcoming::Upcoming,
     },
     shared::{dialogs::DialogPage, navigation::ui::MenuAction, store::Store},
 };
@@ -30,12 +32,26 @@ pub struct AppModel {
     pub(crate) dialog_pages: VecDeque<DialogPage>,
     pub(crate) dialog_text_input: cosmic::widget::Id,
     pub(crate) store: Store,
+    /// Whether there is anything to undo or redo, refreshed after every
+    /// update so drawing the menu never touches the journal.
+    pub(crate) can_undo: bool,
+    pub(crate) can_redo: bool,
     pub(crate) content: Content,
     pub(crate) details: Details,
     pub(crate) trash: Trash,
     pub(crate) trash_entity: nav_bar::Id,
+    pub(crate) my_day: MyDay,
+    pub(crate) my_day_entity: nav_bar::Id,
+    pub(crate) upcoming: Upcoming,
+    pub(crate) upcoming_entity: nav_bar::Id,
     pub(crate) favorites: Favorites,
     pub(crate) favorites_entity: nav_bar::Id,
+    pub(crate) tags: Tags,
+    pub(crate) tags_entity: nav_bar::Id,
+    pub(crate) damaged: Damaged,
+    pub(crate) damaged_entity: nav_bar::Id,
+    pub(crate) snapshots: Snapshots,
+    pub(crate) states: States,
     pub(crate) sent_reminders: HashSet<(Uuid, i64)>,
     pub(crate) toasts: cosmic::widget::Toasts<super::message::Message>,
     pub(crate) search: Search,
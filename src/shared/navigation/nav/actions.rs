@@ -1,4 +1,5 @@
 use crate::features::lists::List;
+use crate::shared::store::watcher::StoreEvent;
 use cosmic::{widget::menu::Action, widget::segmented_button};
 
 #[derive(Debug, Clone)]
@@ -10,7 +11,7 @@ pub enum TasksAction {
     RestoreTaskFromList(uuid::Uuid, uuid::Uuid),
     FetchLists,
     NavSelect(segmented_button::Entity),
-    SyncFromDisk,
+    StoreChanged(StoreEvent),
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
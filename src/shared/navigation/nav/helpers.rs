@@ -1,16 +1,40 @@
-use crate::{app::AppModel, config::ListSortBy, features::lists::List};
+use crate::{
+    app::AppModel,
+    config::ListSortBy,
+    features::{damaged::DamagedMarker, lists::List, tags::TagsMarker},
+};
 
 impl AppModel {
     pub fn reposition_special_items(&mut self) {
         let mut pos: u16 = 0;
+        if self.config.show_my_day {
+            self.nav.position_set(self.my_day_entity, pos);
+            pos += 1;
+        }
+        if self.config.show_upcoming {
+            self.nav.position_set(self.upcoming_entity, pos);
+            pos += 1;
+        }
         if self.config.show_favorites {
             self.nav.position_set(self.favorites_entity, pos);
             pos += 1;
         }
+        if self.nav.data::<TagsMarker>(self.tags_entity).is_some() {
+            self.nav.position_set(self.tags_entity, pos);
+            pos += 1;
+        }
         if self.config.show_trash {
             self.nav.position_set(self.trash_entity, pos);
             pos += 1;
         }
+        if self
+            .nav
+            .data::<DamagedMarker>(self.damaged_entity)
+            .is_some()
+        {
+            self.nav.position_set(self.damaged_entity, pos);
+            pos += 1;
+        }
         let first_list_pos = pos;
 
         let mut list_entities: Vec<_> = self
@@ -33,6 +57,11 @@ impl AppModel {
             ListSortBy::Manual => {
                 list_entities.sort_by_key(|e| self.nav.data::<List>(*e).map(|l| l.created_at));
             }
+            ListSortBy::Modified => {
+                list_entities.sort_by_key(|e| {
+                    std::cmp::Reverse(self.nav.data::<List>(*e).map(|l| l.updated_at))
+                });
+            }
         }
         for (i, entity) in list_entities.iter().enumerate() {
             self.nav.position_set(*entity, first_list_pos + i as u16);
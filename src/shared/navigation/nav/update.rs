@@ -4,10 +4,14 @@ use cosmic::{app, widget, Application};
 
 use crate::{
     app::{AppModel, Message},
-    features::{lists::content, lists::List},
+    features::{
+        damaged::damaged, favorites::favorites, lists::content, lists::List, my_day::my_day,
+        search::search, tags::tags, trash::trash, upcoming::upcoming,
+    },
     shared::{
         dialogs::{DialogAction, DialogPage},
         navigation::nav::TasksAction,
+        store::watcher::StoreEvent,
     },
 };
 
@@ -78,9 +82,7 @@ impl AppModel {
 
                     let mut tasks = vec![
                         cosmic::task::message(Message::Content(content::Message::SetList(None))),
-                        cosmic::task::message(Message::Trash(
-                            crate::features::trash::trash::Message::Load,
-                        )),
+                        cosmic::task::message(Message::Trash(trash::Message::Load)),
                         self.toasts
                             .push(
                                 cosmic::widget::Toast::new(crate::fl!(
@@ -101,9 +103,7 @@ impl AppModel {
                 Ok(list) => {
                     self.create_nav_item(&list);
                     self.reposition_special_items();
-                    return cosmic::task::message(Message::Trash(
-                        crate::features::trash::trash::Message::Load,
-                    ));
+                    return cosmic::task::message(Message::Trash(trash::Message::Load));
                 }
                 Err(err) => {
                     tracing::error!("Error restoring list from trash: {err}");
@@ -120,29 +120,123 @@ impl AppModel {
                             self.create_nav_item(&list);
                             self.reposition_special_items();
                         }
-                        return cosmic::task::message(Message::Trash(
-                            crate::features::trash::trash::Message::Load,
-                        ));
+                        return cosmic::task::message(Message::Trash(trash::Message::Load));
                     }
                     Err(err) => {
                         tracing::error!("Error restoring task from trashed list: {err}");
                     }
                 }
             }
-            TasksAction::SyncFromDisk => {
-                return self.sync_from_disk();
+            TasksAction::StoreChanged(event) => {
+                if let Err(err) = self.store.reload_history() {
+                    tracing::error!("Failed to read the undo history: {err}");
+                }
+                return self.on_store_event(event);
             }
         }
 
         app::Task::none()
     }
 
+    /// Applies a change another window or process made to the store,
+    /// touching only the views it affects.
+    fn on_store_event(&mut self, event: StoreEvent) -> app::Task<Message> {
+        match event {
+            StoreEvent::ListsChanged => {
+                let removed_active = self.sync_lists();
+                let mut tasks = self.reload_views();
+                if removed_active {
+                    tasks.push(cosmic::task::message(Message::Content(
+                        content::Message::SetList(None),
+                    )));
+                }
+                app::Task::batch(tasks)
+            }
+            StoreEvent::ListChanged(list_id) => {
+                let mut tasks = vec![
+                    cosmic::task::message(Message::MyDay(my_day::Message::Load)),
+                    cosmic::task::message(Message::Upcoming(upcoming::Message::Load)),
+                    cosmic::task::message(Message::Favorites(favorites::Message::Load)),
+                    cosmic::task::message(Message::Search(search::Message::Load)),
+                    cosmic::task::message(Message::Tags(tags::Message::Load)),
+                ];
+                if self
+                    .nav
+                    .active_data::<List>()
+                    .is_some_and(|list| list.id == list_id)
+                {
+                    tasks.push(self.sync_active_list());
+                }
+                app::Task::batch(tasks)
+            }
+            StoreEvent::TaskChanged(list_id, task) => app::Task::batch(vec![
+                cosmic::task::message(Message::Content(content::Message::TaskChanged(
+                    list_id,
+                    task.clone(),
+                ))),
+                cosmic::task::message(Message::Favorites(favorites::Message::TaskChanged(
+                    list_id,
+                    task.clone(),
+                ))),
+                cosmic::task::message(Message::Search(search::Message::TaskChanged(list_id, task))),
+                cosmic::task::message(Message::MyDay(my_day::Message::Load)),
+                cosmic::task::message(Message::Upcoming(upcoming::Message::Load)),
+                cosmic::task::message(Message::Tags(tags::Message::Load)),
+            ]),
+            StoreEvent::TaskRemoved(_, task_id) => app::Task::batch(vec![
+                cosmic::task::message(Message::Content(content::Message::TaskRemoved(task_id))),
+                cosmic::task::message(Message::Favorites(favorites::Message::TaskRemoved(task_id))),
+                cosmic::task::message(Message::Search(search::Message::TaskRemoved(task_id))),
+                cosmic::task::message(Message::MyDay(my_day::Message::Load)),
+                cosmic::task::message(Message::Upcoming(upcoming::Message::Load)),
+                cosmic::task::message(Message::Tags(tags::Message::Load)),
+            ]),
+            StoreEvent::TrashChanged => cosmic::task::message(Message::Trash(trash::Message::Load)),
+            StoreEvent::QuarantineChanged => {
+                cosmic::task::message(Message::Damaged(damaged::Message::Load))
+            }
+            StoreEvent::StatesChanged => match self.store.states().load_all() {
+                Ok(states) => {
+                    cosmic::task::message(Message::Content(content::Message::SetStates(states)))
+                }
+                Err(err) => {
+                    tracing::error!("Error syncing task states: {err}");
+                    app::Task::none()
+                }
+            },
+            StoreEvent::Reload => self.sync_from_disk(),
+        }
+    }
+
     fn sync_from_disk(&mut self) -> app::Task<Message> {
+        let removed_active = self.sync_lists();
+        let mut tasks = self.reload_views();
+
+        if removed_active {
+            tasks.push(cosmic::task::message(Message::Content(
+                content::Message::SetList(None),
+            )));
+        } else {
+            tasks.push(self.sync_active_list());
+        }
+
+        if let Ok(states) = self.store.states().load_all() {
+            tasks.push(cosmic::task::message(Message::Content(
+                content::Message::SetStates(states),
+            )));
+        }
+
+        app::Task::batch(tasks)
+    }
+
+    /// Brings the navigation in line with the list registry. Returns whether
+    /// the active list was removed.
+    fn sync_lists(&mut self) -> bool {
         let disk_lists = match self.store.lists().load_all() {
             Ok(lists) => lists,
             Err(err) => {
                 tracing::error!("Error syncing lists: {err}");
-                return app::Task::none();
+                return false;
             }
         };
 
@@ -184,29 +278,35 @@ impl AppModel {
         }
         self.reposition_special_items();
 
-        let mut tasks = vec![
-            cosmic::task::message(Message::Trash(crate::features::trash::trash::Message::Load)),
-            cosmic::task::message(Message::Search(
-                crate::features::search::search::Message::Load,
-            )),
-        ];
+        removed_active
+    }
 
-        if removed_active {
-            tasks.push(cosmic::task::message(Message::Content(
-                content::Message::SetList(None),
-            )));
-        } else if let Some(active_list) = self.nav.active_data::<List>().cloned() {
-            match self.store.tasks(active_list.id).load_all() {
-                Ok(disk_tasks) => {
-                    tasks.push(cosmic::task::message(Message::Content(
-                        content::Message::SyncTasks(disk_tasks),
-                    )));
-                }
-                Err(err) => tracing::error!("Error syncing tasks: {err}"),
+    /// Reloads the views that gather tasks from every list.
+    fn reload_views(&self) -> Vec<app::Task<Message>> {
+        vec![
+            cosmic::task::message(Message::Trash(trash::Message::Load)),
+            cosmic::task::message(Message::MyDay(my_day::Message::Load)),
+            cosmic::task::message(Message::Upcoming(upcoming::Message::Load)),
+            cosmic::task::message(Message::Favorites(favorites::Message::Load)),
+            cosmic::task::message(Message::Search(search::Message::Load)),
+            cosmic::task::message(Message::Tags(tags::Message::Load)),
+            cosmic::task::message(Message::Damaged(damaged::Message::Load)),
+        ]
+    }
+
+    fn sync_active_list(&self) -> app::Task<Message> {
+        let Some(active_list) = self.nav.active_data::<List>() else {
+            return app::Task::none();
+        };
+        match self.store.tasks(active_list.id).load_all() {
+            Ok(disk_tasks) => {
+                cosmic::task::message(Message::Content(content::Message::SyncTasks(disk_tasks)))
+            }
+            Err(err) => {
+                tracing::error!("Error syncing tasks: {err}");
+                app::Task::none()
             }
         }
-
-        app::Task::batch(tasks)
     }
 
     pub fn update_nav_menu(&mut self, action: NavMenuAction) -> app::Task<Message> {
@@ -249,9 +349,7 @@ impl AppModel {
                 }
             }
             NavMenuAction::TrashRestoreAll => {
-                return cosmic::task::message(Message::Trash(
-                    crate::features::trash::trash::Message::RestoreAll,
-                ));
+                return cosmic::task::message(Message::Trash(trash::Message::RestoreAll));
             }
         }
 
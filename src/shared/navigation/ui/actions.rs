@@ -1,14 +1,22 @@
 use cosmic::{
-    iced::keyboard::{Key, Modifiers},
+    iced::{
+        event::Status,
+        keyboard::{Key, Modifiers},
+    },
     widget::menu::Action,
 };
 
-use crate::app::{ContextPage, Message};
+use crate::{
+    app::{ContextPage, Message},
+    shared::store::snapshot::SnapshotRetention,
+};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum MenuAction {
     About,
     Settings,
+    Undo,
+    Redo,
     WindowClose,
     WindowNew,
     NewList,
@@ -18,21 +26,38 @@ pub enum MenuAction {
     ToggleHideCompleted(bool),
     ToggleHideCompletedShortcut,
     ToggleSearchBar,
+    IndentTask,
+    OutdentTask,
     SortByNameAsc,
     SortByNameDesc,
     SortByDateAsc,
     SortByDateDesc,
+    SortByModified,
     SortByManual,
 }
 
 #[derive(Debug, Clone)]
 pub enum ApplicationAction {
-    Key(Modifiers, Key),
+    Key(Modifiers, Key, Status),
     Modifiers(Modifiers),
     AppTheme(usize),
+    ToggleShowMyDay(bool),
+    ToggleShowUpcoming(bool),
     ToggleShowFavorites(bool),
     ToggleShowTrash(bool),
     ListSortBy(usize),
+    SnapshotRetention(SnapshotRetention),
+}
+
+impl MenuAction {
+    /// Whether the shortcut is left to a focused widget that handles the key
+    /// itself, such as Tab moving the focus on or Ctrl+Z undoing typing.
+    pub fn defers_to_widgets(&self) -> bool {
+        matches!(
+            self,
+            MenuAction::Undo | MenuAction::Redo | MenuAction::IndentTask | MenuAction::OutdentTask
+        )
+    }
 }
 
 impl Action for MenuAction {
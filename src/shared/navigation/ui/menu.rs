@@ -19,6 +19,34 @@ use crate::{
 pub fn menu_bar<'a>(state: &AppModel) -> Element<'a, Message> {
     let list_selected = state.nav.active_data::<List>().is_some();
 
+    let undo_icon = || {
+        Some(
+            widget::icon::from_name("edit-undo-symbolic")
+                .size(14)
+                .handle(),
+        )
+    };
+    let redo_icon = || {
+        Some(
+            widget::icon::from_name("edit-redo-symbolic")
+                .size(14)
+                .handle(),
+        )
+    };
+    let history = vec![
+        if state.can_undo {
+            Item::Button(fl!("undo"), undo_icon(), MenuAction::Undo)
+        } else {
+            Item::ButtonDisabled(fl!("undo"), undo_icon(), MenuAction::Undo)
+        },
+        if state.can_redo {
+            Item::Button(fl!("redo"), redo_icon(), MenuAction::Redo)
+        } else {
+            Item::ButtonDisabled(fl!("redo"), redo_icon(), MenuAction::Redo)
+        },
+        Item::Divider,
+    ];
+
     MenuBar::new(vec![
         Tree::with_children(
             Element::from(root(fl!("file"))),
@@ -61,61 +89,70 @@ pub fn menu_bar<'a>(state: &AppModel) -> Element<'a, Message> {
             Element::from(root(fl!("edit"))),
             items(
                 &state.key_binds,
-                list_selected
-                    .then_some(vec![
-                        Item::Button(
-                            fl!("rename"),
-                            Some(widget::icon::from_name("edit-symbolic").size(14).handle()),
-                            MenuAction::RenameList,
-                        ),
-                        Item::Divider,
-                        Item::Button(
-                            fl!("icon"),
-                            Some(
-                                widget::icon::from_name("face-smile-big-symbolic")
-                                    .size(14)
-                                    .handle(),
-                            ),
-                            MenuAction::Icon,
-                        ),
-                        Item::Divider,
-                        Item::Button(
-                            fl!("delete"),
-                            Some(
-                                widget::icon::from_name("user-trash-full-symbolic")
-                                    .size(14)
-                                    .handle(),
-                            ),
-                            MenuAction::DeleteList,
-                        ),
-                    ])
-                    .unwrap_or(vec![
-                        Item::ButtonDisabled(
-                            fl!("rename"),
-                            Some(widget::icon::from_name("edit-symbolic").size(14).handle()),
-                            MenuAction::RenameList,
-                        ),
-                        Item::Divider,
-                        Item::ButtonDisabled(
-                            fl!("icon"),
-                            Some(
-                                widget::icon::from_name("face-smile-big-symbolic")
-                                    .size(14)
-                                    .handle(),
-                            ),
-                            MenuAction::Icon,
-                        ),
-                        Item::Divider,
-                        Item::ButtonDisabled(
-                            fl!("delete"),
-                            Some(
-                                widget::icon::from_name("user-trash-full-symbolic")
-                                    .size(14)
-                                    .handle(),
-                            ),
-                            MenuAction::DeleteList,
-                        ),
-                    ]),
+                history
+                    .into_iter()
+                    .chain(
+                        list_selected
+                            .then_some(vec![
+                                Item::Button(
+                                    fl!("rename"),
+                                    Some(
+                                        widget::icon::from_name("edit-symbolic").size(14).handle(),
+                                    ),
+                                    MenuAction::RenameList,
+                                ),
+                                Item::Divider,
+                                Item::Button(
+                                    fl!("icon"),
+                                    Some(
+                                        widget::icon::from_name("face-smile-big-symbolic")
+                                            .size(14)
+                                            .handle(),
+                                    ),
+                                    MenuAction::Icon,
+                                ),
+                                Item::Divider,
+                                Item::Button(
+                                    fl!("delete"),
+                                    Some(
+                                        widget::icon::from_name("user-trash-full-symbolic")
+                                            .size(14)
+                                            .handle(),
+                                    ),
+                                    MenuAction::DeleteList,
+                                ),
+                            ])
+                            .unwrap_or(vec![
+                                Item::ButtonDisabled(
+                                    fl!("rename"),
+                                    Some(
+                                        widget::icon::from_name("edit-symbolic").size(14).handle(),
+                                    ),
+                                    MenuAction::RenameList,
+                                ),
+                                Item::Divider,
+                                Item::ButtonDisabled(
+                                    fl!("icon"),
+                                    Some(
+                                        widget::icon::from_name("face-smile-big-symbolic")
+                                            .size(14)
+                                            .handle(),
+                                    ),
+                                    MenuAction::Icon,
+                                ),
+                                Item::Divider,
+                                Item::ButtonDisabled(
+                                    fl!("delete"),
+                                    Some(
+                                        widget::icon::from_name("user-trash-full-symbolic")
+                                            .size(14)
+                                            .handle(),
+                                    ),
+                                    MenuAction::DeleteList,
+                                ),
+                            ]),
+                    )
+                    .collect(),
             ),
         ),
         Tree::with_children(
@@ -182,6 +219,12 @@ pub fn menu_bar<'a>(state: &AppModel) -> Element<'a, Message> {
                             state.config.sort_by == SortBy::DateDesc,
                             MenuAction::SortByDateDesc,
                         ),
+                        Item::CheckBox(
+                            fl!("sort-modified"),
+                            None,
+                            state.config.sort_by == SortBy::Modified,
+                            MenuAction::SortByModified,
+                        ),
                         Item::CheckBox(
                             fl!("sort-manual"),
                             None,
@@ -202,6 +245,11 @@ pub fn menu_bar<'a>(state: &AppModel) -> Element<'a, Message> {
                             None,
                             MenuAction::SortByDateDesc,
                         ),
+                        Item::ButtonDisabled(
+                            fl!("sort-modified"),
+                            None,
+                            MenuAction::SortByModified,
+                        ),
                         Item::ButtonDisabled(fl!("sort-manual"), None, MenuAction::SortByManual),
                     ]),
             ),
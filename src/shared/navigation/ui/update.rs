@@ -1,6 +1,6 @@
 use std::{env, process};
 
-use cosmic::{app, widget::menu::Action as _, Application};
+use cosmic::{app, iced::event, widget, widget::menu::Action as _, Application};
 
 use crate::{
     app::{AppModel, ContextPage, Message},
@@ -8,9 +8,18 @@ use crate::{
     features::{
         favorites::FavoritesMarker,
         lists::{content, List},
+        my_day::MyDayMarker,
+        snapshots::snapshots,
         trash::TrashMarker,
+        upcoming::UpcomingMarker,
     },
-    shared::dialogs::{DialogAction, DialogPage},
+    fl,
+    shared::{
+        dialogs::{DialogAction, DialogPage},
+        navigation::nav::TasksAction,
+        store::watcher::StoreEvent,
+    },
+    Error, StoreError,
 };
 
 use super::{ApplicationAction, MenuAction};
@@ -24,6 +33,50 @@ impl AppModel {
                 }
                 return cosmic::command::set_theme(self.config.app_theme.theme());
             }
+            ApplicationAction::ToggleShowMyDay(show) => {
+                if let Err(err) = self.config.set_show_my_day(&self.handler, show) {
+                    tracing::error!("{err}");
+                }
+                if show {
+                    self.show_my_day_nav_item();
+                } else {
+                    self.hide_my_day_nav_item();
+                    if self.nav.active_data::<MyDayMarker>().is_some() {
+                        let entity = self
+                            .nav
+                            .iter()
+                            .find(|e| self.nav.data::<List>(*e).is_some())
+                            .unwrap_or_else(|| self.nav.iter().last().unwrap_or_default());
+                        return self.update(crate::app::Message::Content(
+                            crate::features::lists::content::Message::SetList(
+                                self.nav.data::<List>(entity).cloned(),
+                            ),
+                        ));
+                    }
+                }
+            }
+            ApplicationAction::ToggleShowUpcoming(show) => {
+                if let Err(err) = self.config.set_show_upcoming(&self.handler, show) {
+                    tracing::error!("{err}");
+                }
+                if show {
+                    self.show_upcoming_nav_item();
+                } else {
+                    self.hide_upcoming_nav_item();
+                    if self.nav.active_data::<UpcomingMarker>().is_some() {
+                        let entity = self
+                            .nav
+                            .iter()
+                            .find(|e| self.nav.data::<List>(*e).is_some())
+                            .unwrap_or_else(|| self.nav.iter().last().unwrap_or_default());
+                        return self.update(crate::app::Message::Content(
+                            crate::features::lists::content::Message::SetList(
+                                self.nav.data::<List>(entity).cloned(),
+                            ),
+                        ));
+                    }
+                }
+            }
             ApplicationAction::ToggleShowFavorites(show) => {
                 if let Err(err) = self.config.set_show_favorites(&self.handler, show) {
                     tracing::error!("{err}");
@@ -52,6 +105,14 @@ impl AppModel {
                 }
                 self.reposition_special_items();
             }
+            ApplicationAction::SnapshotRetention(retention) => {
+                if let Err(err) = self.config.set_snapshot_retention(&self.handler, retention) {
+                    tracing::error!("{err}");
+                }
+                return self.update(Message::Snapshots(snapshots::Message::SetRetention(
+                    retention,
+                )));
+            }
             ApplicationAction::ToggleShowTrash(show) => {
                 if let Err(err) = self.config.set_show_trash(&self.handler, show) {
                     tracing::error!("{err}");
@@ -74,8 +135,11 @@ impl AppModel {
                     }
                 }
             }
-            ApplicationAction::Key(modifiers, key) => {
+            ApplicationAction::Key(modifiers, key, status) => {
                 for (key_bind, action) in self.key_binds.clone().into_iter() {
+                    if action.defers_to_widgets() && status != event::Status::Ignored {
+                        continue;
+                    }
                     if key_bind.matches(modifiers, &key, None) {
                         return cosmic::task::message(action.message());
                     }
@@ -97,6 +161,32 @@ impl AppModel {
             MenuAction::Settings => {
                 return cosmic::task::message(Message::ToggleContextPage(ContextPage::Settings));
             }
+            MenuAction::Undo | MenuAction::Redo => {
+                let result = if action == MenuAction::Undo {
+                    self.store.undo()
+                } else {
+                    self.store.redo()
+                };
+                let toast = match result {
+                    Ok(true) => {
+                        return cosmic::task::message(Message::Tasks(TasksAction::StoreChanged(
+                            StoreEvent::Reload,
+                        )));
+                    }
+                    Ok(false) if action == MenuAction::Undo => fl!("nothing-to-undo"),
+                    Ok(false) => fl!("nothing-to-redo"),
+                    Err(Error::Store(StoreError::UndoConflict)) => fl!("undo-conflict"),
+                    Err(err) => {
+                        tracing::error!("Failed to apply history entry: {err}");
+                        let error = err.to_string();
+                        fl!("undo-failed", error = error.as_str())
+                    }
+                };
+                return self
+                    .toasts
+                    .push(widget::Toast::new(toast))
+                    .map(cosmic::Action::App);
+            }
             MenuAction::WindowClose => {
                 if let Some(window_id) = self.core.main_window_id() {
                     return cosmic::iced::window::close(window_id);
@@ -155,6 +245,16 @@ impl AppModel {
                     "global-search-input",
                 ));
             }
+            MenuAction::IndentTask => {
+                return cosmic::task::message(Message::Content(
+                    content::Message::IndentFocusedTask,
+                ));
+            }
+            MenuAction::OutdentTask => {
+                return cosmic::task::message(Message::Content(
+                    content::Message::OutdentFocusedTask,
+                ));
+            }
             MenuAction::SortByNameAsc => {
                 if let Err(err) = self
                     .config
@@ -199,6 +299,17 @@ impl AppModel {
                     content::SortBy::DateDesc,
                 )));
             }
+            MenuAction::SortByModified => {
+                if let Err(err) = self
+                    .config
+                    .set_sort_by(&self.handler, config::SortBy::Modified)
+                {
+                    tracing::error!("{err}")
+                }
+                return cosmic::task::message(Message::Content(content::Message::SetSort(
+                    content::SortBy::Modified,
+                )));
+            }
             MenuAction::SortByManual => {
                 if let Err(err) = self
                     .config
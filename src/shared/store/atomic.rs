@@ -0,0 +1,161 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+const TEMP_EXTENSION: &str = "tmp";
+const BACKUP_EXTENSION: &str = "bak";
+const CORRUPT_EXTENSION: &str = "corrupt";
+
+/// Temp files younger than this may still belong to a writer in another
+/// process, so recovery leaves them alone.
+const STALE_TEMP_AGE: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    /// The file was missing or parsed fine; nothing had to be done.
+    Intact,
+    /// The file was damaged and has been replaced by its last backup.
+    RestoredFromBackup,
+    /// The file was damaged and no usable backup exists. The damaged copy
+    /// has been moved aside so the caller can rebuild it.
+    Unrecoverable,
+}
+
+/// Replaces `path` with `contents` without ever exposing a partially written
+/// file: the data goes to a sibling temp file, is fsynced, and is then
+/// renamed over the destination.
+pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let temp = temp_path(path);
+    let result = (|| {
+        let mut file = File::create(&temp)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        fs::rename(&temp, path)?;
+        sync_parent(path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Appends `contents` to `path`, creating it if needed, and fsyncs it
+/// before returning. A crash can cut short only the last append.
+pub fn append(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let created = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(contents.as_ref())?;
+    file.sync_data()?;
+    if created {
+        sync_parent(path)?;
+    }
+    Ok(())
+}
+
+/// Like [`write`], but first keeps the current contents of `path` in a
+/// `.bak` sibling. Used for registries, which cannot be rebuilt from
+/// anything else on disk.
+pub fn write_with_backup(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    if path.exists() {
+        let backup = sibling(path, BACKUP_EXTENSION);
+        fs::copy(path, &backup)?;
+        File::open(&backup)?.sync_all()?;
+    }
+    write(path, contents)
+}
+
+/// Checks `path` with `is_valid` and, if it does not hold up, tries to put
+/// the last backup back in its place. Stale temp files left behind by an
+/// interrupted [`write`] are removed along the way.
+pub fn recover(path: &Path, is_valid: impl Fn(&str) -> bool) -> io::Result<Recovery> {
+    remove_stale_temp_files(path)?;
+
+    if !path.exists() {
+        return Ok(Recovery::Intact);
+    }
+
+    let content = fs::read_to_string(path).unwrap_or_default();
+    if is_valid(&content) {
+        return Ok(Recovery::Intact);
+    }
+
+    let backup = sibling(path, BACKUP_EXTENSION);
+    let backup_content = fs::read_to_string(&backup).ok();
+    if let Some(backup_content) = backup_content.filter(|c| is_valid(c)) {
+        write(path, backup_content)?;
+        return Ok(Recovery::RestoredFromBackup);
+    }
+
+    fs::rename(path, sibling(path, CORRUPT_EXTENSION))?;
+    Ok(Recovery::Unrecoverable)
+}
+
+fn remove_stale_temp_files(path: &Path) -> io::Result<()> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+        return Ok(());
+    };
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    let prefix = format!("{name}.");
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let temp = entry.path();
+        let is_ours = temp.extension().and_then(|e| e.to_str()) == Some(TEMP_EXTENSION)
+            && entry
+                .file_name()
+                .to_str()
+                .is_some_and(|n| n.starts_with(&prefix));
+        if !is_ours {
+            continue;
+        }
+
+        let is_stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age >= STALE_TEMP_AGE);
+        if is_stale {
+            tracing::warn!("removing interrupted write {:?}", temp.file_name());
+            fs::remove_file(&temp)?;
+        }
+    }
+    Ok(())
+}
+
+/// A temp file name no other write can be using: the process id keeps
+/// processes apart, and a counter keeps apart threads of this one writing
+/// the same file.
+fn temp_path(path: &Path) -> PathBuf {
+    static NEXT_WRITE: AtomicU64 = AtomicU64::new(0);
+    let write = NEXT_WRITE.fetch_add(1, Ordering::Relaxed);
+    sibling(
+        path,
+        &format!("{}.{write}.{TEMP_EXTENSION}", std::process::id()),
+    )
+}
+
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(extension);
+    path.with_file_name(name)
+}
+
+#[cfg(unix)]
+fn sync_parent(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(parent) => File::open(parent)?.sync_all(),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use uuid::Uuid;
+
+use super::{LockGuard, StorageBackend};
+use crate::features::lists::list::{List, TrashedList};
+use crate::features::tasks::state::TaskState;
+use crate::features::tasks::task::{Task, TrashedTask};
+use crate::shared::store::history::Revision;
+use crate::{Error, Result, StoreError};
+
+#[derive(Debug, Default)]
+struct Data {
+    lists: Vec<List>,
+    states: Option<Vec<TaskState>>,
+    tasks: HashMap<Uuid, HashMap<Uuid, Task>>,
+    trashed_tasks: HashMap<Uuid, TrashedTask>,
+    trashed_lists: Vec<TrashedList>,
+    trashed_list_tasks: HashMap<Uuid, HashMap<Uuid, Task>>,
+    revisions: HashMap<Uuid, Vec<Revision>>,
+}
+
+/// Keeps everything in memory. Meant for tests and tools that want a
+/// throwaway store; nothing survives the process.
+///
+/// Each call is atomic on its own, but [`StorageBackend::lock`] does not
+/// serialize read-modify-write cycles across threads.
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    data: Mutex<Data>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn data(&self) -> MutexGuard<'_, Data> {
+        self.data
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl StorageBackend for MemoryBackend {
+    fn root(&self) -> Option<&Path> {
+        None
+    }
+
+    fn lock(&self) -> Result<Box<dyn LockGuard + '_>> {
+        Ok(Box::new(()))
+    }
+
+    fn read_lists(&self) -> Result<Vec<List>> {
+        Ok(self.data().lists.clone())
+    }
+
+    fn write_lists(&self, lists: &[List]) -> Result<()> {
+        self.data().lists = lists.to_vec();
+        Ok(())
+    }
+
+    fn create_list(&self, list_id: Uuid) -> Result<()> {
+        self.data().tasks.entry(list_id).or_default();
+        Ok(())
+    }
+
+    fn remove_list(&self, list_id: Uuid) -> Result<()> {
+        self.data().tasks.remove(&list_id);
+        Ok(())
+    }
+
+    fn has_list(&self, list_id: Uuid) -> bool {
+        self.data().tasks.contains_key(&list_id)
+    }
+
+    fn list_ids(&self) -> Result<Vec<Uuid>> {
+        Ok(self.data().tasks.keys().copied().collect())
+    }
+
+    fn read_states(&self) -> Result<Option<Vec<TaskState>>> {
+        Ok(self.data().states.clone())
+    }
+
+    fn write_states(&self, states: &[TaskState]) -> Result<()> {
+        self.data().states = Some(states.to_vec());
+        Ok(())
+    }
+
+    fn read_task(&self, list_id: Uuid, task_id: Uuid) -> Result<Task> {
+        self.data()
+            .tasks
+            .get(&list_id)
+            .and_then(|tasks| tasks.get(&task_id))
+            .cloned()
+            .ok_or(Error::Store(StoreError::TaskNotFound(task_id)))
+    }
+
+    fn read_tasks(&self, list_id: Uuid) -> Result<Vec<Task>> {
+        Ok(self
+            .data()
+            .tasks
+            .get(&list_id)
+            .map(|tasks| tasks.values().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    fn write_task(&self, list_id: Uuid, task: &Task) -> Result<()> {
+        self.data()
+            .tasks
+            .entry(list_id)
+            .or_default()
+            .insert(task.id, task.clone());
+        Ok(())
+    }
+
+    fn remove_task(&self, list_id: Uuid, task_id: Uuid) -> Result<()> {
+        self.data()
+            .tasks
+            .get_mut(&list_id)
+            .and_then(|tasks| tasks.remove(&task_id))
+            .map(|_| ())
+            .ok_or(Error::Store(StoreError::TaskNotFound(task_id)))
+    }
+
+    fn read_trashed_tasks(&self) -> Result<Vec<TrashedTask>> {
+        Ok(self.data().trashed_tasks.values().cloned().collect())
+    }
+
+    fn write_trashed_task(&self, trashed: &TrashedTask) -> Result<()> {
+        self.data()
+            .trashed_tasks
+            .insert(trashed.task.id, trashed.clone());
+        Ok(())
+    }
+
+    fn remove_trashed_task(&self, task_id: Uuid) -> Result<()> {
+        self.data()
+            .trashed_tasks
+            .remove(&task_id)
+            .map(|_| ())
+            .ok_or(Error::Store(StoreError::TaskNotFound(task_id)))
+    }
+
+    fn read_trashed_lists(&self) -> Result<Vec<TrashedList>> {
+        Ok(self.data().trashed_lists.clone())
+    }
+
+    fn write_trashed_lists(&self, lists: &[TrashedList]) -> Result<()> {
+        self.data().trashed_lists = lists.to_vec();
+        Ok(())
+    }
+
+    fn move_list_to_trash(&self, list_id: Uuid) -> Result<()> {
+        let mut data = self.data();
+        if let Some(tasks) = data.tasks.remove(&list_id) {
+            data.trashed_list_tasks.insert(list_id, tasks);
+        }
+        Ok(())
+    }
+
+    fn move_list_from_trash(&self, list_id: Uuid) -> Result<()> {
+        let mut data = self.data();
+        let tasks = data.trashed_list_tasks.remove(&list_id).unwrap_or_default();
+        data.tasks.insert(list_id, tasks);
+        Ok(())
+    }
+
+    fn read_trashed_list_tasks(&self, list_id: Uuid) -> Result<Vec<Task>> {
+        Ok(self
+            .data()
+            .trashed_list_tasks
+            .get(&list_id)
+            .map(|tasks| tasks.values().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    fn write_trashed_list_task(&self, list_id: Uuid, task: &Task) -> Result<()> {
+        self.data()
+            .trashed_list_tasks
+            .entry(list_id)
+            .or_default()
+            .insert(task.id, task.clone());
+        Ok(())
+    }
+
+    fn restore_trashed_list_task(&self, list_id: Uuid, task_id: Uuid) -> Result<()> {
+        let mut data = self.data();
+        let task = data
+            .trashed_list_tasks
+            .get_mut(&list_id)
+            .and_then(|tasks| tasks.remove(&task_id))
+            .ok_or(Error::Store(StoreError::TaskNotFound(task_id)))?;
+        data.tasks.entry(list_id).or_default().insert(task_id, task);
+        Ok(())
+    }
+
+    fn remove_trashed_list_task(&self, list_id: Uuid, task_id: Uuid) -> Result<()> {
+        self.data()
+            .trashed_list_tasks
+            .get_mut(&list_id)
+            .and_then(|tasks| tasks.remove(&task_id))
+            .map(|_| ())
+            .ok_or(Error::Store(StoreError::TaskNotFound(task_id)))
+    }
+
+    fn has_trashed_list_tasks(&self, list_id: Uuid) -> bool {
+        self.data()
+            .trashed_list_tasks
+            .get(&list_id)
+            .is_some_and(|tasks| !tasks.is_empty())
+    }
+
+    fn remove_trashed_list(&self, list_id: Uuid) -> Result<()> {
+        self.data().trashed_list_tasks.remove(&list_id);
+        Ok(())
+    }
+
+    fn read_revisions(&self, task_id: Uuid) -> Result<Vec<Revision>> {
+        Ok(self
+            .data()
+            .revisions
+            .get(&task_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn write_revisions(&self, task_id: Uuid, revisions: &[Revision]) -> Result<()> {
+        let mut data = self.data();
+        if revisions.is_empty() {
+            data.revisions.remove(&task_id);
+        } else {
+            data.revisions.insert(task_id, revisions.to_vec());
+        }
+        Ok(())
+    }
+}
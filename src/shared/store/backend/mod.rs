@@ -0,0 +1,145 @@
+//! Storage backends for [`Store`](super::Store).
+//!
+//! A backend only knows how to read and write the raw pieces of data the
+//! store is made of: the list and state registries, one record per task,
+//! and the trash. Everything built on top of that — read-modify-write
+//! cycles, ordering, consistency between registries — lives in the
+//! `ListStore`, `TaskStore`, `TrashStore` and `StateStore` views, so every
+//! backend behaves the same way to feature code.
+
+mod memory;
+mod ron_dir;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+pub use memory::MemoryBackend;
+pub use ron_dir::{pretty, RecordKind, RonDirBackend};
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteBackend;
+
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use super::change::Change;
+use super::history::Revision;
+use super::quarantine::QuarantinedItem;
+use crate::features::lists::list::{List, TrashedList};
+use crate::features::tasks::state::TaskState;
+use crate::features::tasks::task::{Task, TrashedTask};
+use crate::{Error, Result, StoreError};
+
+/// Held for the duration of a read-modify-write cycle. Dropping the guard
+/// releases the lock.
+pub trait LockGuard {}
+
+impl<T> LockGuard for T {}
+
+pub trait StorageBackend: std::fmt::Debug + Send + Sync {
+    /// Directory the data lives in, for backends that keep it on disk.
+    fn root(&self) -> Option<&Path>;
+
+    /// Whether a change at `path` is one this backend made itself, as
+    /// opposed to another window, process or tool.
+    fn is_echo(&self, path: &Path) -> bool {
+        let _ = path;
+        false
+    }
+
+    /// Works out which part of the store a changed path under [`root`]
+    /// belongs to. Returns `None` for paths the store does not care about,
+    /// such as lock or temporary files.
+    ///
+    /// [`root`]: StorageBackend::root
+    fn classify(&self, path: &Path) -> Option<Change> {
+        let _ = path;
+        Some(Change::Unknown)
+    }
+
+    /// Serializes read-modify-write cycles against other users of the same
+    /// data. Must be reentrant within a thread.
+    fn lock(&self) -> Result<Box<dyn LockGuard + '_>>;
+
+    /// Runs `f` so that either all of the writes it makes are kept or, if it
+    /// fails, none of them. Backends that cannot roll back just run it.
+    fn atomically(&self, f: &mut dyn FnMut() -> Result<()>) -> Result<()> {
+        f()
+    }
+
+    fn read_lists(&self) -> Result<Vec<List>>;
+    fn write_lists(&self, lists: &[List]) -> Result<()>;
+
+    /// Makes sure a list has somewhere to keep its tasks.
+    fn create_list(&self, list_id: Uuid) -> Result<()>;
+    /// Drops a list's task storage along with any tasks still in it.
+    fn remove_list(&self, list_id: Uuid) -> Result<()>;
+    fn has_list(&self, list_id: Uuid) -> bool;
+    /// Every list that has task storage, registered or not.
+    fn list_ids(&self) -> Result<Vec<Uuid>>;
+
+    /// Returns `None` when no states have been written yet.
+    fn read_states(&self) -> Result<Option<Vec<TaskState>>>;
+    fn write_states(&self, states: &[TaskState]) -> Result<()>;
+
+    fn read_task(&self, list_id: Uuid, task_id: Uuid) -> Result<Task>;
+    fn read_tasks(&self, list_id: Uuid) -> Result<Vec<Task>>;
+    fn write_task(&self, list_id: Uuid, task: &Task) -> Result<()>;
+    fn remove_task(&self, list_id: Uuid, task_id: Uuid) -> Result<()>;
+
+    fn read_trashed_tasks(&self) -> Result<Vec<TrashedTask>>;
+    fn write_trashed_task(&self, trashed: &TrashedTask) -> Result<()>;
+    fn remove_trashed_task(&self, task_id: Uuid) -> Result<()>;
+
+    fn read_trashed_lists(&self) -> Result<Vec<TrashedList>>;
+    fn write_trashed_lists(&self, lists: &[TrashedList]) -> Result<()>;
+
+    /// Moves the tasks of a list into the trash, keeping them grouped so the
+    /// list can be restored as a whole.
+    fn move_list_to_trash(&self, list_id: Uuid) -> Result<()>;
+    /// Moves the tasks of a trashed list back, creating an empty list if
+    /// none were kept.
+    fn move_list_from_trash(&self, list_id: Uuid) -> Result<()>;
+    fn read_trashed_list_tasks(&self, list_id: Uuid) -> Result<Vec<Task>>;
+    /// Puts a task into a trashed list, creating the list's trashed storage
+    /// if needed.
+    fn write_trashed_list_task(&self, list_id: Uuid, task: &Task) -> Result<()>;
+    /// Moves a single task of a trashed list back into the live list.
+    fn restore_trashed_list_task(&self, list_id: Uuid, task_id: Uuid) -> Result<()>;
+    fn remove_trashed_list_task(&self, list_id: Uuid, task_id: Uuid) -> Result<()>;
+    fn has_trashed_list_tasks(&self, list_id: Uuid) -> bool;
+    /// Drops whatever is left of a trashed list's tasks.
+    fn remove_trashed_list(&self, list_id: Uuid) -> Result<()>;
+
+    /// Earlier versions of a task, oldest first.
+    fn read_revisions(&self, task_id: Uuid) -> Result<Vec<Revision>>;
+    /// Replaces the history of a task. Writing none removes it.
+    fn write_revisions(&self, task_id: Uuid, revisions: &[Revision]) -> Result<()>;
+
+    /// Records that failed to parse and were set aside while reading.
+    /// Backends that cannot end up with unparseable records have none.
+    fn quarantined(&self) -> Result<Vec<QuarantinedItem>> {
+        Ok(vec![])
+    }
+
+    /// Parses a quarantined record again and, if it now holds up, moves it
+    /// back to where it was found. Returns its restored path. If it still
+    /// fails, the new parse error is recorded and returned.
+    fn restore_quarantined(&self, id: Uuid) -> Result<PathBuf> {
+        Err(Error::Store(StoreError::QuarantinedItemNotFound(id)))
+    }
+
+    fn discard_quarantined(&self, id: Uuid) -> Result<()> {
+        Err(Error::Store(StoreError::QuarantinedItemNotFound(id)))
+    }
+
+    /// The content of a quarantined record, as it was set aside.
+    fn quarantined_data(&self, id: Uuid) -> Result<String> {
+        Err(Error::Store(StoreError::QuarantinedItemNotFound(id)))
+    }
+
+    /// Takes over a record another backend set aside, so an import keeps it
+    /// among the damaged items. `item.origin` is where that backend found it.
+    fn import_quarantined(&self, _item: &QuarantinedItem, _data: &str) -> Result<()> {
+        Err(Error::Io(std::io::ErrorKind::Unsupported.into()))
+    }
+}
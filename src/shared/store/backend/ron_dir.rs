@@ -0,0 +1,717 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use jiff::Timestamp;
+use ron::error::SpannedError;
+use ron::ser::PrettyConfig;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{LockGuard, StorageBackend};
+use crate::features::lists::list::{List, TrashedList};
+use crate::features::tasks::state::TaskState;
+use crate::features::tasks::task::{Task, TrashedTask};
+use crate::shared::store::atomic::{self, Recovery};
+use crate::shared::store::change::Change;
+use crate::shared::store::echo::WriteLog;
+use crate::shared::store::history::Revision;
+use crate::shared::store::lock::StoreLock;
+use crate::shared::store::quarantine::QuarantinedItem;
+use crate::{Error, Result, StoreError};
+
+const LOCK_FILE: &str = ".lock";
+const LISTS_REGISTRY: &str = "lists.ron";
+const STATES_REGISTRY: &str = "states.ron";
+pub(super) const TRASH_DIR: &str = "_trash";
+const TRASHED_LISTS_REGISTRY: &str = "lists.ron";
+pub(super) const TRASHED_LISTS_DIR: &str = "lists";
+const QUARANTINE_DIR: &str = "_quarantine";
+const QUARANTINE_RECORD: &str = "error.ron";
+const HISTORY_DIR: &str = "_history";
+
+/// Formatting used for every file the directory layout holds.
+pub fn pretty() -> PrettyConfig {
+    PrettyConfig::new().depth_limit(6).struct_names(true)
+}
+
+/// What a file in the directory layout holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecordKind {
+    Lists,
+    States,
+    /// A task, either live or inside a trashed list.
+    Task,
+    TrashedTask,
+    TrashedLists,
+}
+
+/// The original on-disk layout: registries in `lists.ron` and `states.ron`,
+/// one `<task-id>.ron` file per task under `<list-id>/`, and the trash under
+/// `_trash/`.
+#[derive(Debug, Clone)]
+pub struct RonDirBackend {
+    base_dir: PathBuf,
+    writes: Arc<WriteLog>,
+}
+
+impl RonDirBackend {
+    pub fn open(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let base_dir = base_dir.as_ref().to_path_buf();
+        fs::create_dir_all(&base_dir)?;
+        let backend = Self {
+            base_dir,
+            writes: Arc::default(),
+        };
+        backend.recover()?;
+        Ok(backend)
+    }
+
+    /// Opens the layout without reading anything through the models, for
+    /// upgrading files they cannot read yet.
+    pub fn open_for_upgrade(base_dir: impl AsRef<Path>) -> Result<Self> {
+        let base_dir = base_dir.as_ref().to_path_buf();
+        fs::create_dir_all(&base_dir)?;
+        let backend = Self {
+            base_dir,
+            writes: Arc::default(),
+        };
+        backend.restore_registries()?;
+        Ok(backend)
+    }
+
+    /// Restores registries cut short by a crash from their backups, going
+    /// by whether they parse at all rather than as the models. One without
+    /// a usable backup is set aside, and
+    /// [`check_and_repair`](crate::shared::store::check::check_and_repair)
+    /// registers its lists again.
+    fn restore_registries(&self) -> Result<()> {
+        let _lock = self.lock()?;
+        for path in [
+            self.registry_path(),
+            self.states_registry_path(),
+            self.trashed_lists_registry_path(),
+        ] {
+            match atomic::recover(&path, |s| ron::from_str::<ron::Value>(s).is_ok())? {
+                Recovery::Intact => {}
+                Recovery::RestoredFromBackup => {
+                    tracing::warn!("{:?} was damaged and has been restored from backup", path)
+                }
+                Recovery::Unrecoverable => {
+                    tracing::error!("{:?} was damaged beyond repair and was set aside", path)
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Repairs registries left half-written by a crash or power loss. A
+    /// damaged registry is restored from its backup when possible; otherwise
+    /// it is rebuilt from the directories it describes.
+    fn recover(&self) -> Result<()> {
+        let _lock = self.lock()?;
+        let lists = atomic::recover(&self.registry_path(), |s| {
+            ron::from_str::<Vec<List>>(s).is_ok()
+        })?;
+        self.log_recovery(&self.registry_path(), lists);
+        if lists == Recovery::Unrecoverable {
+            let lists: Vec<List> = Self::list_dirs(&self.base_dir)?
+                .into_iter()
+                .map(List::recovered)
+                .collect();
+            self.write_lists(&lists)?;
+        }
+
+        let states = atomic::recover(&self.states_registry_path(), |s| {
+            ron::from_str::<Vec<TaskState>>(s).is_ok()
+        })?;
+        // An unrecoverable states registry is reseeded by `StateStore::load_all`.
+        self.log_recovery(&self.states_registry_path(), states);
+
+        let trashed = atomic::recover(&self.trashed_lists_registry_path(), |s| {
+            ron::from_str::<Vec<TrashedList>>(s).is_ok()
+        })?;
+        self.log_recovery(&self.trashed_lists_registry_path(), trashed);
+        if trashed == Recovery::Unrecoverable {
+            let lists: Vec<TrashedList> = Self::list_dirs(&self.trashed_lists_dir())?
+                .into_iter()
+                .map(|id| TrashedList::new(List::recovered(id)))
+                .collect();
+            self.write_trashed_lists(&lists)?;
+        }
+
+        Ok(())
+    }
+
+    /// Every data file in the layout along with what it holds. Registries
+    /// that have not been written yet are left out.
+    pub fn records(&self) -> Result<Vec<(RecordKind, PathBuf)>> {
+        let mut records = Vec::new();
+        for (kind, path) in [
+            (RecordKind::Lists, self.registry_path()),
+            (RecordKind::States, self.states_registry_path()),
+            (RecordKind::TrashedLists, self.trashed_lists_registry_path()),
+        ] {
+            if path.exists() {
+                records.push((kind, path));
+            }
+        }
+
+        for list_id in Self::list_dirs(&self.base_dir)? {
+            records
+                .extend(Self::ron_files(&self.list_dir(list_id))?.map(|p| (RecordKind::Task, p)));
+        }
+        if self.trash_dir().exists() {
+            records
+                .extend(Self::ron_files(&self.trash_dir())?.map(|p| (RecordKind::TrashedTask, p)));
+        }
+        for list_id in Self::list_dirs(&self.trashed_lists_dir())? {
+            records.extend(
+                Self::ron_files(&self.trashed_list_data_dir(list_id))?
+                    .map(|p| (RecordKind::Task, p)),
+            );
+        }
+        Ok(records)
+    }
+
+    /// Top-level entries that hold the data of the layout in `base_dir`,
+    /// relative to it: both registries, every list directory and the trash.
+    /// Only looks at names, so copies such as snapshots stay untouched.
+    pub fn data_entries(base_dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut entries: Vec<PathBuf> = [LISTS_REGISTRY, STATES_REGISTRY, TRASH_DIR]
+            .into_iter()
+            .filter(|name| base_dir.join(name).exists())
+            .map(PathBuf::from)
+            .collect();
+        entries.extend(
+            Self::list_dirs(base_dir)?
+                .into_iter()
+                .map(|id| PathBuf::from(id.to_string())),
+        );
+        Ok(entries)
+    }
+
+    fn ron_files(dir: &Path) -> Result<impl Iterator<Item = PathBuf>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("ron") {
+                files.push(path);
+            }
+        }
+        Ok(files.into_iter())
+    }
+
+    fn log_recovery(&self, path: &Path, recovery: Recovery) {
+        match recovery {
+            Recovery::Intact => {}
+            Recovery::RestoredFromBackup => {
+                tracing::warn!("{:?} was damaged and has been restored from backup", path)
+            }
+            Recovery::Unrecoverable => {
+                tracing::error!("{:?} was damaged beyond repair and has been rebuilt", path)
+            }
+        }
+    }
+
+    /// Returns the ids of every list data directory directly under `dir`.
+    fn list_dirs(dir: &Path) -> Result<Vec<Uuid>> {
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut ids = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            if let Some(id) = entry
+                .file_name()
+                .to_str()
+                .and_then(|n| Uuid::parse_str(n).ok())
+            {
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+
+    fn registry_path(&self) -> PathBuf {
+        self.base_dir.join(LISTS_REGISTRY)
+    }
+
+    fn states_registry_path(&self) -> PathBuf {
+        self.base_dir.join(STATES_REGISTRY)
+    }
+
+    fn trash_dir(&self) -> PathBuf {
+        self.base_dir.join(TRASH_DIR)
+    }
+
+    fn trashed_task_path(&self, task_id: Uuid) -> PathBuf {
+        self.trash_dir().join(format!("{task_id}.ron"))
+    }
+
+    fn trashed_lists_dir(&self) -> PathBuf {
+        self.trash_dir().join(TRASHED_LISTS_DIR)
+    }
+
+    fn trashed_lists_registry_path(&self) -> PathBuf {
+        self.trashed_lists_dir().join(TRASHED_LISTS_REGISTRY)
+    }
+
+    fn trashed_list_data_dir(&self, list_id: Uuid) -> PathBuf {
+        self.trashed_lists_dir().join(list_id.to_string())
+    }
+
+    fn list_dir(&self, list_id: Uuid) -> PathBuf {
+        self.base_dir.join(list_id.to_string())
+    }
+
+    fn task_path(&self, list_id: Uuid, task_id: Uuid) -> PathBuf {
+        self.list_dir(list_id).join(format!("{task_id}.ron"))
+    }
+
+    fn read_registry<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(ron::from_str(&content)?)
+    }
+
+    /// Reads every `.ron` file in `dir`. Files that do not parse are moved
+    /// to quarantine; files that cannot be read at all are skipped.
+    fn read_dir_records<T: DeserializeOwned>(
+        &self,
+        dir: &Path,
+        kind: RecordKind,
+    ) -> Result<Vec<T>> {
+        let mut records = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("ron") {
+                continue;
+            }
+            match fs::read_to_string(&path).map(|s| ron::from_str::<T>(&s)) {
+                Ok(Ok(record)) => records.push(record),
+                Ok(Err(e)) => {
+                    if let Err(err) = self.quarantine(kind, &path, &e) {
+                        tracing::error!("could not quarantine {:?}: {err}", path.file_name());
+                    }
+                }
+                Err(e) => tracing::error!("could not read {:?}: {e}", path.file_name()),
+            }
+        }
+        Ok(records)
+    }
+
+    fn quarantine_dir(&self) -> PathBuf {
+        self.base_dir.join(QUARANTINE_DIR)
+    }
+
+    fn history_path(&self, task_id: Uuid) -> PathBuf {
+        self.base_dir
+            .join(HISTORY_DIR)
+            .join(format!("{task_id}.ron"))
+    }
+
+    /// Moves a file that failed to parse into `_quarantine/<id>/`, next to
+    /// an `error.ron` describing where it came from and what was wrong.
+    ///
+    /// Only the removal from its original place is recorded as this
+    /// process's own write. What lands in quarantine is left for the watcher
+    /// to report, so every open window learns about the damaged item.
+    pub fn quarantine(
+        &self,
+        kind: RecordKind,
+        path: &Path,
+        error: &SpannedError,
+    ) -> Result<QuarantinedItem> {
+        let id = Uuid::new_v4();
+        let dir = self.quarantine_dir().join(id.to_string());
+        fs::create_dir_all(&dir)?;
+
+        let mut item = QuarantinedItem {
+            id,
+            kind,
+            origin: path
+                .strip_prefix(&self.base_dir)
+                .unwrap_or(path)
+                .to_path_buf(),
+            error: error.into(),
+            quarantined_at: Timestamp::now(),
+            path: PathBuf::new(),
+        };
+        item.path = dir.join(item.file_name());
+        Self::write_quarantine_record(&dir, &item)?;
+
+        self.writes.removed(path);
+        fs::rename(path, &item.path)?;
+        tracing::warn!(
+            "{:?} could not be parsed ({}) and was moved to {:?}",
+            path,
+            item.error,
+            dir
+        );
+        Ok(item)
+    }
+
+    fn read_quarantined(&self, id: Uuid) -> Result<QuarantinedItem> {
+        let dir = self.quarantine_dir().join(id.to_string());
+        let content = fs::read_to_string(dir.join(QUARANTINE_RECORD))
+            .map_err(|_| Error::Store(StoreError::QuarantinedItemNotFound(id)))?;
+        let mut item: QuarantinedItem = ron::from_str(&content)?;
+        item.path = dir.join(item.file_name());
+        Ok(item)
+    }
+
+    fn write_quarantine_record(dir: &Path, item: &QuarantinedItem) -> Result<()> {
+        let content = ron::ser::to_string_pretty(item, pretty())?;
+        atomic::write(&dir.join(QUARANTINE_RECORD), content)?;
+        Ok(())
+    }
+
+    /// Parses `content` as whatever `kind` says it holds.
+    fn check(kind: RecordKind, content: &str) -> std::result::Result<(), SpannedError> {
+        match kind {
+            RecordKind::Lists => ron::from_str::<Vec<List>>(content).map(drop),
+            RecordKind::States => ron::from_str::<Vec<TaskState>>(content).map(drop),
+            RecordKind::Task => ron::from_str::<Task>(content).map(drop),
+            RecordKind::TrashedTask => ron::from_str::<TrashedTask>(content).map(drop),
+            RecordKind::TrashedLists => ron::from_str::<Vec<TrashedList>>(content).map(drop),
+        }
+    }
+
+    fn dir_has_ron_files(dir: &Path) -> bool {
+        fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .any(|e| e.path().extension().and_then(|x| x.to_str()) == Some("ron"))
+            })
+            .unwrap_or(false)
+    }
+}
+
+impl StorageBackend for RonDirBackend {
+    fn root(&self) -> Option<&Path> {
+        Some(&self.base_dir)
+    }
+
+    fn is_echo(&self, path: &Path) -> bool {
+        self.writes.is_echo(path)
+    }
+
+    fn classify(&self, path: &Path) -> Option<Change> {
+        let relative = path.strip_prefix(&self.base_dir).ok()?;
+        let parts: Vec<&str> = relative.iter().filter_map(|p| p.to_str()).collect();
+        let is_record = |name: &str| name.ends_with(".ron");
+
+        match parts.as_slice() {
+            [] => Some(Change::Unknown),
+            [LISTS_REGISTRY] => Some(Change::Lists),
+            [STATES_REGISTRY] => Some(Change::States),
+            [TRASH_DIR, ..] => Some(Change::Trash),
+            [QUARANTINE_DIR, ..] => Some(Change::Quarantine),
+            [dir] => Uuid::parse_str(dir).ok().map(Change::List),
+            [dir, file] if is_record(file) => {
+                let list_id = Uuid::parse_str(dir).ok()?;
+                let task_id = Uuid::parse_str(file.trim_end_matches(".ron")).ok()?;
+                Some(Change::Task { list_id, task_id })
+            }
+            _ => None,
+        }
+    }
+
+    fn lock(&self) -> Result<Box<dyn LockGuard + '_>> {
+        Ok(Box::new(StoreLock::acquire(
+            &self.base_dir.join(LOCK_FILE),
+        )?))
+    }
+
+    fn read_lists(&self) -> Result<Vec<List>> {
+        Self::read_registry(&self.registry_path())
+    }
+
+    fn write_lists(&self, lists: &[List]) -> Result<()> {
+        let path = self.registry_path();
+        let content = ron::ser::to_string_pretty(lists, pretty())?;
+        self.writes.wrote(&path, &content);
+        atomic::write_with_backup(&path, content)?;
+        Ok(())
+    }
+
+    fn create_list(&self, list_id: Uuid) -> Result<()> {
+        let list_dir = self.list_dir(list_id);
+        if !list_dir.exists() {
+            self.writes.created_dir(&list_dir);
+            fs::create_dir_all(&list_dir)?;
+        }
+        Ok(())
+    }
+
+    fn remove_list(&self, list_id: Uuid) -> Result<()> {
+        let list_dir = self.list_dir(list_id);
+        if list_dir.exists() {
+            self.writes.removed(&list_dir);
+            fs::remove_dir_all(&list_dir)?;
+        }
+        Ok(())
+    }
+
+    fn has_list(&self, list_id: Uuid) -> bool {
+        self.list_dir(list_id).exists()
+    }
+
+    fn list_ids(&self) -> Result<Vec<Uuid>> {
+        Self::list_dirs(&self.base_dir)
+    }
+
+    fn read_states(&self) -> Result<Option<Vec<TaskState>>> {
+        let path = self.states_registry_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)?;
+        Ok(Some(ron::from_str(&content)?))
+    }
+
+    fn write_states(&self, states: &[TaskState]) -> Result<()> {
+        let path = self.states_registry_path();
+        let content = ron::ser::to_string_pretty(states, pretty())?;
+        self.writes.wrote(&path, &content);
+        atomic::write_with_backup(&path, content)?;
+        Ok(())
+    }
+
+    fn read_task(&self, list_id: Uuid, task_id: Uuid) -> Result<Task> {
+        let path = self.task_path(list_id, task_id);
+        let content = fs::read_to_string(&path)
+            .map_err(|_| Error::Store(StoreError::TaskNotFound(task_id)))?;
+        ron::from_str(&content).map_err(|e| {
+            if let Err(err) = self.quarantine(RecordKind::Task, &path, &e) {
+                tracing::error!("could not quarantine {:?}: {err}", path.file_name());
+            }
+            e.into()
+        })
+    }
+
+    fn read_tasks(&self, list_id: Uuid) -> Result<Vec<Task>> {
+        self.read_dir_records(&self.list_dir(list_id), RecordKind::Task)
+    }
+
+    fn write_task(&self, list_id: Uuid, task: &Task) -> Result<()> {
+        let path = self.task_path(list_id, task.id);
+        let content = ron::ser::to_string_pretty(task, pretty())?;
+        self.writes.wrote(&path, &content);
+        atomic::write(&path, content)?;
+        Ok(())
+    }
+
+    fn remove_task(&self, list_id: Uuid, task_id: Uuid) -> Result<()> {
+        let path = self.task_path(list_id, task_id);
+        self.writes.removed(&path);
+        fs::remove_file(&path).map_err(|_| Error::Store(StoreError::TaskNotFound(task_id)))
+    }
+
+    fn read_trashed_tasks(&self) -> Result<Vec<TrashedTask>> {
+        let trash_dir = self.trash_dir();
+        if !trash_dir.exists() {
+            return Ok(vec![]);
+        }
+        self.read_dir_records(&trash_dir, RecordKind::TrashedTask)
+    }
+
+    fn write_trashed_task(&self, trashed: &TrashedTask) -> Result<()> {
+        fs::create_dir_all(self.trash_dir())?;
+        let path = self.trashed_task_path(trashed.task.id);
+        let content = ron::ser::to_string_pretty(trashed, pretty())?;
+        self.writes.wrote(&path, &content);
+        atomic::write(&path, content)?;
+        Ok(())
+    }
+
+    fn remove_trashed_task(&self, task_id: Uuid) -> Result<()> {
+        let path = self.trashed_task_path(task_id);
+        self.writes.removed(&path);
+        fs::remove_file(&path).map_err(|_| Error::Store(StoreError::TaskNotFound(task_id)))
+    }
+
+    fn read_trashed_lists(&self) -> Result<Vec<TrashedList>> {
+        Self::read_registry(&self.trashed_lists_registry_path())
+    }
+
+    fn write_trashed_lists(&self, lists: &[TrashedList]) -> Result<()> {
+        fs::create_dir_all(self.trashed_lists_dir())?;
+        let path = self.trashed_lists_registry_path();
+        let content = ron::ser::to_string_pretty(lists, pretty())?;
+        self.writes.wrote(&path, &content);
+        atomic::write_with_backup(&path, content)?;
+        Ok(())
+    }
+
+    fn move_list_to_trash(&self, list_id: Uuid) -> Result<()> {
+        fs::create_dir_all(self.trashed_lists_dir())?;
+        let list_dir = self.list_dir(list_id);
+        if list_dir.exists() {
+            let data_dir = self.trashed_list_data_dir(list_id);
+            self.writes.removed(&list_dir);
+            self.writes.created_dir(&data_dir);
+            fs::rename(&list_dir, data_dir)?;
+        }
+        Ok(())
+    }
+
+    fn move_list_from_trash(&self, list_id: Uuid) -> Result<()> {
+        let data_dir = self.trashed_list_data_dir(list_id);
+        let list_dir = self.list_dir(list_id);
+        self.writes.created_dir(&list_dir);
+        if data_dir.exists() {
+            self.writes.removed(&data_dir);
+            fs::rename(&data_dir, &list_dir)?;
+        } else {
+            fs::create_dir_all(&list_dir)?;
+        }
+        Ok(())
+    }
+
+    fn read_trashed_list_tasks(&self, list_id: Uuid) -> Result<Vec<Task>> {
+        let data_dir = self.trashed_list_data_dir(list_id);
+        if !data_dir.exists() {
+            return Ok(vec![]);
+        }
+        self.read_dir_records(&data_dir, RecordKind::Task)
+    }
+
+    fn write_trashed_list_task(&self, list_id: Uuid, task: &Task) -> Result<()> {
+        let data_dir = self.trashed_list_data_dir(list_id);
+        if !data_dir.exists() {
+            self.writes.created_dir(&data_dir);
+            fs::create_dir_all(&data_dir)?;
+        }
+        let path = data_dir.join(format!("{}.ron", task.id));
+        let content = ron::ser::to_string_pretty(task, pretty())?;
+        self.writes.wrote(&path, &content);
+        atomic::write(&path, content)?;
+        Ok(())
+    }
+
+    fn restore_trashed_list_task(&self, list_id: Uuid, task_id: Uuid) -> Result<()> {
+        let src = self
+            .trashed_list_data_dir(list_id)
+            .join(format!("{task_id}.ron"));
+        let dest = self.task_path(list_id, task_id);
+        self.writes.removed(&src);
+        fs::rename(&src, &dest).map_err(|_| Error::Store(StoreError::TaskNotFound(task_id)))?;
+        self.writes.settled(&dest);
+        Ok(())
+    }
+
+    fn remove_trashed_list_task(&self, list_id: Uuid, task_id: Uuid) -> Result<()> {
+        let path = self
+            .trashed_list_data_dir(list_id)
+            .join(format!("{task_id}.ron"));
+        self.writes.removed(&path);
+        fs::remove_file(&path).map_err(|_| Error::Store(StoreError::TaskNotFound(task_id)))
+    }
+
+    fn has_trashed_list_tasks(&self, list_id: Uuid) -> bool {
+        Self::dir_has_ron_files(&self.trashed_list_data_dir(list_id))
+    }
+
+    fn remove_trashed_list(&self, list_id: Uuid) -> Result<()> {
+        let data_dir = self.trashed_list_data_dir(list_id);
+        if data_dir.exists() {
+            self.writes.removed(&data_dir);
+            fs::remove_dir_all(&data_dir)?;
+        }
+        Ok(())
+    }
+
+    fn read_revisions(&self, task_id: Uuid) -> Result<Vec<Revision>> {
+        Self::read_registry(&self.history_path(task_id))
+    }
+
+    fn write_revisions(&self, task_id: Uuid, revisions: &[Revision]) -> Result<()> {
+        let path = self.history_path(task_id);
+        if revisions.is_empty() {
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+            return Ok(());
+        }
+        fs::create_dir_all(self.base_dir.join(HISTORY_DIR))?;
+        let content = ron::ser::to_string_pretty(revisions, pretty())?;
+        atomic::write(&path, content)?;
+        Ok(())
+    }
+
+    fn quarantined(&self) -> Result<Vec<QuarantinedItem>> {
+        let dir = self.quarantine_dir();
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut items = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let Some(id) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| Uuid::parse_str(name).ok())
+            else {
+                continue;
+            };
+            match self.read_quarantined(id) {
+                Ok(item) => items.push(item),
+                Err(e) => tracing::error!("could not read quarantined item {id}: {e}"),
+            }
+        }
+        items.sort_by_key(|item| item.quarantined_at);
+        Ok(items)
+    }
+
+    fn restore_quarantined(&self, id: Uuid) -> Result<PathBuf> {
+        let mut item = self.read_quarantined(id)?;
+        let content = fs::read_to_string(&item.path)?;
+        if let Err(e) = Self::check(item.kind, &content) {
+            item.error = (&e).into();
+            Self::write_quarantine_record(&self.quarantine_dir().join(id.to_string()), &item)?;
+            return Err(e.into());
+        }
+
+        let dest = self.base_dir.join(&item.origin);
+        if let Some(parent) = dest.parent() {
+            if item.kind == RecordKind::TrashedTask {
+                fs::create_dir_all(parent)?;
+            } else if !parent.exists() {
+                let list_id = parent
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| Uuid::parse_str(name).ok())
+                    .unwrap_or_default();
+                return Err(Error::Store(StoreError::ListNotFound(list_id)));
+            }
+        }
+        // Not recorded either: the watcher picks the record up like any
+        // other change, in this window as well as in others.
+        fs::rename(&item.path, &dest)?;
+        fs::remove_dir_all(self.quarantine_dir().join(id.to_string()))?;
+        Ok(dest)
+    }
+
+    fn discard_quarantined(&self, id: Uuid) -> Result<()> {
+        let dir = self.quarantine_dir().join(id.to_string());
+        if !dir.exists() {
+            return Err(Error::Store(StoreError::QuarantinedItemNotFound(id)));
+        }
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    fn quarantined_data(&self, id: Uuid) -> Result<String> {
+        Ok(fs::read_to_string(self.read_quarantined(id)?.path)?)
+    }
+}
@@ -0,0 +1,997 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use jiff::Timestamp;
+use ron::error::SpannedError;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::ron_dir::{TRASHED_LISTS_DIR, TRASH_DIR};
+use super::{LockGuard, RecordKind, StorageBackend};
+use crate::features::lists::list::{List, TrashedList};
+use crate::features::tasks::state::TaskState;
+use crate::features::tasks::task::{Task, TrashedTask};
+use crate::shared::store::change::Change;
+use crate::shared::store::history::Revision;
+use crate::shared::store::lock::StoreLock;
+use crate::shared::store::quarantine::QuarantinedItem;
+use crate::{Error, Result, StoreError};
+
+/// Records are kept as RON in a `data` column so the schema does not have to
+/// follow every field added to a model. Columns that queries filter or sort
+/// on are copied out next to it and indexed.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS lists (
+        id TEXT PRIMARY KEY,
+        position INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS list_storage (
+        list_id TEXT PRIMARY KEY
+    );
+    CREATE TABLE IF NOT EXISTS states (
+        id TEXT PRIMARY KEY,
+        position INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS tasks (
+        list_id TEXT NOT NULL,
+        id TEXT NOT NULL,
+        parent_id TEXT,
+        favorite INTEGER NOT NULL,
+        today INTEGER NOT NULL,
+        due_date TEXT,
+        reminder_at INTEGER,
+        data TEXT NOT NULL,
+        PRIMARY KEY (list_id, id)
+    );
+    CREATE INDEX IF NOT EXISTS tasks_parent ON tasks (parent_id);
+    CREATE INDEX IF NOT EXISTS tasks_favorite ON tasks (favorite) WHERE favorite = 1;
+    CREATE INDEX IF NOT EXISTS tasks_today ON tasks (today) WHERE today = 1;
+    CREATE INDEX IF NOT EXISTS tasks_due_date ON tasks (due_date) WHERE due_date IS NOT NULL;
+    CREATE INDEX IF NOT EXISTS tasks_reminder ON tasks (reminder_at) WHERE reminder_at IS NOT NULL;
+    CREATE TABLE IF NOT EXISTS trashed_tasks (
+        id TEXT PRIMARY KEY,
+        original_list_id TEXT NOT NULL,
+        deleted_at INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS trashed_tasks_deleted_at ON trashed_tasks (deleted_at);
+    CREATE TABLE IF NOT EXISTS trashed_lists (
+        id TEXT PRIMARY KEY,
+        position INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS trashed_list_tasks (
+        list_id TEXT NOT NULL,
+        id TEXT NOT NULL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (list_id, id)
+    );
+    CREATE TABLE IF NOT EXISTS task_revisions (
+        task_id TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS quarantine (
+        id TEXT PRIMARY KEY,
+        record TEXT NOT NULL,
+        data TEXT NOT NULL
+    );
+";
+
+const IMPORTED_KEY: &str = "imported_from_ron";
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+fn encode<T: Serialize>(value: &T) -> Result<String> {
+    Ok(ron::to_string(value)?)
+}
+
+fn decode<T: DeserializeOwned>(data: &str) -> Result<T> {
+    Ok(ron::from_str(data)?)
+}
+
+/// What a row of `table` holds, and the columns that tell it apart from the
+/// other rows, joined by `/`.
+fn table_layout(table: &str) -> (RecordKind, &'static str) {
+    match table {
+        "lists" => (RecordKind::Lists, "id"),
+        "states" => (RecordKind::States, "id"),
+        "trashed_lists" => (RecordKind::TrashedLists, "id"),
+        "trashed_tasks" => (RecordKind::TrashedTask, "id"),
+        _ => (RecordKind::Task, "list_id || '/' || id"),
+    }
+}
+
+/// Where a record the directory layout set aside at `origin` would have
+/// been in the database: the table and keys it is restored by.
+fn origin_in_database(kind: RecordKind, origin: &Path) -> PathBuf {
+    let parts: Vec<&str> = origin.iter().filter_map(|part| part.to_str()).collect();
+    let id = origin
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    match (kind, parts.as_slice()) {
+        (RecordKind::Lists, _) => PathBuf::from("lists"),
+        (RecordKind::States, _) => PathBuf::from("states"),
+        (RecordKind::TrashedLists, _) => PathBuf::from("trashed_lists"),
+        (RecordKind::TrashedTask, _) => Path::new("trashed_tasks").join(id),
+        (RecordKind::Task, [TRASH_DIR, TRASHED_LISTS_DIR, list_id, _]) => {
+            Path::new("trashed_list_tasks").join(list_id).join(id)
+        }
+        (RecordKind::Task, [list_id, _]) => Path::new("tasks").join(list_id).join(id),
+        (RecordKind::Task, _) => origin.to_path_buf(),
+    }
+}
+
+/// Position after the last row of a registry table.
+fn next_position(conn: &Connection, table: &str) -> Result<i64> {
+    Ok(conn.query_row(
+        &format!("SELECT COALESCE(MAX(position) + 1, 0) FROM {table}"),
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+fn replace_lists(conn: &Connection, lists: &[List]) -> Result<()> {
+    conn.execute("DELETE FROM lists", [])?;
+    for (position, list) in lists.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO lists (id, position, data) VALUES (?1, ?2, ?3)",
+            params![list.id.to_string(), position as i64, encode(list)?],
+        )?;
+    }
+    Ok(())
+}
+
+fn replace_states(conn: &Connection, states: &[TaskState]) -> Result<()> {
+    conn.execute("DELETE FROM states", [])?;
+    for (position, state) in states.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO states (id, position, data) VALUES (?1, ?2, ?3)",
+            params![state.id.to_string(), position as i64, encode(state)?],
+        )?;
+    }
+    Ok(())
+}
+
+fn replace_trashed_lists(conn: &Connection, lists: &[TrashedList]) -> Result<()> {
+    conn.execute("DELETE FROM trashed_lists", [])?;
+    for (position, trashed) in lists.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO trashed_lists (id, position, data) VALUES (?1, ?2, ?3)",
+            params![
+                trashed.list.id.to_string(),
+                position as i64,
+                encode(trashed)?
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes a task row along with the columns copied out of it for indexing.
+fn insert_task(conn: &Connection, list_id: Uuid, task: &Task) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO tasks
+            (list_id, id, parent_id, favorite, today, due_date, reminder_at, data)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            list_id.to_string(),
+            task.id.to_string(),
+            task.parent_id.map(|id| id.to_string()),
+            task.favorite,
+            task.today,
+            task.due_date.as_ref().map(|due| due.date.to_string()),
+            task.reminder_date.map(|at| at.as_second()),
+            encode(task)?,
+        ],
+    )?;
+    Ok(())
+}
+
+fn insert_trashed_task(conn: &Connection, trashed: &TrashedTask) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO trashed_tasks (id, original_list_id, deleted_at, data)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![
+            trashed.task.id.to_string(),
+            trashed.original_list_id.to_string(),
+            trashed.deleted_at.as_second(),
+            encode(trashed)?,
+        ],
+    )?;
+    Ok(())
+}
+
+fn insert_trashed_list_task(conn: &Connection, list_id: Uuid, task: &Task) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO trashed_list_tasks (list_id, id, data) VALUES (?1, ?2, ?3)",
+        params![list_id.to_string(), task.id.to_string(), encode(task)?],
+    )?;
+    Ok(())
+}
+
+/// Reads `(key, data)` pairs, for queries selecting exactly those two columns.
+fn read_rows(conn: &Connection, sql: &str) -> Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(rows)
+}
+
+fn set_schema_version(conn: &Connection, version: u32) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+        params![SCHEMA_VERSION_KEY, version.to_string()],
+    )?;
+    Ok(())
+}
+
+/// Runs a registry stored one row per entry through `upgrade` as a whole,
+/// the way it is laid out in its RON file. Returns the upgraded entries if
+/// anything changed.
+fn upgrade_registry<T: Serialize + DeserializeOwned>(
+    rows: &[(String, String)],
+    kind: RecordKind,
+    upgrade: &mut impl FnMut(RecordKind, &str) -> Result<String>,
+) -> Result<Option<Vec<T>>> {
+    if rows.is_empty() {
+        return Ok(None);
+    }
+    let data: Vec<&str> = rows.iter().map(|(_, data)| data.as_str()).collect();
+    let content = format!("[{}]", data.join(","));
+    let entries: Vec<T> = decode(&upgrade(kind, &content)?)?;
+    let unchanged = entries.len() == rows.len()
+        && entries
+            .iter()
+            .zip(&data)
+            .all(|(entry, data)| encode(entry).is_ok_and(|encoded| encoded == *data));
+    Ok((!unchanged).then_some(entries))
+}
+
+/// Runs one record through `upgrade`, returning it if anything changed. A
+/// record that cannot be upgraded is logged and left as it is, to be set
+/// aside when it is read.
+fn upgrade_row<T: Serialize + DeserializeOwned>(
+    data: &str,
+    kind: RecordKind,
+    upgrade: &mut impl FnMut(RecordKind, &str) -> Result<String>,
+) -> Option<T> {
+    let record = upgrade(kind, data).and_then(|upgraded| {
+        let record: T = decode(&upgraded)?;
+        Ok((encode(&record)? != data).then_some(record))
+    });
+    record.unwrap_or_else(|e| {
+        tracing::error!("Failed to upgrade {:?} record: {}", kind, e);
+        None
+    })
+}
+
+fn parse_id(id: &str) -> Option<Uuid> {
+    Uuid::parse_str(id)
+        .inspect_err(|e| tracing::error!("Skipping row with invalid id {:?}: {}", id, e))
+        .ok()
+}
+
+/// Keeps the whole store in a single SQLite database. Scales better than
+/// [`RonDirBackend`](super::RonDirBackend) for large collections, since
+/// loading a list is one indexed query instead of a directory walk.
+#[derive(Debug)]
+pub struct SqliteBackend {
+    path: PathBuf,
+    root: PathBuf,
+    conn: Mutex<Connection>,
+    /// Last seen `PRAGMA data_version`, which only moves when another
+    /// connection commits.
+    data_version: Mutex<i64>,
+}
+
+impl SqliteBackend {
+    /// Opens the database at `path`, creating it and its tables if needed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let root = path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+        std::fs::create_dir_all(&root)?;
+
+        let conn = Connection::open(&path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        conn.execute_batch(SCHEMA)?;
+        let data_version = conn.pragma_query_value(None, "data_version", |row| row.get(0))?;
+
+        Ok(Self {
+            path,
+            root,
+            conn: Mutex::new(conn),
+            data_version: Mutex::new(data_version),
+        })
+    }
+
+    /// Whether the RON layout has already been imported into this database.
+    pub fn is_imported(&self) -> Result<bool> {
+        let value: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![IMPORTED_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value.is_some())
+    }
+
+    pub fn mark_imported(&self) -> Result<()> {
+        self.conn().execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+            params![IMPORTED_KEY, jiff::Timestamp::now().to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// The schema version the records were last upgraded to, or `None` for a
+    /// database from before it was recorded.
+    pub fn schema_version(&self) -> Result<Option<u32>> {
+        let value: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![SCHEMA_VERSION_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        value
+            .map(|value| {
+                value.trim().parse().map_err(|_| {
+                    StoreError::InvalidSchemaVersion(self.path.clone(), value.clone()).into()
+                })
+            })
+            .transpose()
+    }
+
+    pub fn set_schema_version(&self, version: u32) -> Result<()> {
+        set_schema_version(&self.conn(), version)
+    }
+
+    /// Passes every record through `upgrade`, which gets and returns RON as
+    /// [`SchemaMigrator`](crate::shared::store::migrations::SchemaMigrator)
+    /// steps do, and records `version` in the same transaction. Returns how
+    /// many records changed.
+    pub fn upgrade_records(
+        &self,
+        version: u32,
+        mut upgrade: impl FnMut(RecordKind, &str) -> Result<String>,
+    ) -> Result<usize> {
+        let mut conn = self.conn();
+        let tx = conn.savepoint()?;
+        let mut rewritten = 0;
+
+        let rows = read_rows(&tx, "SELECT id, data FROM lists ORDER BY position")?;
+        if let Some(lists) = upgrade_registry::<List>(&rows, RecordKind::Lists, &mut upgrade)? {
+            replace_lists(&tx, &lists)?;
+            rewritten += 1;
+        }
+        let rows = read_rows(&tx, "SELECT id, data FROM states ORDER BY position")?;
+        if let Some(states) =
+            upgrade_registry::<TaskState>(&rows, RecordKind::States, &mut upgrade)?
+        {
+            replace_states(&tx, &states)?;
+            rewritten += 1;
+        }
+        let rows = read_rows(&tx, "SELECT id, data FROM trashed_lists ORDER BY position")?;
+        if let Some(lists) =
+            upgrade_registry::<TrashedList>(&rows, RecordKind::TrashedLists, &mut upgrade)?
+        {
+            replace_trashed_lists(&tx, &lists)?;
+            rewritten += 1;
+        }
+
+        for (list_id, data) in read_rows(&tx, "SELECT list_id, data FROM tasks")? {
+            let Some(list_id) = parse_id(&list_id) else {
+                continue;
+            };
+            if let Some(task) = upgrade_row::<Task>(&data, RecordKind::Task, &mut upgrade) {
+                insert_task(&tx, list_id, &task)?;
+                rewritten += 1;
+            }
+        }
+        for (_, data) in read_rows(&tx, "SELECT id, data FROM trashed_tasks")? {
+            if let Some(trashed) =
+                upgrade_row::<TrashedTask>(&data, RecordKind::TrashedTask, &mut upgrade)
+            {
+                insert_trashed_task(&tx, &trashed)?;
+                rewritten += 1;
+            }
+        }
+        for (list_id, data) in read_rows(&tx, "SELECT list_id, data FROM trashed_list_tasks")? {
+            let Some(list_id) = parse_id(&list_id) else {
+                continue;
+            };
+            if let Some(task) = upgrade_row::<Task>(&data, RecordKind::Task, &mut upgrade) {
+                insert_trashed_list_task(&tx, list_id, &task)?;
+                rewritten += 1;
+            }
+        }
+
+        set_schema_version(&tx, version)?;
+        tx.commit()?;
+        Ok(rewritten)
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_path(&self) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".lock");
+        self.path.with_file_name(name)
+    }
+
+    /// Reads the records `sql` selects from `table`, as `rowid, data` pairs.
+    /// Rows that do not parse are moved to quarantine.
+    fn read_records<T: DeserializeOwned>(
+        &self,
+        table: &str,
+        sql: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<T>> {
+        let mut conn = self.conn();
+        let mut records = Vec::new();
+        let mut damaged = Vec::new();
+        {
+            let mut stmt = conn.prepare_cached(sql)?;
+            let rows = stmt.query_map(params, |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })?;
+            for row in rows {
+                let (rowid, data) = row?;
+                match ron::from_str(&data) {
+                    Ok(record) => records.push(record),
+                    Err(e) => damaged.push((rowid, data, e)),
+                }
+            }
+        }
+        for (rowid, data, error) in damaged {
+            if let Err(err) = self.quarantine(&mut conn, table, rowid, &data, &error) {
+                tracing::error!("could not quarantine a row of {table}: {err}");
+            }
+        }
+        Ok(records)
+    }
+
+    /// Moves a row that failed to parse out of `table` and into the
+    /// `quarantine` table, along with where it came from and what was wrong.
+    fn quarantine(
+        &self,
+        conn: &mut Connection,
+        table: &str,
+        rowid: i64,
+        data: &str,
+        error: &SpannedError,
+    ) -> Result<QuarantinedItem> {
+        let (kind, key) = table_layout(table);
+        let tx = conn.savepoint()?;
+        let key: String = tx.query_row(
+            &format!("SELECT {key} FROM {table} WHERE rowid = ?1"),
+            params![rowid],
+            |row| row.get(0),
+        )?;
+        let item = QuarantinedItem {
+            id: Uuid::new_v4(),
+            kind,
+            origin: Path::new(table).join(key),
+            error: error.into(),
+            quarantined_at: Timestamp::now(),
+            path: self.path.clone(),
+        };
+        tx.execute(
+            "INSERT INTO quarantine (id, record, data) VALUES (?1, ?2, ?3)",
+            params![item.id.to_string(), encode(&item)?, data],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM {table} WHERE rowid = ?1"),
+            params![rowid],
+        )?;
+        tx.commit()?;
+        tracing::warn!(
+            "{:?} in {:?} could not be parsed ({}) and was quarantined",
+            item.origin,
+            self.path,
+            item.error
+        );
+        Ok(item)
+    }
+
+    fn read_quarantined(&self, id: Uuid) -> Result<(QuarantinedItem, String)> {
+        let row: Option<(String, String)> = self
+            .conn()
+            .query_row(
+                "SELECT record, data FROM quarantine WHERE id = ?1",
+                params![id.to_string()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let (record, data) = row.ok_or(Error::Store(StoreError::QuarantinedItemNotFound(id)))?;
+        let mut item: QuarantinedItem = decode(&record)?;
+        item.path = self.path.clone();
+        Ok((item, data))
+    }
+
+    fn exists(&self, sql: &str, params: impl rusqlite::Params) -> bool {
+        self.conn()
+            .query_row(sql, params, |_| Ok(()))
+            .optional()
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to query {:?}: {}", self.path, e);
+                None
+            })
+            .is_some()
+    }
+}
+
+impl StorageBackend for SqliteBackend {
+    fn root(&self) -> Option<&Path> {
+        Some(&self.root)
+    }
+
+    fn is_echo(&self, path: &Path) -> bool {
+        if self.classify(path).is_none() {
+            return false;
+        }
+        let version: i64 = match self
+            .conn()
+            .pragma_query_value(None, "data_version", |row| row.get(0))
+        {
+            Ok(version) => version,
+            Err(e) => {
+                tracing::error!("Failed to query {:?}: {}", self.path, e);
+                return false;
+            }
+        };
+        let mut seen = self
+            .data_version
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        std::mem::replace(&mut *seen, version) == version
+    }
+
+    /// Any write to the database or its journal may have touched anything.
+    fn classify(&self, path: &Path) -> Option<Change> {
+        let name = path.file_name()?.to_str()?;
+        let db_name = self.path.file_name()?.to_str()?;
+        let is_database = name == db_name
+            || name
+                .strip_prefix(db_name)
+                .is_some_and(|suffix| suffix == "-wal" || suffix == "-journal");
+        is_database.then_some(Change::Unknown)
+    }
+
+    /// SQLite serializes individual statements, but the store's cycles span
+    /// several of them, so they take the same advisory lock file the RON
+    /// backend uses.
+    fn lock(&self) -> Result<Box<dyn LockGuard + '_>> {
+        Ok(Box::new(StoreLock::acquire(&self.lock_path())?))
+    }
+
+    fn atomically(&self, f: &mut dyn FnMut() -> Result<()>) -> Result<()> {
+        self.conn().execute_batch("BEGIN IMMEDIATE")?;
+        // The methods `f` calls nest savepoints inside this transaction.
+        match f() {
+            Ok(()) => {
+                self.conn().execute_batch("COMMIT")?;
+                Ok(())
+            }
+            Err(e) => {
+                if let Err(err) = self.conn().execute_batch("ROLLBACK") {
+                    tracing::error!("Failed to roll back {:?}: {}", self.path, err);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    fn read_lists(&self) -> Result<Vec<List>> {
+        self.read_records(
+            "lists",
+            "SELECT rowid, data FROM lists ORDER BY position",
+            [],
+        )
+    }
+
+    fn write_lists(&self, lists: &[List]) -> Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.savepoint()?;
+        replace_lists(&tx, lists)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn create_list(&self, list_id: Uuid) -> Result<()> {
+        self.conn().execute(
+            "INSERT OR IGNORE INTO list_storage (list_id) VALUES (?1)",
+            params![list_id.to_string()],
+        )?;
+        Ok(())
+    }
+
+    fn remove_list(&self, list_id: Uuid) -> Result<()> {
+        let id = list_id.to_string();
+        let mut conn = self.conn();
+        let tx = conn.savepoint()?;
+        tx.execute("DELETE FROM tasks WHERE list_id = ?1", params![id])?;
+        tx.execute("DELETE FROM list_storage WHERE list_id = ?1", params![id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn has_list(&self, list_id: Uuid) -> bool {
+        self.exists(
+            "SELECT 1 FROM list_storage WHERE list_id = ?1",
+            params![list_id.to_string()],
+        )
+    }
+
+    fn list_ids(&self) -> Result<Vec<Uuid>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare_cached("SELECT list_id FROM list_storage")?;
+        let ids = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .filter_map(|id| id.ok().and_then(|id| Uuid::parse_str(&id).ok()))
+            .collect();
+        Ok(ids)
+    }
+
+    fn read_states(&self) -> Result<Option<Vec<TaskState>>> {
+        let states: Vec<TaskState> = self.read_records(
+            "states",
+            "SELECT rowid, data FROM states ORDER BY position",
+            [],
+        )?;
+        if states.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(states))
+    }
+
+    fn write_states(&self, states: &[TaskState]) -> Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.savepoint()?;
+        replace_states(&tx, states)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn read_task(&self, list_id: Uuid, task_id: Uuid) -> Result<Task> {
+        let data: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT data FROM tasks WHERE list_id = ?1 AND id = ?2",
+                params![list_id.to_string(), task_id.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        match data {
+            Some(data) => decode(&data),
+            None => Err(Error::Store(StoreError::TaskNotFound(task_id))),
+        }
+    }
+
+    fn read_tasks(&self, list_id: Uuid) -> Result<Vec<Task>> {
+        self.read_records(
+            "tasks",
+            "SELECT rowid, data FROM tasks WHERE list_id = ?1",
+            params![list_id.to_string()],
+        )
+    }
+
+    fn write_task(&self, list_id: Uuid, task: &Task) -> Result<()> {
+        insert_task(&self.conn(), list_id, task)
+    }
+
+    fn remove_task(&self, list_id: Uuid, task_id: Uuid) -> Result<()> {
+        let removed = self.conn().execute(
+            "DELETE FROM tasks WHERE list_id = ?1 AND id = ?2",
+            params![list_id.to_string(), task_id.to_string()],
+        )?;
+        if removed == 0 {
+            return Err(Error::Store(StoreError::TaskNotFound(task_id)));
+        }
+        Ok(())
+    }
+
+    fn read_trashed_tasks(&self) -> Result<Vec<TrashedTask>> {
+        self.read_records("trashed_tasks", "SELECT rowid, data FROM trashed_tasks", [])
+    }
+
+    fn write_trashed_task(&self, trashed: &TrashedTask) -> Result<()> {
+        insert_trashed_task(&self.conn(), trashed)
+    }
+
+    fn remove_trashed_task(&self, task_id: Uuid) -> Result<()> {
+        let removed = self.conn().execute(
+            "DELETE FROM trashed_tasks WHERE id = ?1",
+            params![task_id.to_string()],
+        )?;
+        if removed == 0 {
+            return Err(Error::Store(StoreError::TaskNotFound(task_id)));
+        }
+        Ok(())
+    }
+
+    fn read_trashed_lists(&self) -> Result<Vec<TrashedList>> {
+        self.read_records(
+            "trashed_lists",
+            "SELECT rowid, data FROM trashed_lists ORDER BY position",
+            [],
+        )
+    }
+
+    fn write_trashed_lists(&self, lists: &[TrashedList]) -> Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.savepoint()?;
+        replace_trashed_lists(&tx, lists)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn move_list_to_trash(&self, list_id: Uuid) -> Result<()> {
+        let id = list_id.to_string();
+        let mut conn = self.conn();
+        let tx = conn.savepoint()?;
+        tx.execute("DELETE FROM list_storage WHERE list_id = ?1", params![id])?;
+        tx.execute(
+            "INSERT OR REPLACE INTO trashed_list_tasks (list_id, id, data)
+             SELECT list_id, id, data FROM tasks WHERE list_id = ?1",
+            params![id],
+        )?;
+        tx.execute("DELETE FROM tasks WHERE list_id = ?1", params![id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn move_list_from_trash(&self, list_id: Uuid) -> Result<()> {
+        let id = list_id.to_string();
+        let tasks: Vec<Task> = self.read_trashed_list_tasks(list_id)?;
+
+        let mut conn = self.conn();
+        let tx = conn.savepoint()?;
+        tx.execute(
+            "INSERT OR IGNORE INTO list_storage (list_id) VALUES (?1)",
+            params![id],
+        )?;
+        // Rows are rewritten through `insert_task` so the indexed columns
+        // are filled in again.
+        for task in &tasks {
+            insert_task(&tx, list_id, task)?;
+        }
+        tx.execute(
+            "DELETE FROM trashed_list_tasks WHERE list_id = ?1",
+            params![id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn read_trashed_list_tasks(&self, list_id: Uuid) -> Result<Vec<Task>> {
+        self.read_records(
+            "trashed_list_tasks",
+            "SELECT rowid, data FROM trashed_list_tasks WHERE list_id = ?1",
+            params![list_id.to_string()],
+        )
+    }
+
+    fn write_trashed_list_task(&self, list_id: Uuid, task: &Task) -> Result<()> {
+        insert_trashed_list_task(&self.conn(), list_id, task)
+    }
+
+    fn restore_trashed_list_task(&self, list_id: Uuid, task_id: Uuid) -> Result<()> {
+        let data: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT data FROM trashed_list_tasks WHERE list_id = ?1 AND id = ?2",
+                params![list_id.to_string(), task_id.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let task: Task = match data {
+            Some(data) => decode(&data)?,
+            None => return Err(Error::Store(StoreError::TaskNotFound(task_id))),
+        };
+
+        self.create_list(list_id)?;
+        self.write_task(list_id, &task)?;
+        self.remove_trashed_list_task(list_id, task_id)
+    }
+
+    fn remove_trashed_list_task(&self, list_id: Uuid, task_id: Uuid) -> Result<()> {
+        let removed = self.conn().execute(
+            "DELETE FROM trashed_list_tasks WHERE list_id = ?1 AND id = ?2",
+            params![list_id.to_string(), task_id.to_string()],
+        )?;
+        if removed == 0 {
+            return Err(Error::Store(StoreError::TaskNotFound(task_id)));
+        }
+        Ok(())
+    }
+
+    fn has_trashed_list_tasks(&self, list_id: Uuid) -> bool {
+        self.exists(
+            "SELECT 1 FROM trashed_list_tasks WHERE list_id = ?1 LIMIT 1",
+            params![list_id.to_string()],
+        )
+    }
+
+    fn remove_trashed_list(&self, list_id: Uuid) -> Result<()> {
+        self.conn().execute(
+            "DELETE FROM trashed_list_tasks WHERE list_id = ?1",
+            params![list_id.to_string()],
+        )?;
+        Ok(())
+    }
+
+    fn read_revisions(&self, task_id: Uuid) -> Result<Vec<Revision>> {
+        let data: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT data FROM task_revisions WHERE task_id = ?1",
+                params![task_id.to_string()],
+                |row| row.get(0),
+            )
+            .optional()?;
+        data.map_or(Ok(vec![]), |data| decode(&data))
+    }
+
+    fn write_revisions(&self, task_id: Uuid, revisions: &[Revision]) -> Result<()> {
+        let id = task_id.to_string();
+        if revisions.is_empty() {
+            self.conn()
+                .execute("DELETE FROM task_revisions WHERE task_id = ?1", params![id])?;
+        } else {
+            self.conn().execute(
+                "INSERT OR REPLACE INTO task_revisions (task_id, data) VALUES (?1, ?2)",
+                params![id, encode(&revisions)?],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn quarantined(&self) -> Result<Vec<QuarantinedItem>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare_cached("SELECT record FROM quarantine")?;
+        let records = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let mut items = Vec::new();
+        for record in records {
+            match decode::<QuarantinedItem>(&record) {
+                Ok(mut item) => {
+                    item.path = self.path.clone();
+                    items.push(item);
+                }
+                Err(e) => tracing::error!("could not read quarantined item: {e}"),
+            }
+        }
+        items.sort_by_key(|item| item.quarantined_at);
+        Ok(items)
+    }
+
+    /// Puts the row back into the table it came from, at the end of a
+    /// registry.
+    fn restore_quarantined(&self, id: Uuid) -> Result<PathBuf> {
+        let (mut item, data) = self.read_quarantined(id)?;
+        let mut origin = item.origin.iter().map(|part| part.to_string_lossy());
+        let table = origin.next().unwrap_or_default().into_owned();
+        let keys: Vec<String> = origin.map(|part| part.into_owned()).collect();
+
+        let mut conn = self.conn();
+        let tx = conn.savepoint()?;
+        let restored = match table.as_str() {
+            "lists" => ron::from_str::<List>(&data).map(|list| {
+                let position = next_position(&tx, "lists")?;
+                tx.execute(
+                    "INSERT OR REPLACE INTO lists (id, position, data) VALUES (?1, ?2, ?3)",
+                    params![list.id.to_string(), position, encode(&list)?],
+                )?;
+                Ok(())
+            }),
+            "states" => ron::from_str::<TaskState>(&data).map(|state| {
+                let position = next_position(&tx, "states")?;
+                tx.execute(
+                    "INSERT OR REPLACE INTO states (id, position, data) VALUES (?1, ?2, ?3)",
+                    params![state.id.to_string(), position, encode(&state)?],
+                )?;
+                Ok(())
+            }),
+            "trashed_lists" => ron::from_str::<TrashedList>(&data).map(|trashed| {
+                let position = next_position(&tx, "trashed_lists")?;
+                tx.execute(
+                    "INSERT OR REPLACE INTO trashed_lists (id, position, data) VALUES (?1, ?2, ?3)",
+                    params![trashed.list.id.to_string(), position, encode(&trashed)?],
+                )?;
+                Ok(())
+            }),
+            "trashed_tasks" => ron::from_str::<TrashedTask>(&data)
+                .map(|trashed| insert_trashed_task(&tx, &trashed)),
+            "tasks" | "trashed_list_tasks" => ron::from_str::<Task>(&data).map(|task| {
+                let list_id = keys
+                    .first()
+                    .and_then(|id| Uuid::parse_str(id).ok())
+                    .unwrap_or_default();
+                if table == "trashed_list_tasks" {
+                    return insert_trashed_list_task(&tx, list_id, &task);
+                }
+                let exists = tx
+                    .query_row(
+                        "SELECT 1 FROM list_storage WHERE list_id = ?1",
+                        params![list_id.to_string()],
+                        |_| Ok(()),
+                    )
+                    .optional()?
+                    .is_some();
+                if !exists {
+                    return Err(Error::Store(StoreError::ListNotFound(list_id)));
+                }
+                insert_task(&tx, list_id, &task)
+            }),
+            _ => return Err(Error::Store(StoreError::QuarantinedItemNotFound(id))),
+        };
+        match restored {
+            Ok(result) => result?,
+            Err(e) => {
+                item.error = (&e).into();
+                tx.execute(
+                    "UPDATE quarantine SET record = ?2 WHERE id = ?1",
+                    params![id.to_string(), encode(&item)?],
+                )?;
+                tx.commit()?;
+                return Err(e.into());
+            }
+        }
+        tx.execute(
+            "DELETE FROM quarantine WHERE id = ?1",
+            params![id.to_string()],
+        )?;
+        tx.commit()?;
+        Ok(self.path.clone())
+    }
+
+    fn discard_quarantined(&self, id: Uuid) -> Result<()> {
+        let removed = self.conn().execute(
+            "DELETE FROM quarantine WHERE id = ?1",
+            params![id.to_string()],
+        )?;
+        if removed == 0 {
+            return Err(Error::Store(StoreError::QuarantinedItemNotFound(id)));
+        }
+        Ok(())
+    }
+
+    fn quarantined_data(&self, id: Uuid) -> Result<String> {
+        Ok(self.read_quarantined(id)?.1)
+    }
+
+    fn import_quarantined(&self, item: &QuarantinedItem, data: &str) -> Result<()> {
+        let item = QuarantinedItem {
+            origin: origin_in_database(item.kind, &item.origin),
+            path: self.path.clone(),
+            ..item.clone()
+        };
+        self.conn().execute(
+            "INSERT OR REPLACE INTO quarantine (id, record, data) VALUES (?1, ?2, ?3)",
+            params![item.id.to_string(), encode(&item)?, data],
+        )?;
+        Ok(())
+    }
+}
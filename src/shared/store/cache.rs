@@ -0,0 +1,186 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::RangeInclusive;
+
+use jiff::civil::Date;
+use jiff::Timestamp;
+use uuid::Uuid;
+
+use super::change::Change;
+use crate::features::lists::list::{List, TrashedList};
+use crate::features::tasks::state::TaskState;
+use crate::features::tasks::task::{Task, TrashedTask};
+
+/// `(list_id, task_id)`.
+pub type TaskKey = (Uuid, Uuid);
+
+/// What [`Store`](super::Store) has already read from its backend.
+///
+/// Tasks are cached a whole list at a time, so a list is either fully known
+/// or not at all. Single tasks reported as changed on disk are marked stale
+/// and re-read on the next access to their list instead of dropping the
+/// list. The secondary indexes only cover lists that are loaded.
+#[derive(Debug, Default)]
+pub struct Cache {
+    pub lists: Option<Vec<List>>,
+    pub states: Option<Vec<TaskState>>,
+    pub trashed_tasks: Option<Vec<TrashedTask>>,
+    pub trashed_lists: Option<Vec<TrashedList>>,
+    pub trashed_list_tasks: HashMap<Uuid, Vec<Task>>,
+    tasks: HashMap<Uuid, HashMap<Uuid, Task>>,
+    stale: HashSet<TaskKey>,
+    favorites: HashSet<TaskKey>,
+    reminders: BTreeSet<(Timestamp, TaskKey)>,
+    /// Keyed by the date as stored, which for a time pinned to another zone
+    /// can be a day or two off the local one.
+    due: BTreeSet<(Date, TaskKey)>,
+}
+
+impl Cache {
+    pub fn has_list(&self, list_id: Uuid) -> bool {
+        self.tasks.contains_key(&list_id)
+    }
+
+    pub fn list_tasks(&self, list_id: Uuid) -> Option<impl Iterator<Item = &Task>> {
+        self.tasks.get(&list_id).map(|tasks| tasks.values())
+    }
+
+    pub fn task(&self, (list_id, task_id): TaskKey) -> Option<&Task> {
+        self.tasks.get(&list_id)?.get(&task_id)
+    }
+
+    /// Takes the ids of the tasks in `list_id` that need to be re-read.
+    pub fn take_stale(&mut self, list_id: Uuid) -> Vec<Uuid> {
+        let keys: Vec<TaskKey> = self
+            .stale
+            .iter()
+            .filter(|(list, _)| *list == list_id)
+            .copied()
+            .collect();
+        keys.into_iter()
+            .map(|key| {
+                self.stale.remove(&key);
+                key.1
+            })
+            .collect()
+    }
+
+    pub fn insert_list(&mut self, list_id: Uuid, tasks: Vec<Task>) {
+        self.remove_list(list_id);
+        let entry = self.tasks.entry(list_id).or_default();
+        for task in tasks {
+            entry.insert(task.id, task);
+        }
+        let keys: Vec<TaskKey> = entry.keys().map(|task_id| (list_id, *task_id)).collect();
+        for key in keys {
+            self.index(key);
+        }
+    }
+
+    pub fn remove_list(&mut self, list_id: Uuid) {
+        if let Some(tasks) = self.tasks.remove(&list_id) {
+            for task in tasks.values() {
+                self.unindex((list_id, task.id), task);
+            }
+        }
+        self.stale.retain(|(list, _)| *list != list_id);
+    }
+
+    /// Records a task that was written. Ignored unless its list is loaded.
+    pub fn insert_task(&mut self, list_id: Uuid, task: Task) {
+        let key = (list_id, task.id);
+        let Some(tasks) = self.tasks.get_mut(&list_id) else {
+            return;
+        };
+        if let Some(previous) = tasks.insert(task.id, task) {
+            self.unindex(key, &previous);
+        }
+        self.stale.remove(&key);
+        self.index(key);
+    }
+
+    pub fn remove_task(&mut self, list_id: Uuid, task_id: Uuid) {
+        let key = (list_id, task_id);
+        self.stale.remove(&key);
+        if let Some(task) = self
+            .tasks
+            .get_mut(&list_id)
+            .and_then(|tasks| tasks.remove(&task_id))
+        {
+            self.unindex(key, &task);
+        }
+    }
+
+    pub fn favorites(&self) -> impl Iterator<Item = TaskKey> + '_ {
+        self.favorites.iter().copied()
+    }
+
+    pub fn reminders(
+        &self,
+        range: RangeInclusive<Timestamp>,
+    ) -> impl Iterator<Item = TaskKey> + '_ {
+        let (start, end) = range.into_inner();
+        self.reminders
+            .range((start, (Uuid::nil(), Uuid::nil()))..=(end, (Uuid::max(), Uuid::max())))
+            .map(|(_, key)| *key)
+    }
+
+    pub fn due(&self, range: RangeInclusive<Date>) -> impl Iterator<Item = TaskKey> + '_ {
+        let (start, end) = range.into_inner();
+        self.due
+            .range((start, (Uuid::nil(), Uuid::nil()))..=(end, (Uuid::max(), Uuid::max())))
+            .map(|(_, key)| *key)
+    }
+
+    /// Forgets whatever a change on disk may have made out of date.
+    pub fn invalidate(&mut self, change: Change) {
+        match change {
+            Change::Lists => self.lists = None,
+            Change::List(list_id) => self.remove_list(list_id),
+            Change::Task { list_id, task_id } => {
+                if self.has_list(list_id) {
+                    self.stale.insert((list_id, task_id));
+                }
+            }
+            Change::States => self.states = None,
+            Change::Trash => self.clear_trash(),
+            Change::Quarantine => {}
+            Change::Unknown => *self = Self::default(),
+        }
+    }
+
+    fn clear_trash(&mut self) {
+        self.trashed_tasks = None;
+        self.trashed_lists = None;
+        self.trashed_list_tasks.clear();
+    }
+
+    fn index(&mut self, key: TaskKey) {
+        let Some(task) = self.task(key) else {
+            return;
+        };
+        let (favorite, reminder, due) = (
+            task.favorite,
+            task.reminder_date,
+            task.due_date.as_ref().map(|due| due.date),
+        );
+        if favorite {
+            self.favorites.insert(key);
+        }
+        if let Some(reminder) = reminder {
+            self.reminders.insert((reminder, key));
+        }
+        if let Some(due) = due {
+            self.due.insert((due, key));
+        }
+    }
+
+    fn unindex(&mut self, key: TaskKey, task: &Task) {
+        self.favorites.remove(&key);
+        if let Some(reminder) = task.reminder_date {
+            self.reminders.remove(&(reminder, key));
+        }
+        if let Some(due) = &task.due_date {
+            self.due.remove(&(due.date, key));
+        }
+    }
+}
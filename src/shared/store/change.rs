@@ -0,0 +1,22 @@
+use uuid::Uuid;
+
+/// The part of the store a change on disk touched, as decoded from a watched
+/// path by [`StorageBackend::classify`](super::backend::StorageBackend::classify).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Change {
+    /// The list registry.
+    Lists,
+    /// A list's task storage as a whole, e.g. its directory was created,
+    /// removed or moved to the trash.
+    List(Uuid),
+    Task {
+        list_id: Uuid,
+        task_id: Uuid,
+    },
+    States,
+    Trash,
+    /// Damaged records were set aside, restored or discarded.
+    Quarantine,
+    /// Something the backend cannot narrow down; everything is reloaded.
+    Unknown,
+}
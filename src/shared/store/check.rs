@@ -0,0 +1,323 @@
+//! Consistency checks across the pieces of the store.
+//!
+//! Each record is valid on its own, but nothing stops them from
+//! disagreeing with each other: a sub-task can be deleted while its parent
+//! still lists it, a state can be deleted while tasks still use it, and a
+//! list directory can lose its registry entry. [`Store::check`] finds these
+//! and [`Store::repair`] fixes the ones that have an obvious fix.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use uuid::Uuid;
+
+use super::Store;
+use crate::features::lists::list::List;
+use crate::features::tasks::state::default_states;
+use crate::features::tasks::task::Task;
+use crate::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Problem {
+    /// A task lists a sub-task that does not exist in its list.
+    MissingSubTask {
+        list_id: Uuid,
+        task_id: Uuid,
+        sub_task_id: Uuid,
+    },
+    /// A task has a parent that does not exist in its list.
+    MissingParent {
+        list_id: Uuid,
+        task_id: Uuid,
+        parent_id: Uuid,
+    },
+    /// A task has a parent that lives in another list.
+    ParentInOtherList {
+        list_id: Uuid,
+        task_id: Uuid,
+        parent_id: Uuid,
+        parent_list_id: Uuid,
+    },
+    /// A task points at a parent that does not list it as a sub-task.
+    UnlistedSubTask {
+        list_id: Uuid,
+        task_id: Uuid,
+        parent_id: Uuid,
+    },
+    /// A task is in a state that is not in the state registry.
+    UnknownState {
+        list_id: Uuid,
+        task_id: Uuid,
+        state_id: Uuid,
+    },
+    /// Tasks are stored for a list the list registry does not know about.
+    UnregisteredList(Uuid),
+    /// A registered list has nowhere to keep its tasks.
+    MissingListStorage(Uuid),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::MissingSubTask {
+                list_id,
+                task_id,
+                sub_task_id,
+            } => write!(
+                f,
+                "task {task_id} in list {list_id} lists missing sub-task {sub_task_id}"
+            ),
+            Problem::MissingParent {
+                list_id,
+                task_id,
+                parent_id,
+            } => write!(
+                f,
+                "task {task_id} in list {list_id} has missing parent {parent_id}"
+            ),
+            Problem::ParentInOtherList {
+                list_id,
+                task_id,
+                parent_id,
+                parent_list_id,
+            } => write!(
+                f,
+                "task {task_id} in list {list_id} has parent {parent_id} in list {parent_list_id}"
+            ),
+            Problem::UnlistedSubTask {
+                list_id,
+                task_id,
+                parent_id,
+            } => write!(
+                f,
+                "task {task_id} in list {list_id} is not listed by its parent {parent_id}"
+            ),
+            Problem::UnknownState {
+                list_id,
+                task_id,
+                state_id,
+            } => write!(
+                f,
+                "task {task_id} in list {list_id} is in unknown state {state_id}"
+            ),
+            Problem::UnregisteredList(list_id) => {
+                write!(f, "list {list_id} has tasks but is not registered")
+            }
+            Problem::MissingListStorage(list_id) => {
+                write!(f, "list {list_id} is registered but has no task storage")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    pub problems: Vec<Problem>,
+}
+
+impl CheckReport {
+    pub fn is_clean(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RepairReport {
+    pub repaired: Vec<Problem>,
+    /// Problems still found after repairing.
+    pub remaining: Vec<Problem>,
+}
+
+impl Store {
+    /// Looks for references between records that do not hold up. Reads
+    /// straight from the backend rather than the cache and fixes nothing.
+    pub fn check(&self) -> Result<CheckReport> {
+        let registered: Vec<Uuid> = self.backend().read_lists()?.iter().map(|l| l.id).collect();
+        let stored = self.backend().list_ids()?;
+        let states: HashSet<Uuid> = self
+            .backend()
+            .read_states()?
+            .unwrap_or_else(default_states)
+            .iter()
+            .map(|s| s.id)
+            .collect();
+
+        let mut problems = Vec::new();
+        for list_id in &stored {
+            if !registered.contains(list_id) {
+                problems.push(Problem::UnregisteredList(*list_id));
+            }
+        }
+        for list_id in &registered {
+            if !stored.contains(list_id) {
+                problems.push(Problem::MissingListStorage(*list_id));
+            }
+        }
+
+        let mut tasks: HashMap<Uuid, Vec<Task>> = HashMap::new();
+        for list_id in &stored {
+            tasks.insert(*list_id, self.backend().read_tasks(*list_id)?);
+        }
+        let home: HashMap<Uuid, Uuid> = tasks
+            .iter()
+            .flat_map(|(list_id, tasks)| tasks.iter().map(|t| (t.id, *list_id)))
+            .collect();
+
+        for (list_id, list_tasks) in &tasks {
+            let by_id: HashMap<Uuid, &Task> = list_tasks.iter().map(|t| (t.id, t)).collect();
+            for task in list_tasks {
+                problems.extend(Self::check_task(*list_id, task, &by_id, &home, &states));
+            }
+        }
+        Ok(CheckReport { problems })
+    }
+
+    /// Fixes what [`Store::check`] finds, as far as there is a safe fix:
+    /// dangling references are dropped, tasks in unknown states fall back
+    /// to their default state, sub-tasks missing from their parent are
+    /// added back, and orphaned lists are registered again.
+    pub fn repair(&self) -> Result<RepairReport> {
+        let _lock = self.lock()?;
+        let report = self.check()?;
+        if report.is_clean() {
+            return Ok(RepairReport::default());
+        }
+        let found = report.problems;
+
+        let mut task_fixes: HashMap<(Uuid, Uuid), Vec<Problem>> = HashMap::new();
+        for problem in &found {
+            match *problem {
+                Problem::MissingSubTask {
+                    list_id, task_id, ..
+                }
+                | Problem::MissingParent {
+                    list_id, task_id, ..
+                }
+                | Problem::ParentInOtherList {
+                    list_id, task_id, ..
+                }
+                | Problem::UnknownState {
+                    list_id, task_id, ..
+                } => task_fixes
+                    .entry((list_id, task_id))
+                    .or_default()
+                    .push(*problem),
+                Problem::UnlistedSubTask {
+                    list_id, parent_id, ..
+                } => task_fixes
+                    .entry((list_id, parent_id))
+                    .or_default()
+                    .push(*problem),
+                Problem::UnregisteredList(list_id) => {
+                    self.lists().save(&List::recovered(list_id))?;
+                }
+                Problem::MissingListStorage(list_id) => self.backend().create_list(list_id)?,
+            }
+        }
+
+        for ((list_id, task_id), fixes) in task_fixes {
+            self.tasks(list_id).update(task_id, |task| {
+                for fix in fixes {
+                    Self::fix_task(task, fix);
+                }
+            })?;
+        }
+
+        let remaining = self.check()?.problems;
+        let repaired = found
+            .into_iter()
+            .filter(|problem| !remaining.contains(problem))
+            .collect();
+        Ok(RepairReport {
+            repaired,
+            remaining,
+        })
+    }
+
+    fn check_task(
+        list_id: Uuid,
+        task: &Task,
+        by_id: &HashMap<Uuid, &Task>,
+        home: &HashMap<Uuid, Uuid>,
+        states: &HashSet<Uuid>,
+    ) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        let task_id = task.id;
+
+        for sub_task_id in &task.sub_task_ids {
+            if !by_id.contains_key(sub_task_id) {
+                problems.push(Problem::MissingSubTask {
+                    list_id,
+                    task_id,
+                    sub_task_id: *sub_task_id,
+                });
+            }
+        }
+
+        if let Some(parent_id) = task.parent_id {
+            match (by_id.get(&parent_id), home.get(&parent_id)) {
+                (Some(parent), _) if !parent.sub_task_ids.contains(&task_id) => {
+                    problems.push(Problem::UnlistedSubTask {
+                        list_id,
+                        task_id,
+                        parent_id,
+                    });
+                }
+                (Some(_), _) => {}
+                (None, Some(parent_list_id)) => problems.push(Problem::ParentInOtherList {
+                    list_id,
+                    task_id,
+                    parent_id,
+                    parent_list_id: *parent_list_id,
+                }),
+                (None, None) => problems.push(Problem::MissingParent {
+                    list_id,
+                    task_id,
+                    parent_id,
+                }),
+            }
+        }
+
+        if let Some(state_id) = task.state_id {
+            if !states.contains(&state_id) {
+                problems.push(Problem::UnknownState {
+                    list_id,
+                    task_id,
+                    state_id,
+                });
+            }
+        }
+        problems
+    }
+
+    fn fix_task(task: &mut Task, problem: Problem) {
+        match problem {
+            Problem::MissingSubTask { sub_task_id, .. } => {
+                task.sub_task_ids.retain(|id| *id != sub_task_id);
+            }
+            Problem::MissingParent { .. } | Problem::ParentInOtherList { .. } => {
+                task.parent_id = None;
+            }
+            Problem::UnlistedSubTask { task_id, .. } => {
+                if !task.sub_task_ids.contains(&task_id) {
+                    task.sub_task_ids.push(task_id);
+                }
+            }
+            Problem::UnknownState { .. } => task.state_id = None,
+            Problem::UnregisteredList(_) | Problem::MissingListStorage(_) => {}
+        }
+    }
+}
+
+/// Checks the store and repairs what it can, logging what was found. Run on
+/// startup and after migrations, so problems do not pile up unnoticed.
+pub fn check_and_repair(store: &Store) -> Result<RepairReport> {
+    let report = store.repair()?;
+    for problem in &report.repaired {
+        tracing::warn!("Repaired: {problem}");
+    }
+    for problem in &report.remaining {
+        tracing::error!("Could not repair: {problem}");
+    }
+    Ok(report)
+}
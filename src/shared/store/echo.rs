@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a write is remembered. Comfortably longer than the watcher's
+/// debounce, so every event a write produces arrives while it is known.
+const WINDOW: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    File(u64),
+    Dir,
+    Absent,
+}
+
+impl State {
+    fn of(path: &Path) -> Self {
+        match std::fs::read(path) {
+            Ok(contents) => Self::File(hash(&contents)),
+            Err(_) if path.is_dir() => Self::Dir,
+            Err(_) => Self::Absent,
+        }
+    }
+}
+
+fn hash(contents: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(contents);
+    hasher.finish()
+}
+
+/// Remembers what this process recently left at each path it wrote, so
+/// file watcher events caused by those writes can be told apart from
+/// changes made by someone else.
+///
+/// An event is only an echo while the path still holds what was written.
+/// If another process overwrites it in the meantime, the contents differ and
+/// the change is reported as usual.
+#[derive(Debug, Default)]
+pub struct WriteLog {
+    writes: Mutex<HashMap<PathBuf, (State, Instant)>>,
+}
+
+impl WriteLog {
+    pub fn wrote(&self, path: &Path, contents: impl AsRef<[u8]>) {
+        self.record(path, State::File(hash(contents.as_ref())));
+    }
+
+    pub fn created_dir(&self, path: &Path) {
+        self.record(path, State::Dir);
+    }
+
+    pub fn removed(&self, path: &Path) {
+        self.record(path, State::Absent);
+    }
+
+    /// Records whatever is at `path` now, for operations such as renames
+    /// that do not go through [`WriteLog::wrote`].
+    pub fn settled(&self, path: &Path) {
+        self.record(path, State::of(path));
+    }
+
+    /// Whether the current state of `path` is one this process put there
+    /// within the last few seconds. Anything missing from under a directory
+    /// this process removed or moved away counts as well, unless the path
+    /// itself was written after that.
+    pub fn is_echo(&self, path: &Path) -> bool {
+        let recorded = {
+            let writes = self.writes();
+            let recent = |p: &Path| {
+                writes
+                    .get(p)
+                    .filter(|(_, at)| at.elapsed() < WINDOW)
+                    .copied()
+            };
+            let removed_parent = path
+                .ancestors()
+                .skip(1)
+                .filter_map(|dir| recent(dir).filter(|(state, _)| *state == State::Absent))
+                .max_by_key(|(_, at)| *at);
+            match (recent(path), removed_parent) {
+                (Some(own), Some(parent)) => Some(if own.1 >= parent.1 { own } else { parent }),
+                (own, parent) => own.or(parent),
+            }
+        };
+        recorded.is_some_and(|(state, _)| state == State::of(path))
+    }
+
+    fn record(&self, path: &Path, state: State) {
+        let mut writes = self.writes();
+        writes.retain(|_, (_, at)| at.elapsed() < WINDOW);
+        writes.insert(path.to_path_buf(), (state, Instant::now()));
+    }
+
+    fn writes(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, (State, Instant)>> {
+        self.writes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,170 @@
+//! Earlier versions of each task.
+//!
+//! Every save that changes what a user would call the content of a task —
+//! title, notes, dates, priority or state — adds a [`Revision`]. Saves in
+//! quick succession, such as typing in the notes editor, update the newest
+//! revision instead of adding one per keystroke. Only the last
+//! [`MAX_REVISIONS`] are kept.
+
+use jiff::{SignedDuration, Timestamp};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::Store;
+use crate::features::tasks::due::{self, DueDate};
+use crate::features::tasks::task::{Priority, Task};
+use crate::{Error, Result, StoreError};
+
+pub const MAX_REVISIONS: usize = 50;
+
+/// Saves closer than this to the newest revision are folded into it.
+const EDIT_SESSION: SignedDuration = SignedDuration::from_secs(60);
+
+/// What a task looked like at one point in time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Revision {
+    pub at: Timestamp,
+    pub title: String,
+    pub notes: String,
+    pub priority: Priority,
+    pub state_id: Option<Uuid>,
+    pub completion_date: Option<Timestamp>,
+    #[serde(default, deserialize_with = "due::deserialize")]
+    pub due_date: Option<DueDate>,
+    pub reminder_date: Option<Timestamp>,
+}
+
+impl Revision {
+    pub fn of(task: &Task, at: Timestamp) -> Self {
+        Self {
+            at,
+            title: task.title.clone(),
+            notes: task.notes.clone(),
+            priority: task.priority,
+            state_id: task.state_id,
+            completion_date: task.completion_date,
+            due_date: task.due_date.clone(),
+            reminder_date: task.reminder_date,
+        }
+    }
+
+    /// Puts the captured fields back on `task`, leaving everything else,
+    /// such as its place in the list and its sub-tasks, as it is.
+    pub fn apply_to(&self, task: &mut Task) {
+        task.title.clone_from(&self.title);
+        task.notes.clone_from(&self.notes);
+        task.priority = self.priority;
+        task.state_id = self.state_id;
+        task.completion_date = self.completion_date;
+        task.due_date.clone_from(&self.due_date);
+        task.reminder_date = self.reminder_date;
+    }
+
+    fn same_content(&self, other: &Revision) -> bool {
+        Revision {
+            at: other.at,
+            ..self.clone()
+        } == *other
+    }
+
+    /// The fields that differ between this revision and another.
+    pub fn diff(&self, other: &Revision) -> Vec<Field> {
+        [
+            (Field::Title, self.title != other.title),
+            (Field::Notes, self.notes != other.notes),
+            (Field::Priority, self.priority != other.priority),
+            (Field::State, self.state_id != other.state_id),
+            (
+                Field::Completed,
+                self.completion_date != other.completion_date,
+            ),
+            (Field::DueDate, self.due_date != other.due_date),
+            (Field::Reminder, self.reminder_date != other.reminder_date),
+        ]
+        .into_iter()
+        .filter_map(|(field, changed)| changed.then_some(field))
+        .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Title,
+    Notes,
+    Priority,
+    State,
+    Completed,
+    DueDate,
+    Reminder,
+}
+
+pub struct HistoryStore<'s> {
+    pub(super) store: &'s Store,
+}
+
+impl HistoryStore<'_> {
+    /// Revisions of a task, oldest first.
+    pub fn load(&self, task_id: Uuid) -> Result<Vec<Revision>> {
+        self.store.backend().read_revisions(task_id)
+    }
+
+    /// Puts a task back the way it was in the revision taken at `at`. This
+    /// is a save like any other, so it can be undone. It always adds a
+    /// revision of its own, so the version it replaced stays in history.
+    pub fn restore(&self, list_id: Uuid, task_id: Uuid, at: Timestamp) -> Result<Task> {
+        let mut revisions = self.load(task_id)?;
+        let revision = revisions
+            .iter()
+            .find(|r| r.at == at)
+            .cloned()
+            .ok_or(Error::Store(StoreError::RevisionNotFound(task_id)))?;
+        let task = self
+            .store
+            .tasks(list_id)
+            .update(task_id, |task| revision.apply_to(task))?;
+
+        revisions.push(Revision::of(&task, Timestamp::now()));
+        self.write(task_id, revisions)?;
+        Ok(task)
+    }
+
+    /// Adds a revision for a task that was just saved. A task saved for the
+    /// first time with no history yet also gets one for what it held before,
+    /// dated when it was created, so the first edit can be gone back on.
+    pub(super) fn record(&self, before: Option<&Task>, after: &Task) -> Result<()> {
+        let now = Timestamp::now();
+        let mut revisions = self.load(after.id)?;
+        if revisions.is_empty() {
+            if let Some(before) = before {
+                revisions.push(Revision::of(before, before.creation_date));
+            }
+        }
+
+        let revision = Revision::of(after, now);
+        let len = revisions.len();
+        match revisions.last_mut() {
+            Some(last) if last.same_content(&revision) => return Ok(()),
+            // The first revision is never folded into, so the state before
+            // the first edit session survives it.
+            Some(last) if len > 1 && now.duration_since(last.at) < EDIT_SESSION => {
+                *last = revision;
+            }
+            _ => revisions.push(revision),
+        }
+
+        self.write(after.id, revisions)
+    }
+
+    fn write(&self, task_id: Uuid, mut revisions: Vec<Revision>) -> Result<()> {
+        let excess = revisions.len().saturating_sub(MAX_REVISIONS);
+        revisions.drain(..excess);
+        self.store.backend().write_revisions(task_id, &revisions)
+    }
+
+    /// Drops the history of a task that was deleted for good.
+    pub(super) fn forget(&self, task_id: Uuid) {
+        if let Err(err) = self.store.backend().write_revisions(task_id, &[]) {
+            tracing::error!("Failed to remove history of task {task_id}: {err}");
+        }
+    }
+}
@@ -0,0 +1,623 @@
+//! Undo and redo for every change made through the store.
+//!
+//! Each store operation is recorded as one [`Entry`]: the before and after
+//! values of every record it touched. Undoing an entry writes the before
+//! values back, redoing it writes the after values again. Entries are
+//! appended to a journal file next to the data, one per line, so undo
+//! survives restarts and is shared by every window.
+//!
+//! Nothing is undone over someone else's change: if a record no longer
+//! holds what the entry left in it, [`Store::undo`] fails with
+//! [`StoreError::UndoConflict`] and writes nothing.
+
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use jiff::{SignedDuration, Timestamp};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::backend::StorageBackend;
+use super::change::Change;
+use super::transaction::WriteAhead;
+use super::Store;
+use crate::features::lists::list::{List, TrashedList};
+use crate::features::tasks::state::TaskState;
+use crate::features::tasks::task::{Task, TrashedTask};
+use crate::{Error, Result, StoreError};
+
+pub const JOURNAL_FILE: &str = "journal";
+
+/// Entries kept for undo. Older ones are dropped.
+const MAX_ENTRIES: usize = 100;
+
+/// Edits to the same task this close together undo as one, so typing a
+/// title is not undone a keystroke at a time.
+const COALESCE_WINDOW: SignedDuration = SignedDuration::from_secs(3);
+
+/// A single change to one record, with what it held before and after.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Operation {
+    Lists {
+        before: Vec<List>,
+        after: Vec<List>,
+    },
+    States {
+        before: Vec<TaskState>,
+        after: Vec<TaskState>,
+    },
+    TrashedLists {
+        before: Vec<TrashedList>,
+        after: Vec<TrashedList>,
+    },
+    Task {
+        list_id: Uuid,
+        before: Option<Task>,
+        after: Option<Task>,
+    },
+    TrashedTask {
+        task_id: Uuid,
+        before: Option<TrashedTask>,
+        after: Option<TrashedTask>,
+    },
+    /// A task kept inside a trashed list.
+    TrashedListTask {
+        list_id: Uuid,
+        task_id: Uuid,
+        before: Option<Task>,
+        after: Option<Task>,
+    },
+    /// A list's task storage was created, or removed when `created` is false.
+    ListStorage {
+        list_id: Uuid,
+        created: bool,
+    },
+    /// A list's tasks were moved into the trash as a whole, or back out of it
+    /// when `trashed` is false.
+    ListTrashed {
+        list_id: Uuid,
+        trashed: bool,
+    },
+}
+
+/// Identifies the record an operation touches, for conflict checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Key {
+    Lists,
+    States,
+    TrashedLists,
+    Task(Uuid, Uuid),
+    TrashedTask(Uuid),
+    TrashedListTask(Uuid, Uuid),
+}
+
+impl Operation {
+    fn key(&self) -> Option<Key> {
+        match self {
+            Operation::Lists { .. } => Some(Key::Lists),
+            Operation::States { .. } => Some(Key::States),
+            Operation::TrashedLists { .. } => Some(Key::TrashedLists),
+            Operation::Task {
+                list_id,
+                before,
+                after,
+            } => before
+                .as_ref()
+                .or(after.as_ref())
+                .map(|task| Key::Task(*list_id, task.id)),
+            Operation::TrashedTask { task_id, .. } => Some(Key::TrashedTask(*task_id)),
+            Operation::TrashedListTask {
+                list_id, task_id, ..
+            } => Some(Key::TrashedListTask(*list_id, *task_id)),
+            Operation::ListStorage { .. } | Operation::ListTrashed { .. } => None,
+        }
+    }
+
+    /// Whether the record holds what this operation leaves in it (`after`)
+    /// or expects to find (`!after`).
+    fn holds(&self, backend: &dyn StorageBackend, applied: bool) -> Result<bool> {
+        Ok(match self {
+            Operation::Lists { before, after } => {
+                same(&backend.read_lists()?, side(applied, before, after))
+            }
+            Operation::States { before, after } => same(
+                &backend.read_states()?.unwrap_or_default(),
+                side(applied, before, after),
+            ),
+            Operation::TrashedLists { before, after } => {
+                same(&backend.read_trashed_lists()?, side(applied, before, after))
+            }
+            Operation::Task {
+                list_id,
+                before,
+                after,
+            } => {
+                let expected = side(applied, before, after);
+                let Some(Key::Task(_, task_id)) = self.key() else {
+                    return Ok(true);
+                };
+                same(&backend.read_task(*list_id, task_id).ok(), expected)
+            }
+            Operation::TrashedTask {
+                task_id,
+                before,
+                after,
+            } => {
+                let current = backend
+                    .read_trashed_tasks()?
+                    .into_iter()
+                    .find(|t| t.task.id == *task_id);
+                same(&current, side(applied, before, after))
+            }
+            Operation::TrashedListTask {
+                list_id,
+                task_id,
+                before,
+                after,
+            } => {
+                let current = backend
+                    .read_trashed_list_tasks(*list_id)?
+                    .into_iter()
+                    .find(|t| t.id == *task_id);
+                same(&current, side(applied, before, after))
+            }
+            Operation::ListStorage { .. } | Operation::ListTrashed { .. } => true,
+        })
+    }
+
+    /// Writes the after values, or the before values when `forward` is
+    /// false. Returns what the cache has to drop.
+    pub(super) fn apply(&self, backend: &dyn StorageBackend, forward: bool) -> Result<Change> {
+        Ok(match self {
+            Operation::Lists { before, after } => {
+                backend.write_lists(side(forward, before, after).as_slice())?;
+                Change::Lists
+            }
+            Operation::States { before, after } => {
+                backend.write_states(side(forward, before, after).as_slice())?;
+                Change::States
+            }
+            Operation::TrashedLists { before, after } => {
+                backend.write_trashed_lists(side(forward, before, after).as_slice())?;
+                Change::Trash
+            }
+            Operation::Task {
+                list_id,
+                before,
+                after,
+            } => {
+                match (side(forward, before, after), side(forward, after, before)) {
+                    (Some(task), _) => backend.write_task(*list_id, task)?,
+                    (None, Some(task)) => ignore_missing(backend.remove_task(*list_id, task.id))?,
+                    (None, None) => {}
+                }
+                Change::List(*list_id)
+            }
+            Operation::TrashedTask {
+                task_id,
+                before,
+                after,
+            } => {
+                match side(forward, before, after) {
+                    Some(trashed) => backend.write_trashed_task(trashed)?,
+                    None => ignore_missing(backend.remove_trashed_task(*task_id))?,
+                }
+                Change::Trash
+            }
+            Operation::TrashedListTask {
+                list_id,
+                task_id,
+                before,
+                after,
+            } => {
+                match side(forward, before, after) {
+                    Some(task) => backend.write_trashed_list_task(*list_id, task)?,
+                    None => {
+                        ignore_missing(backend.remove_trashed_list_task(*list_id, *task_id))?;
+                        if !backend.has_trashed_list_tasks(*list_id) {
+                            backend.remove_trashed_list(*list_id)?;
+                        }
+                    }
+                }
+                Change::Trash
+            }
+            Operation::ListStorage { list_id, created } => {
+                if *created == forward {
+                    backend.create_list(*list_id)?;
+                } else {
+                    backend.remove_list(*list_id)?;
+                }
+                Change::Unknown
+            }
+            Operation::ListTrashed { list_id, trashed } => {
+                if *trashed == forward {
+                    backend.move_list_to_trash(*list_id)?;
+                } else {
+                    backend.move_list_from_trash(*list_id)?;
+                }
+                Change::Unknown
+            }
+        })
+    }
+}
+
+/// Everything one store operation changed, undone and redone as a unit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub id: Uuid,
+    pub at: Timestamp,
+    pub operations: Vec<Operation>,
+}
+
+impl Entry {
+    /// Folds `next` into this entry if both are quick successive edits of
+    /// the same existing task.
+    fn absorb(&mut self, next: &Entry) -> bool {
+        let (
+            [Operation::Task {
+                list_id,
+                before: Some(_),
+                after: Some(current),
+            }],
+            [Operation::Task {
+                list_id: next_list_id,
+                before: Some(next_before),
+                after: Some(next_after),
+            }],
+        ) = (self.operations.as_mut_slice(), next.operations.as_slice())
+        else {
+            return false;
+        };
+        if list_id != next_list_id
+            || current.id != next_after.id
+            || next.at.duration_since(self.at) > COALESCE_WINDOW
+            || !same(&*current, next_before)
+        {
+            return false;
+        }
+        *current = next_after.clone();
+        self.at = next.at;
+        true
+    }
+}
+
+/// A line of the journal file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Record {
+    /// An entry was recorded. An entry with the id of the last one replaces
+    /// it.
+    Applied(Entry),
+    Undone(Uuid),
+    Redone(Uuid),
+}
+
+#[derive(Debug, Default)]
+struct State {
+    undo: Vec<Entry>,
+    redo: Vec<Entry>,
+    /// Operations of the entry being recorded, and how deeply nested the
+    /// store calls recording it are.
+    pending: Vec<Operation>,
+    depth: usize,
+    /// Length of the journal file when it was last read or written, to
+    /// notice entries appended by other windows.
+    seen_len: u64,
+    records: usize,
+    /// The last entry this store recorded. Only that one is coalesced with,
+    /// never an entry another window recorded in between.
+    last_own: Option<Uuid>,
+}
+
+impl State {
+    fn replay(&mut self, record: Record) {
+        self.records += 1;
+        match record {
+            Record::Applied(entry) => {
+                if self.undo.last().is_some_and(|last| last.id == entry.id) {
+                    self.undo.pop();
+                } else {
+                    self.redo.clear();
+                }
+                self.undo.push(entry);
+                if self.undo.len() > MAX_ENTRIES {
+                    self.undo.remove(0);
+                }
+            }
+            Record::Undone(id) => {
+                if self.undo.last().is_some_and(|last| last.id == id) {
+                    self.redo.extend(self.undo.pop());
+                }
+            }
+            Record::Redone(id) => {
+                if self.redo.last().is_some_and(|last| last.id == id) {
+                    self.undo.extend(self.redo.pop());
+                }
+            }
+        }
+    }
+
+    /// The records that rebuild the current stacks, for compacting the file.
+    fn compacted(&self) -> Vec<Record> {
+        let mut records: Vec<Record> = self.undo.iter().cloned().map(Record::Applied).collect();
+        records.extend(self.redo.iter().rev().cloned().map(Record::Applied));
+        records.extend(self.redo.iter().map(|entry| Record::Undone(entry.id)));
+        records
+    }
+}
+
+/// The undo and redo stacks, backed by an append-only file when the store
+/// has a directory to keep it in.
+#[derive(Debug)]
+pub struct Journal {
+    path: Option<PathBuf>,
+    state: Mutex<State>,
+    write_ahead: WriteAhead,
+}
+
+impl Journal {
+    pub fn new(root: Option<&Path>) -> Self {
+        Self {
+            path: root.map(|root| root.join(JOURNAL_FILE)),
+            state: Mutex::default(),
+            write_ahead: WriteAhead::new(root),
+        }
+    }
+
+    pub fn write_ahead(&self) -> &WriteAhead {
+        &self.write_ahead
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Starts recording an entry, or joins the one being recorded.
+    pub fn begin(&self) {
+        self.state().depth += 1;
+    }
+
+    /// Records an operation before it is carried out, and writes it ahead
+    /// so a crash halfway through the entry can be rolled back.
+    pub fn record(&self, operation: Operation) -> Result<()> {
+        self.write_ahead.log(&operation)?;
+        self.state().pending.push(operation);
+        Ok(())
+    }
+
+    /// Finishes the outermost call and commits what it recorded.
+    pub fn end(&self) -> Result<()> {
+        let operations = {
+            let mut state = self.state();
+            state.depth = state.depth.saturating_sub(1);
+            if state.depth > 0 || state.pending.is_empty() {
+                return Ok(());
+            }
+            std::mem::take(&mut state.pending)
+        };
+        self.write_ahead.clear()?;
+
+        self.refresh()?;
+        let mut entry = Entry {
+            id: Uuid::new_v4(),
+            at: Timestamp::now(),
+            operations,
+        };
+        let last = {
+            let state = self.state();
+            state
+                .undo
+                .last()
+                .filter(|last| state.redo.is_empty() && state.last_own == Some(last.id))
+                .cloned()
+        };
+        if let Some(mut last) = last {
+            if last.absorb(&entry) {
+                entry = last;
+            }
+        }
+        self.state().last_own = Some(entry.id);
+        self.append(Record::Applied(entry))?;
+        self.compact()
+    }
+
+    /// Finishes a call that failed. If it was the outermost one, returns
+    /// the operations it recorded so they can be rolled back.
+    pub fn abort(&self) -> Option<Vec<Operation>> {
+        let mut state = self.state();
+        state.depth = state.depth.saturating_sub(1);
+        (state.depth == 0).then(|| std::mem::take(&mut state.pending))
+    }
+
+    /// Whether there is an entry to undo, as of when the journal was last
+    /// read or written. Does not look at the file.
+    pub fn can_undo(&self) -> bool {
+        !self.state().undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.state().redo.is_empty()
+    }
+
+    /// Forgets every entry, for when the data was replaced wholesale.
+    pub fn clear(&self) -> Result<()> {
+        let mut state = self.state();
+        state.undo.clear();
+        state.redo.clear();
+        state.records = 0;
+        state.seen_len = 0;
+        if let Some(path) = &self.path {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn last_undo(&self) -> Result<Option<Entry>> {
+        self.refresh()?;
+        Ok(self.state().undo.last().cloned())
+    }
+
+    fn last_redo(&self) -> Result<Option<Entry>> {
+        self.refresh()?;
+        Ok(self.state().redo.last().cloned())
+    }
+
+    /// Picks up entries other windows appended since the file was last read.
+    fn refresh(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let len = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        let mut state = self.state();
+        if len == state.seen_len {
+            return Ok(());
+        }
+
+        let content = fs::read_to_string(path).unwrap_or_default();
+        *state = State {
+            pending: std::mem::take(&mut state.pending),
+            depth: state.depth,
+            last_own: state.last_own,
+            seen_len: len,
+            ..State::default()
+        };
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            match ron::from_str::<Record>(line) {
+                Ok(record) => state.replay(record),
+                Err(err) => tracing::warn!("Skipping damaged journal record: {err}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// Rewrites the file with only what is kept once it has grown well past
+    /// that. Called with the store lock held, so no other window appends to
+    /// the file while it is replaced.
+    fn compact(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut state = self.state();
+        if state.records <= MAX_ENTRIES * 4 {
+            return Ok(());
+        }
+        let mut content = String::new();
+        for record in state.compacted() {
+            content.push_str(&ron::to_string(&record)?);
+            content.push('\n');
+        }
+        super::atomic::write(path, &content)?;
+        state.seen_len = content.len() as u64;
+        state.records = state.undo.len() + 2 * state.redo.len();
+        Ok(())
+    }
+
+    fn append(&self, record: Record) -> Result<()> {
+        let line = format!("{}\n", ron::to_string(&record)?);
+        let mut state = self.state();
+        if let Some(path) = &self.path {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            file.write_all(line.as_bytes())?;
+            state.seen_len = file.metadata()?.len();
+        }
+        state.replay(record);
+        Ok(())
+    }
+}
+
+impl Store {
+    /// Whether there is anything to undo. Cheap enough to ask after every
+    /// update; call [`Store::reload_history`] to see what other windows did.
+    pub fn can_undo(&self) -> bool {
+        self.journal().can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.journal().can_redo()
+    }
+
+    /// Picks up the entries other windows recorded since the journal was
+    /// last read.
+    pub fn reload_history(&self) -> Result<()> {
+        self.journal().refresh()
+    }
+
+    /// Reverts the most recent entry. Returns `false` if there was nothing to
+    /// undo.
+    pub fn undo(&self) -> Result<bool> {
+        let _lock = self.lock()?;
+        let Some(entry) = self.journal().last_undo()? else {
+            return Ok(false);
+        };
+        self.replay(&entry, false)?;
+        self.journal().append(Record::Undone(entry.id))?;
+        Ok(true)
+    }
+
+    /// Reapplies the most recently undone entry. Returns `false` if there
+    /// was nothing to redo.
+    pub fn redo(&self) -> Result<bool> {
+        let _lock = self.lock()?;
+        let Some(entry) = self.journal().last_redo()? else {
+            return Ok(false);
+        };
+        self.replay(&entry, true)?;
+        self.journal().append(Record::Redone(entry.id))?;
+        Ok(true)
+    }
+
+    /// Applies an entry's operations in order, or reverts them in reverse
+    /// order. Checks first that every record it touches is as the entry
+    /// left it, or as it expects to find it.
+    fn replay(&self, entry: &Entry, forward: bool) -> Result<()> {
+        let operations: Vec<&Operation> = if forward {
+            entry.operations.iter().collect()
+        } else {
+            entry.operations.iter().rev().collect()
+        };
+
+        let mut checked = HashSet::new();
+        for operation in &operations {
+            let Some(key) = operation.key() else {
+                continue;
+            };
+            if checked.insert(key) && !operation.holds(self.backend(), !forward)? {
+                return Err(Error::Store(StoreError::UndoConflict));
+            }
+        }
+
+        for operation in operations {
+            let change = operation.apply(self.backend(), forward)?;
+            self.forget(change);
+        }
+        Ok(())
+    }
+}
+
+/// `after` when going forward, `before` when going back.
+fn side<'a, T>(forward: bool, before: &'a T, after: &'a T) -> &'a T {
+    if forward {
+        after
+    } else {
+        before
+    }
+}
+
+/// Whether two values would be written out the same.
+pub(super) fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    matches!((ron::to_string(a), ron::to_string(b)), (Ok(a), Ok(b)) if a == b)
+}
+
+/// Treats removing a record that is already gone as done.
+fn ignore_missing(result: Result<()>) -> Result<()> {
+    match result {
+        Err(Error::Store(StoreError::TaskNotFound(_))) => Ok(()),
+        result => result,
+    }
+}
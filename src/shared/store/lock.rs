@@ -0,0 +1,82 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::{Error, Result, StoreError};
+
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+const RETRY_INTERVAL: Duration = Duration::from_millis(25);
+
+thread_local! {
+    /// Lock files this thread currently holds, with their nesting depth.
+    /// Store operations call each other (trashing a list detaches it from
+    /// the list registry, for example), so the lock has to be reentrant.
+    static HELD: RefCell<HashMap<PathBuf, (File, usize)>> = RefCell::new(HashMap::new());
+}
+
+/// Exclusive advisory lock on a file, held for the lifetime of the guard.
+/// Other processes (and other threads of this one) taking the same lock
+/// wait until it is released, or give up with [`StoreError::LockTimeout`].
+pub struct StoreLock {
+    path: PathBuf,
+    // The guard is tied to the thread-local bookkeeping above.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl StoreLock {
+    pub fn acquire(path: &Path) -> Result<Self> {
+        HELD.with_borrow_mut(|held| {
+            if let Some((_, depth)) = held.get_mut(path) {
+                *depth += 1;
+            } else {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .truncate(false)
+                    .read(true)
+                    .write(true)
+                    .open(path)?;
+
+                let started = Instant::now();
+                loop {
+                    match file.try_lock() {
+                        Ok(()) => break,
+                        Err(TryLockError::WouldBlock) if started.elapsed() < LOCK_TIMEOUT => {
+                            std::thread::sleep(RETRY_INTERVAL);
+                        }
+                        Err(TryLockError::WouldBlock) => {
+                            return Err(Error::Store(StoreError::LockTimeout(path.to_path_buf())));
+                        }
+                        Err(TryLockError::Error(err)) => return Err(err.into()),
+                    }
+                }
+                held.insert(path.to_path_buf(), (file, 1));
+            }
+
+            Ok(Self {
+                path: path.to_path_buf(),
+                _not_send: PhantomData,
+            })
+        })
+    }
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        HELD.with_borrow_mut(|held| {
+            let Some((_, depth)) = held.get_mut(&self.path) else {
+                return;
+            };
+            *depth -= 1;
+            if *depth == 0 {
+                if let Some((file, _)) = held.remove(&self.path) {
+                    if let Err(err) = file.unlock() {
+                        tracing::error!("failed to release store lock: {err}");
+                    }
+                }
+            }
+        });
+    }
+}
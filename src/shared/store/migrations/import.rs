@@ -0,0 +1,150 @@
+use crate::shared::store::backend::StorageBackend;
+use crate::{Error, Result};
+
+use super::MigrationReport;
+
+/// Copies everything one backend holds into another: lists, their tasks,
+/// states, the trash, the history of every task and the records set aside
+/// as damaged. Used to move the RON directory layout into a
+/// database backend.
+///
+/// The copy is all or nothing: if anything fails to import, nothing is kept
+/// in the target and the report's errors say why.
+pub struct Importer<'a> {
+    source: &'a dyn StorageBackend,
+    target: &'a dyn StorageBackend,
+}
+
+impl<'a> Importer<'a> {
+    pub fn new(source: &'a dyn StorageBackend, target: &'a dyn StorageBackend) -> Self {
+        Self { source, target }
+    }
+
+    pub fn import(&self) -> Result<MigrationReport> {
+        let mut report = MigrationReport::default();
+        let _source_lock = self.source.lock()?;
+        let _target_lock = self.target.lock()?;
+
+        tracing::info!("Starting import into {:?}", self.target.root());
+        let result = self.target.atomically(&mut || {
+            report = self.copy()?;
+            if report.errors.is_empty() {
+                Ok(())
+            } else {
+                Err(Error::MigrationFailed(format!(
+                    "{} errors during import",
+                    report.errors.len()
+                )))
+            }
+        });
+        match result {
+            Ok(()) => {}
+            Err(Error::MigrationFailed(_)) => return Ok(report),
+            Err(e) => return Err(e),
+        }
+
+        tracing::info!("Import complete!");
+        tracing::info!("Lists imported: {}", report.lists_migrated);
+        tracing::info!("Tasks imported: {}", report.tasks_migrated);
+        tracing::info!("Damaged items imported: {}", report.quarantined_migrated);
+
+        Ok(report)
+    }
+
+    /// Lists whose tasks failed to copy are left out of the target's
+    /// registries, so it never holds a list without its tasks.
+    fn copy(&self) -> Result<MigrationReport> {
+        let mut report = MigrationReport::default();
+
+        let mut lists = Vec::new();
+        for list in self.source.read_lists()? {
+            match self.import_list_tasks(list.id) {
+                Ok(tasks) => {
+                    report.lists_migrated += 1;
+                    report.tasks_migrated += tasks;
+                    lists.push(list);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to import list {}: {}", list.id, e);
+                    report.errors.push(format!("list {}: {}", list.id, e));
+                }
+            }
+        }
+        self.target.write_lists(&lists)?;
+
+        if let Some(states) = self.source.read_states()? {
+            self.target.write_states(&states)?;
+        }
+
+        for trashed in self.source.read_trashed_tasks()? {
+            let imported = self
+                .target
+                .write_trashed_task(&trashed)
+                .and_then(|()| self.import_history(trashed.task.id));
+            if let Err(e) = imported {
+                report
+                    .errors
+                    .push(format!("trashed task {}: {}", trashed.task.id, e));
+            }
+        }
+
+        let mut trashed_lists = Vec::new();
+        for trashed in self.source.read_trashed_lists()? {
+            match self.import_trashed_list_tasks(trashed.list.id) {
+                Ok(()) => trashed_lists.push(trashed),
+                Err(e) => {
+                    tracing::error!("Failed to import trashed list {}: {}", trashed.list.id, e);
+                    report
+                        .errors
+                        .push(format!("trashed list {}: {}", trashed.list.id, e));
+                }
+            }
+        }
+        self.target.write_trashed_lists(&trashed_lists)?;
+
+        // Last, since reading the source above may have set more aside.
+        for item in self.source.quarantined()? {
+            let imported = self
+                .source
+                .quarantined_data(item.id)
+                .and_then(|data| self.target.import_quarantined(&item, &data));
+            match imported {
+                Ok(()) => report.quarantined_migrated += 1,
+                Err(e) => report
+                    .errors
+                    .push(format!("damaged item {}: {}", item.file_name(), e)),
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn import_list_tasks(&self, list_id: uuid::Uuid) -> Result<usize> {
+        self.target.create_list(list_id)?;
+        let tasks = self.source.read_tasks(list_id)?;
+        for task in &tasks {
+            self.target.write_task(list_id, task)?;
+            self.import_history(task.id)?;
+        }
+        Ok(tasks.len())
+    }
+
+    /// Trashed lists keep their tasks grouped, so they are written as a live
+    /// list first and then moved to the trash as a whole.
+    fn import_trashed_list_tasks(&self, list_id: uuid::Uuid) -> Result<()> {
+        self.target.create_list(list_id)?;
+        for task in self.source.read_trashed_list_tasks(list_id)? {
+            self.target.write_task(list_id, &task)?;
+            self.import_history(task.id)?;
+        }
+        self.target.move_list_to_trash(list_id)
+    }
+
+    fn import_history(&self, task_id: uuid::Uuid) -> Result<()> {
+        let revisions = self.source.read_revisions(task_id)?;
+        if revisions.is_empty() {
+            return Ok(());
+        }
+        self.target.write_revisions(task_id, &revisions)
+    }
+}
@@ -1,4 +1,5 @@
-use crate::features::lists::list::List;
+use crate::features::lists::list::{List, ListLayout};
+use crate::features::tasks::due::DueDate;
 use crate::features::tasks::task::Task;
 use crate::shared::store::Store;
 use crate::Result;
@@ -78,13 +79,16 @@ impl Migrator {
 
         tracing::info!("List: {} (old ID: {})", old_list.name, old_list.id);
 
+        let now = jiff::Timestamp::now();
         let new_list = List {
             id: parse_or_generate_uuid(&old_list.id),
             name: old_list.name.clone(),
             description: old_list.description,
             icon: old_list.icon,
             hide_completed: old_list.hide_completed,
-            created_at: jiff::Timestamp::now(),
+            layout: ListLayout::default(),
+            created_at: now,
+            updated_at: now,
         };
 
         self.store.lists().save(&new_list)?;
@@ -183,13 +187,15 @@ impl Migrator {
             state_id: None,
             priority: old_task.priority,
             recurrence: old_task.recurrence,
+            series_id: None,
             tags: old_task.tags,
             parent_id,
             sub_task_ids,
             completion_date: old_task.completion_date,
-            due_date: old_task.due_date,
+            due_date: old_task.due_date.map(DueDate::on),
             reminder_date: old_task.reminder_date,
             creation_date: old_task.created_date_time,
+            updated_at: old_task.last_modified_date_time,
             sort_order: 0,
         };
 
@@ -226,14 +232,16 @@ impl Migrator {
             expanded: old_task.expanded,
             state_id: None,
             priority: old_task.priority,
-            recurrence: old_task.recurrence,
+            recurrence: old_task.recurrence.clone(),
+            series_id: None,
             tags: old_task.tags.clone(),
             parent_id,
             sub_task_ids,
             completion_date: old_task.completion_date,
-            due_date: old_task.due_date,
+            due_date: old_task.due_date.map(DueDate::on),
             reminder_date: old_task.reminder_date,
             creation_date: old_task.created_date_time,
+            updated_at: old_task.last_modified_date_time,
             sort_order: 0,
         };
 
@@ -255,4 +263,16 @@ pub struct MigrationReport {
     pub lists_migrated: usize,
     pub tasks_migrated: usize,
     pub errors: Vec<String>,
+    /// Schema versions a [`SchemaMigrator`](super::SchemaMigrator) step
+    /// moved between. Zero for the other migrations.
+    pub from_version: u32,
+    pub to_version: u32,
+    /// Files a schema step rewrote, registries included.
+    pub records_rewritten: usize,
+    /// Task files a schema step could not parse and moved to quarantine.
+    pub records_quarantined: usize,
+    /// Damaged records an import carried over from the source's quarantine.
+    pub quarantined_migrated: usize,
+    /// Set when nothing was actually written.
+    pub dry_run: bool,
 }
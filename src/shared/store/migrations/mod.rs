@@ -35,11 +35,21 @@
 //! }
 //! ```
 
+#[cfg(feature = "sqlite")]
+mod import;
 mod migrate;
 mod models;
+mod schema;
+mod steps;
 
+#[cfg(feature = "sqlite")]
+pub use import::Importer;
 pub use migrate::{MigrationReport, Migrator};
+#[cfg(feature = "sqlite")]
+use schema::upgrade_sqlite;
+pub use schema::{SchemaMigrator, CURRENT_VERSION, SCHEMA_VERSION_FILE};
 
+use crate::shared::store::check::check_and_repair;
 use crate::shared::store::Store;
 use crate::{Error, Result};
 use std::path::Path;
@@ -49,8 +59,10 @@ pub fn run_migration(
     new_base_dir: impl AsRef<Path>,
 ) -> Result<MigrationReport> {
     let store = Store::open(new_base_dir)?;
-    let migrator = Migrator::new(old_base_dir, store);
-    migrator.migrate()
+    let migrator = Migrator::new(old_base_dir, store.clone());
+    let report = migrator.migrate()?;
+    check_and_repair(&store)?;
+    Ok(report)
 }
 
 pub fn needs_migration(old_base_dir: impl AsRef<Path>) -> bool {
@@ -81,3 +93,82 @@ pub fn migrate(old_base_dir: std::path::PathBuf, new_base_dir: &std::path::Path)
     }
     Ok(())
 }
+
+/// Upgrades the directory layout in `base_dir` to the current schema version.
+/// Runs before the store is opened, so the store only ever sees data in the
+/// format it expects.
+pub fn upgrade(base_dir: &Path) -> Result<()> {
+    let reports = SchemaMigrator::new(base_dir).migrate().map_err(|err| {
+        tracing::error!("Schema upgrade failed: {}", err);
+        Error::MigrationFailed(err.to_string())
+    })?;
+    for report in &reports {
+        if !report.errors.is_empty() {
+            tracing::error!(
+                "\n⚠ Errors upgrading schema v{} -> v{} ({}):",
+                report.from_version,
+                report.to_version,
+                report.errors.len()
+            );
+            for error in &report.errors {
+                tracing::error!("    - {}", error);
+            }
+            return Err(Error::MigrationFailed(format!(
+                "{} errors during schema upgrade",
+                report.errors.len()
+            )));
+        }
+        tracing::info!(
+            "Schema upgraded to v{} ({} files rewritten, {} quarantined)",
+            report.to_version,
+            report.records_rewritten,
+            report.records_quarantined
+        );
+    }
+    Ok(())
+}
+
+/// Reports what [`upgrade`] would rewrite, without touching anything.
+#[allow(dead_code)]
+pub fn plan_upgrade(base_dir: &Path) -> Result<Vec<MigrationReport>> {
+    SchemaMigrator::new(base_dir).dry_run(true).migrate()
+}
+
+/// Opens the SQLite database in `base_dir`, importing the RON layout found
+/// next to it the first time. The RON files are left in place, so the import
+/// is retried on the next start if it did not finish cleanly. Run [`upgrade`]
+/// first: the import reads the RON files as the current schema, and the
+/// database is upgraded on its own from then on.
+#[cfg(feature = "sqlite")]
+pub fn open_sqlite(base_dir: &Path) -> Result<crate::shared::store::backend::SqliteBackend> {
+    use crate::shared::store::backend::{RonDirBackend, SqliteBackend};
+
+    let backend = SqliteBackend::open(base_dir.join("tasks.db"))?;
+    if !backend.is_imported()? {
+        let source = RonDirBackend::open(base_dir)?;
+        let report = Importer::new(&source, &backend).import()?;
+        if !report.errors.is_empty() {
+            tracing::error!("\n⚠ Errors encountered ({}):", report.errors.len());
+            for error in &report.errors {
+                tracing::error!("    - {}", error);
+            }
+            return Err(Error::MigrationFailed(format!(
+                "{} errors during import",
+                report.errors.len()
+            )));
+        }
+        backend.set_schema_version(CURRENT_VERSION)?;
+        backend.mark_imported()?;
+    } else if let Some(report) = upgrade_sqlite(&backend).map_err(|err| {
+        tracing::error!("Schema upgrade failed: {}", err);
+        Error::MigrationFailed(err.to_string())
+    })? {
+        tracing::info!(
+            "Database schema upgraded from v{} to v{} ({} records rewritten)",
+            report.from_version,
+            report.to_version,
+            report.records_rewritten
+        );
+    }
+    Ok(backend)
+}
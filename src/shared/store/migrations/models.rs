@@ -1,8 +1,19 @@
+//! Shapes of records that the live models no longer read or write.
+//!
+//! [`Task`] and [`List`] are the nested layout the [`Migrator`] converts
+//! from. The `v*` modules freeze each schema version's records for the
+//! steps upgrading from and to it, so a step keeps reading exactly what it
+//! was written against however `Task` and `List` change later. Struct names
+//! match the live ones, since the store writes them out.
+//!
+//! [`Migrator`]: super::Migrator
+
 use jiff::{civil::Date, Timestamp};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-use crate::features::tasks::task::{Priority, Recurrence};
+use crate::features::tasks::recurrence::{self, Recurrence};
+use crate::features::tasks::task::Priority;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Task {
@@ -18,7 +29,8 @@ pub struct Task {
     pub completion_date: Option<Timestamp>,
     pub due_date: Option<Date>,
     pub reminder_date: Option<Timestamp>,
-    pub recurrence: Recurrence,
+    #[serde(default, deserialize_with = "recurrence::deserialize")]
+    pub recurrence: Option<Recurrence>,
     #[serde(default)]
     pub expanded: bool,
     pub sub_tasks: Vec<Task>,
@@ -38,3 +50,542 @@ pub struct List {
     #[serde(default)]
     pub hide_completed: bool,
 }
+
+/// Records as schema v2 writes them: every field spelled out. v1 files left
+/// `Task::state_id`, `Task::sort_order` and `List::created_at` to serde
+/// defaults, which these fill in the same way. `updated_at` is only there if
+/// a build from before the version was recorded wrote it.
+pub mod v2 {
+    use jiff::{civil::Date, Timestamp};
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct Task {
+        pub id: Uuid,
+        pub title: String,
+        pub notes: String,
+        pub favorite: bool,
+        pub today: bool,
+        pub expanded: bool,
+        #[serde(default)]
+        pub state_id: Option<Uuid>,
+        pub priority: Priority,
+        pub recurrence: Recurrence,
+        pub tags: Vec<String>,
+        pub parent_id: Option<Uuid>,
+        pub sub_task_ids: Vec<Uuid>,
+        pub completion_date: Option<Timestamp>,
+        pub due_date: Option<Date>,
+        pub reminder_date: Option<Timestamp>,
+        pub creation_date: Timestamp,
+        #[serde(default)]
+        pub sort_order: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub updated_at: Option<Timestamp>,
+    }
+
+    #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+    pub enum Priority {
+        #[default]
+        Low,
+        Normal,
+        High,
+    }
+
+    /// The weekdays a task repeats on.
+    #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+    pub struct Recurrence {
+        pub monday: bool,
+        pub tuesday: bool,
+        pub wednesday: bool,
+        pub thursday: bool,
+        pub friday: bool,
+        pub saturday: bool,
+        pub sunday: bool,
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct TrashedTask {
+        pub task: Task,
+        pub original_list_id: Uuid,
+        pub deleted_at: Timestamp,
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct List {
+        pub id: Uuid,
+        pub name: String,
+        pub description: String,
+        pub icon: Option<String>,
+        pub hide_completed: bool,
+        #[serde(default = "Timestamp::now")]
+        pub created_at: Timestamp,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub updated_at: Option<Timestamp>,
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct TrashedList {
+        pub list: List,
+        pub deleted_at: Timestamp,
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct TaskState {
+        pub id: Uuid,
+        pub name: String,
+        pub is_completed: bool,
+        pub position: u32,
+    }
+}
+
+/// Records as schema v3 writes them: v2 with `updated_at` always set. A
+/// list's `layout` is only there if a build from before it was written out
+/// set it.
+pub mod v3 {
+    use jiff::{civil::Date, Timestamp};
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    use super::v2;
+
+    pub use v2::{Priority, Recurrence};
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct Task {
+        pub id: Uuid,
+        pub title: String,
+        pub notes: String,
+        pub favorite: bool,
+        pub today: bool,
+        pub expanded: bool,
+        pub state_id: Option<Uuid>,
+        pub priority: Priority,
+        pub recurrence: Recurrence,
+        pub tags: Vec<String>,
+        pub parent_id: Option<Uuid>,
+        pub sub_task_ids: Vec<Uuid>,
+        pub completion_date: Option<Timestamp>,
+        pub due_date: Option<Date>,
+        pub reminder_date: Option<Timestamp>,
+        pub creation_date: Timestamp,
+        pub updated_at: Timestamp,
+        pub sort_order: u32,
+    }
+
+    impl From<v2::Task> for Task {
+        /// A task never changed since it was created.
+        fn from(task: v2::Task) -> Self {
+            Self {
+                id: task.id,
+                title: task.title,
+                notes: task.notes,
+                favorite: task.favorite,
+                today: task.today,
+                expanded: task.expanded,
+                state_id: task.state_id,
+                priority: task.priority,
+                recurrence: task.recurrence,
+                tags: task.tags,
+                parent_id: task.parent_id,
+                sub_task_ids: task.sub_task_ids,
+                completion_date: task.completion_date,
+                due_date: task.due_date,
+                reminder_date: task.reminder_date,
+                creation_date: task.creation_date,
+                updated_at: task.updated_at.unwrap_or(task.creation_date),
+                sort_order: task.sort_order,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct TrashedTask {
+        pub task: Task,
+        pub original_list_id: Uuid,
+        pub deleted_at: Timestamp,
+    }
+
+    impl From<v2::TrashedTask> for TrashedTask {
+        fn from(trashed: v2::TrashedTask) -> Self {
+            Self {
+                task: trashed.task.into(),
+                original_list_id: trashed.original_list_id,
+                deleted_at: trashed.deleted_at,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct List {
+        pub id: Uuid,
+        pub name: String,
+        pub description: String,
+        pub icon: Option<String>,
+        pub hide_completed: bool,
+        pub created_at: Timestamp,
+        pub updated_at: Timestamp,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub layout: Option<ListLayout>,
+    }
+
+    #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+    pub enum ListLayout {
+        #[default]
+        List,
+        Board,
+    }
+
+    impl From<v2::List> for List {
+        /// A list never changed since it was created.
+        fn from(list: v2::List) -> Self {
+            Self {
+                id: list.id,
+                name: list.name,
+                description: list.description,
+                icon: list.icon,
+                hide_completed: list.hide_completed,
+                created_at: list.created_at,
+                updated_at: list.updated_at.unwrap_or(list.created_at),
+                layout: None,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct TrashedList {
+        pub list: List,
+        pub deleted_at: Timestamp,
+    }
+
+    impl From<v2::TrashedList> for TrashedList {
+        fn from(trashed: v2::TrashedList) -> Self {
+            Self {
+                list: trashed.list.into(),
+                deleted_at: trashed.deleted_at,
+            }
+        }
+    }
+}
+
+/// Records as schema v4 writes them: the weekday flags of `recurrence`
+/// replaced by the RRULE string of the same weekly rule.
+pub mod v4 {
+    use jiff::{civil::Date, Timestamp};
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    use super::{v2, v3};
+
+    pub use v3::Priority;
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct Task {
+        pub id: Uuid,
+        pub title: String,
+        pub notes: String,
+        pub favorite: bool,
+        pub today: bool,
+        pub expanded: bool,
+        pub state_id: Option<Uuid>,
+        pub priority: Priority,
+        pub recurrence: Option<String>,
+        pub tags: Vec<String>,
+        pub parent_id: Option<Uuid>,
+        pub sub_task_ids: Vec<Uuid>,
+        pub completion_date: Option<Timestamp>,
+        pub due_date: Option<Date>,
+        pub reminder_date: Option<Timestamp>,
+        pub creation_date: Timestamp,
+        pub updated_at: Timestamp,
+        pub sort_order: u32,
+    }
+
+    impl From<v3::Task> for Task {
+        fn from(task: v3::Task) -> Self {
+            Self {
+                id: task.id,
+                title: task.title,
+                notes: task.notes,
+                favorite: task.favorite,
+                today: task.today,
+                expanded: task.expanded,
+                state_id: task.state_id,
+                priority: task.priority,
+                recurrence: weekly_rule(task.recurrence),
+                tags: task.tags,
+                parent_id: task.parent_id,
+                sub_task_ids: task.sub_task_ids,
+                completion_date: task.completion_date,
+                due_date: task.due_date,
+                reminder_date: task.reminder_date,
+                creation_date: task.creation_date,
+                updated_at: task.updated_at,
+                sort_order: task.sort_order,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct TrashedTask {
+        pub task: Task,
+        pub original_list_id: Uuid,
+        pub deleted_at: Timestamp,
+    }
+
+    impl From<v3::TrashedTask> for TrashedTask {
+        fn from(trashed: v3::TrashedTask) -> Self {
+            Self {
+                task: trashed.task.into(),
+                original_list_id: trashed.original_list_id,
+                deleted_at: trashed.deleted_at,
+            }
+        }
+    }
+
+    /// `FREQ=WEEKLY;BYDAY=MO,TH` for a task repeating on Mondays and
+    /// Thursdays, or nothing if no day is set.
+    fn weekly_rule(days: v2::Recurrence) -> Option<String> {
+        let codes: Vec<&str> = [
+            (days.monday, "MO"),
+            (days.tuesday, "TU"),
+            (days.wednesday, "WE"),
+            (days.thursday, "TH"),
+            (days.friday, "FR"),
+            (days.saturday, "SA"),
+            (days.sunday, "SU"),
+        ]
+        .into_iter()
+        .filter_map(|(set, code)| set.then_some(code))
+        .collect();
+        (!codes.is_empty()).then(|| format!("FREQ=WEEKLY;BYDAY={}", codes.join(",")))
+    }
+}
+
+/// Records as schema v5 writes them: `due_date` with an optional time and
+/// time zone rather than a bare date. `series_id` is only there if a build
+/// from before it was written out set it.
+pub mod v5 {
+    use jiff::{
+        civil::{Date, Time},
+        Timestamp,
+    };
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    use super::v4;
+
+    pub use v4::Priority;
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct Task {
+        pub id: Uuid,
+        pub title: String,
+        pub notes: String,
+        pub favorite: bool,
+        pub today: bool,
+        pub expanded: bool,
+        pub state_id: Option<Uuid>,
+        pub priority: Priority,
+        pub recurrence: Option<String>,
+        pub tags: Vec<String>,
+        pub parent_id: Option<Uuid>,
+        pub sub_task_ids: Vec<Uuid>,
+        pub completion_date: Option<Timestamp>,
+        pub due_date: Option<DueDate>,
+        pub reminder_date: Option<Timestamp>,
+        pub creation_date: Timestamp,
+        pub updated_at: Timestamp,
+        pub sort_order: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub series_id: Option<Uuid>,
+    }
+
+    impl From<v4::Task> for Task {
+        /// Due some time on the day it was due on before.
+        fn from(task: v4::Task) -> Self {
+            Self {
+                id: task.id,
+                title: task.title,
+                notes: task.notes,
+                favorite: task.favorite,
+                today: task.today,
+                expanded: task.expanded,
+                state_id: task.state_id,
+                priority: task.priority,
+                recurrence: task.recurrence,
+                tags: task.tags,
+                parent_id: task.parent_id,
+                sub_task_ids: task.sub_task_ids,
+                completion_date: task.completion_date,
+                due_date: task.due_date.map(|date| DueDate {
+                    date,
+                    time: None,
+                    time_zone: None,
+                }),
+                reminder_date: task.reminder_date,
+                creation_date: task.creation_date,
+                updated_at: task.updated_at,
+                sort_order: task.sort_order,
+                series_id: None,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct DueDate {
+        pub date: Date,
+        pub time: Option<Time>,
+        pub time_zone: Option<String>,
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct TrashedTask {
+        pub task: Task,
+        pub original_list_id: Uuid,
+        pub deleted_at: Timestamp,
+    }
+
+    impl From<v4::TrashedTask> for TrashedTask {
+        fn from(trashed: v4::TrashedTask) -> Self {
+            Self {
+                task: trashed.task.into(),
+                original_list_id: trashed.original_list_id,
+                deleted_at: trashed.deleted_at,
+            }
+        }
+    }
+}
+
+/// Records as schema v6 writes them: v5 with `series_id` always set.
+pub mod v6 {
+    use jiff::Timestamp;
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    use super::v5;
+
+    pub use v5::{DueDate, Priority};
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct Task {
+        pub id: Uuid,
+        pub title: String,
+        pub notes: String,
+        pub favorite: bool,
+        pub today: bool,
+        pub expanded: bool,
+        pub state_id: Option<Uuid>,
+        pub priority: Priority,
+        pub recurrence: Option<String>,
+        pub series_id: Option<Uuid>,
+        pub tags: Vec<String>,
+        pub parent_id: Option<Uuid>,
+        pub sub_task_ids: Vec<Uuid>,
+        pub completion_date: Option<Timestamp>,
+        pub due_date: Option<DueDate>,
+        pub reminder_date: Option<Timestamp>,
+        pub creation_date: Timestamp,
+        pub updated_at: Timestamp,
+        pub sort_order: u32,
+    }
+
+    impl From<v5::Task> for Task {
+        /// A task not yet part of a series, unless it already was.
+        fn from(task: v5::Task) -> Self {
+            Self {
+                id: task.id,
+                title: task.title,
+                notes: task.notes,
+                favorite: task.favorite,
+                today: task.today,
+                expanded: task.expanded,
+                state_id: task.state_id,
+                priority: task.priority,
+                recurrence: task.recurrence,
+                series_id: task.series_id,
+                tags: task.tags,
+                parent_id: task.parent_id,
+                sub_task_ids: task.sub_task_ids,
+                completion_date: task.completion_date,
+                due_date: task.due_date,
+                reminder_date: task.reminder_date,
+                creation_date: task.creation_date,
+                updated_at: task.updated_at,
+                sort_order: task.sort_order,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct TrashedTask {
+        pub task: Task,
+        pub original_list_id: Uuid,
+        pub deleted_at: Timestamp,
+    }
+
+    impl From<v5::TrashedTask> for TrashedTask {
+        fn from(trashed: v5::TrashedTask) -> Self {
+            Self {
+                task: trashed.task.into(),
+                original_list_id: trashed.original_list_id,
+                deleted_at: trashed.deleted_at,
+            }
+        }
+    }
+}
+
+/// Records as schema v7 writes them: v6 with a list's `layout` always set.
+pub mod v7 {
+    use jiff::Timestamp;
+    use serde::{Deserialize, Serialize};
+    use uuid::Uuid;
+
+    use super::v3;
+
+    pub use v3::ListLayout;
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct List {
+        pub id: Uuid,
+        pub name: String,
+        pub description: String,
+        pub icon: Option<String>,
+        pub hide_completed: bool,
+        pub layout: ListLayout,
+        pub created_at: Timestamp,
+        pub updated_at: Timestamp,
+    }
+
+    impl From<v3::List> for List {
+        /// A list shown as rows, unless it already was a board.
+        fn from(list: v3::List) -> Self {
+            Self {
+                id: list.id,
+                name: list.name,
+                description: list.description,
+                icon: list.icon,
+                hide_completed: list.hide_completed,
+                layout: list.layout.unwrap_or_default(),
+                created_at: list.created_at,
+                updated_at: list.updated_at,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct TrashedList {
+        pub list: List,
+        pub deleted_at: Timestamp,
+    }
+
+    impl From<v3::TrashedList> for TrashedList {
+        fn from(trashed: v3::TrashedList) -> Self {
+            Self {
+                list: trashed.list.into(),
+                deleted_at: trashed.deleted_at,
+            }
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::steps::{steps, MigrationStep};
+use super::MigrationReport;
+use crate::shared::store::atomic;
+#[cfg(feature = "sqlite")]
+use crate::shared::store::backend::SqliteBackend;
+use crate::shared::store::backend::{RecordKind, RonDirBackend, StorageBackend};
+use crate::{Error, Result, StoreError};
+
+pub const SCHEMA_VERSION_FILE: &str = "schema_version";
+
+/// Schema version this build reads and writes.
+pub const CURRENT_VERSION: u32 = 7;
+
+/// Stores written before `schema_version` existed.
+const UNVERSIONED: u32 = 1;
+
+/// Brings the directory layout in `base_dir` up to [`CURRENT_VERSION`] by
+/// running every step between the stored version and the current one.
+///
+/// A step is all or nothing: each file it touches is rewritten in memory
+/// first, and nothing is written if any of them fails. The version file is
+/// only bumped once a step's files are all on disk, so an interrupted run
+/// resumes from the same step.
+pub struct SchemaMigrator {
+    base_dir: PathBuf,
+    dry_run: bool,
+}
+
+impl SchemaMigrator {
+    pub fn new(base_dir: impl AsRef<Path>) -> Self {
+        Self {
+            base_dir: base_dir.as_ref().to_path_buf(),
+            dry_run: false,
+        }
+    }
+
+    /// Runs the steps without writing anything. The reports show what would
+    /// have been rewritten.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Returns one report per step that ran. Stops at the first step that
+    /// reports errors, leaving the store at the version before it.
+    pub fn migrate(&self) -> Result<Vec<MigrationReport>> {
+        let backend = RonDirBackend::open_for_upgrade(&self.base_dir)?;
+        let _lock = backend.lock()?;
+        let records = backend.records()?;
+
+        let Some(version) = self.read_version()? else {
+            if records.is_empty() {
+                // Nothing to upgrade; a new store starts out current.
+                if !self.dry_run {
+                    self.write_version(CURRENT_VERSION)?;
+                }
+                return Ok(vec![]);
+            }
+            return self.run(&backend, UNVERSIONED, records);
+        };
+
+        if version > CURRENT_VERSION {
+            return Err(StoreError::UnsupportedSchema {
+                found: version,
+                supported: CURRENT_VERSION,
+            }
+            .into());
+        }
+        self.run(&backend, version, records)
+    }
+
+    fn run(
+        &self,
+        backend: &RonDirBackend,
+        from: u32,
+        mut records: Vec<(RecordKind, PathBuf)>,
+    ) -> Result<Vec<MigrationReport>> {
+        // Contents as left by the steps so far, so a dry run sees each step's
+        // output in the next one.
+        let mut overlay: HashMap<PathBuf, String> = HashMap::new();
+        let mut reports = Vec::new();
+
+        for step in steps().iter().filter(|step| step.source_version() >= from) {
+            let report = self.run_step(backend, step.as_ref(), &mut records, &mut overlay)?;
+            let failed = !report.errors.is_empty();
+            reports.push(report);
+            if failed {
+                break;
+            }
+        }
+        Ok(reports)
+    }
+
+    fn run_step(
+        &self,
+        backend: &RonDirBackend,
+        step: &dyn MigrationStep,
+        records: &mut Vec<(RecordKind, PathBuf)>,
+        overlay: &mut HashMap<PathBuf, String>,
+    ) -> Result<MigrationReport> {
+        let from = step.source_version();
+        let mut report = MigrationReport {
+            from_version: from,
+            to_version: from + 1,
+            dry_run: self.dry_run,
+            ..Default::default()
+        };
+        tracing::info!(
+            "Schema v{} -> v{}: {}",
+            report.from_version,
+            report.to_version,
+            step.description()
+        );
+
+        let mut rewrites = Vec::new();
+        let mut damaged = Vec::new();
+        for (kind, path) in records.iter() {
+            let content = match overlay.get(path) {
+                Some(content) => content.clone(),
+                None => fs::read_to_string(path)?,
+            };
+            match step.migrate(*kind, &content) {
+                Ok(Some(rewritten)) => rewrites.push((*kind, path.clone(), rewritten)),
+                Ok(None) => {}
+                // A task file that does not parse is set aside like it would
+                // be on any other read, rather than holding up the upgrade.
+                Err(Error::Deserialize(e))
+                    if matches!(kind, RecordKind::Task | RecordKind::TrashedTask) =>
+                {
+                    damaged.push((*kind, path.clone(), e));
+                }
+                Err(e) => {
+                    tracing::error!("Failed to migrate {:?}: {}", path, e);
+                    report.errors.push(format!("{:?}: {}", path, e));
+                }
+            }
+        }
+        if !report.errors.is_empty() {
+            return Ok(report);
+        }
+
+        for (kind, path, error) in damaged {
+            if !self.dry_run {
+                backend.quarantine(kind, &path, &error)?;
+            }
+            report.records_quarantined += 1;
+            records.retain(|(_, p)| *p != path);
+        }
+
+        for (kind, path, rewritten) in rewrites {
+            if !self.dry_run {
+                match kind {
+                    RecordKind::Lists | RecordKind::States | RecordKind::TrashedLists => {
+                        atomic::write_with_backup(&path, &rewritten)?
+                    }
+                    RecordKind::Task | RecordKind::TrashedTask => atomic::write(&path, &rewritten)?,
+                }
+            }
+            if kind == RecordKind::Task {
+                report.tasks_migrated += 1;
+            }
+            report.records_rewritten += 1;
+            overlay.insert(path, rewritten);
+        }
+        if !self.dry_run {
+            self.write_version(report.to_version)?;
+        }
+        Ok(report)
+    }
+
+    /// The stored schema version, or `None` for a store that predates the
+    /// version file.
+    fn read_version(&self) -> Result<Option<u32>> {
+        let path = self.base_dir.join(SCHEMA_VERSION_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)?;
+        content
+            .trim()
+            .parse()
+            .map(Some)
+            .map_err(|_| StoreError::InvalidSchemaVersion(path, content.trim().to_string()).into())
+    }
+
+    fn write_version(&self, version: u32) -> Result<()> {
+        atomic::write(
+            &self.base_dir.join(SCHEMA_VERSION_FILE),
+            format!("{version}\n"),
+        )?;
+        Ok(())
+    }
+}
+
+/// Brings the records in a SQLite database up to [`CURRENT_VERSION`]. Each
+/// record goes through every step from the recorded version on, and the
+/// database takes the results and the new version in one transaction.
+/// Databases written before the version was recorded start from v1, which
+/// the steps accept whatever the rows actually hold.
+#[cfg(feature = "sqlite")]
+pub fn upgrade_sqlite(backend: &SqliteBackend) -> Result<Option<MigrationReport>> {
+    let _lock = backend.lock()?;
+    let from = backend.schema_version()?.unwrap_or(UNVERSIONED);
+    if from > CURRENT_VERSION {
+        return Err(StoreError::UnsupportedSchema {
+            found: from,
+            supported: CURRENT_VERSION,
+        }
+        .into());
+    }
+    if from == CURRENT_VERSION {
+        return Ok(None);
+    }
+
+    let steps: Vec<_> = steps()
+        .into_iter()
+        .filter(|step| step.source_version() >= from)
+        .collect();
+    for step in &steps {
+        tracing::info!(
+            "Schema v{} -> v{}: {}",
+            step.source_version(),
+            step.source_version() + 1,
+            step.description()
+        );
+    }
+    let rewritten = backend.upgrade_records(CURRENT_VERSION, |kind, content| {
+        let mut content = content.to_string();
+        for step in &steps {
+            if let Some(rewritten) = step.migrate(kind, &content)? {
+                content = rewritten;
+            }
+        }
+        Ok(content)
+    })?;
+    Ok(Some(MigrationReport {
+        from_version: from,
+        to_version: CURRENT_VERSION,
+        records_rewritten: rewritten,
+        ..Default::default()
+    }))
+}
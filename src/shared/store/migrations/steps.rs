@@ -0,0 +1,233 @@
+//! Ordered schema migration steps.
+//!
+//! Each step upgrades the store from one schema version to the next by
+//! rewriting the raw contents of the files it cares about. Steps receive
+//! text and parse it into the records frozen for their version in
+//! `models`, never into `Task`, `List` or `TaskState`, so they keep working
+//! after the live models move on.
+//!
+//! A run interrupted before the version is bumped starts the step over, on
+//! files it may already have rewritten, so a step must also accept its own
+//! output.
+//!
+//! To change the on-disk format, freeze the new shape in `models`, add a
+//! step here, append it to [`steps`] and bump
+//! [`CURRENT_VERSION`](super::schema::CURRENT_VERSION).
+
+use ron::extensions::Extensions;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::models::{v2, v3, v4, v5, v6, v7};
+use crate::shared::store::backend::{pretty, RecordKind};
+use crate::Result;
+
+pub trait MigrationStep {
+    /// Version this step upgrades from. It leaves the store at the next one.
+    fn source_version(&self) -> u32;
+
+    fn description(&self) -> &'static str;
+
+    /// Returns the new contents of a file, or `None` to leave it as is.
+    fn migrate(&self, kind: RecordKind, content: &str) -> Result<Option<String>>;
+}
+
+/// Every known step, in the order they apply.
+pub fn steps() -> Vec<Box<dyn MigrationStep>> {
+    vec![
+        Box::new(MaterializeDefaults),
+        Box::new(BackfillUpdatedAt),
+        Box::new(RecurrenceRules),
+        Box::new(DueTimes),
+        Box::new(MaterializeSeries),
+        Box::new(MaterializeLayout),
+    ]
+}
+
+/// v1 → v2: writes out every field that older files leave to
+/// `#[serde(default)]`, such as `Task::state_id`, `Task::sort_order` and
+/// `List::created_at`, so later steps can rely on them being present.
+struct MaterializeDefaults;
+
+impl MigrationStep for MaterializeDefaults {
+    fn source_version(&self) -> u32 {
+        1
+    }
+
+    fn description(&self) -> &'static str {
+        "Write out fields previously filled in by serde defaults"
+    }
+
+    fn migrate(&self, kind: RecordKind, content: &str) -> Result<Option<String>> {
+        match kind {
+            RecordKind::Lists => rewrite::<Vec<v2::List>>(content),
+            RecordKind::States => rewrite::<Vec<v2::TaskState>>(content),
+            RecordKind::Task => rewrite::<v2::Task>(content),
+            RecordKind::TrashedTask => rewrite::<v2::TrashedTask>(content),
+            RecordKind::TrashedLists => rewrite::<Vec<v2::TrashedList>>(content),
+        }
+    }
+}
+
+/// v2 → v3: dates `Task::updated_at` and `List::updated_at`, which older
+/// files do not have, to when the record was created.
+struct BackfillUpdatedAt;
+
+impl MigrationStep for BackfillUpdatedAt {
+    fn source_version(&self) -> u32 {
+        2
+    }
+
+    fn description(&self) -> &'static str {
+        "Date the last change of tasks and lists to when they were created"
+    }
+
+    fn migrate(&self, kind: RecordKind, content: &str) -> Result<Option<String>> {
+        match kind {
+            RecordKind::Lists => convert::<Vec<v2::List>, Vec<v3::List>>(content, |lists| {
+                lists.into_iter().map(Into::into).collect()
+            }),
+            RecordKind::States => Ok(None),
+            RecordKind::Task => convert::<v2::Task, v3::Task>(content, Into::into),
+            RecordKind::TrashedTask => {
+                convert::<v2::TrashedTask, v3::TrashedTask>(content, Into::into)
+            }
+            RecordKind::TrashedLists => {
+                convert::<Vec<v2::TrashedList>, Vec<v3::TrashedList>>(content, |lists| {
+                    lists.into_iter().map(Into::into).collect()
+                })
+            }
+        }
+    }
+}
+
+/// v3 → v4: replaces the weekday flags of `Task::recurrence` with the RRULE
+/// string of the equivalent weekly rule.
+struct RecurrenceRules;
+
+impl MigrationStep for RecurrenceRules {
+    fn source_version(&self) -> u32 {
+        3
+    }
+
+    fn description(&self) -> &'static str {
+        "Store task recurrence as RRULE strings"
+    }
+
+    fn migrate(&self, kind: RecordKind, content: &str) -> Result<Option<String>> {
+        match kind {
+            RecordKind::Task => upgrade::<v3::Task, v4::Task>(content),
+            RecordKind::TrashedTask => upgrade::<v3::TrashedTask, v4::TrashedTask>(content),
+            RecordKind::Lists | RecordKind::States | RecordKind::TrashedLists => Ok(None),
+        }
+    }
+}
+
+/// v4 → v5: turns the bare date of `Task::due_date` into a due date with
+/// no time.
+struct DueTimes;
+
+impl MigrationStep for DueTimes {
+    fn source_version(&self) -> u32 {
+        4
+    }
+
+    fn description(&self) -> &'static str {
+        "Store task due dates with an optional time and time zone"
+    }
+
+    fn migrate(&self, kind: RecordKind, content: &str) -> Result<Option<String>> {
+        match kind {
+            RecordKind::Task => upgrade::<v4::Task, v5::Task>(content),
+            RecordKind::TrashedTask => upgrade::<v4::TrashedTask, v5::TrashedTask>(content),
+            RecordKind::Lists | RecordKind::States | RecordKind::TrashedLists => Ok(None),
+        }
+    }
+}
+
+/// v5 → v6: writes out `Task::series_id`, which tasks that never recurred
+/// left out.
+struct MaterializeSeries;
+
+impl MigrationStep for MaterializeSeries {
+    fn source_version(&self) -> u32 {
+        5
+    }
+
+    fn description(&self) -> &'static str {
+        "Write out the recurring series of every task"
+    }
+
+    fn migrate(&self, kind: RecordKind, content: &str) -> Result<Option<String>> {
+        match kind {
+            RecordKind::Task => convert::<v5::Task, v6::Task>(content, Into::into),
+            RecordKind::TrashedTask => {
+                convert::<v5::TrashedTask, v6::TrashedTask>(content, Into::into)
+            }
+            RecordKind::Lists | RecordKind::States | RecordKind::TrashedLists => Ok(None),
+        }
+    }
+}
+
+/// v6 → v7: writes out `List::layout`, which lists never switched to a
+/// board left out.
+struct MaterializeLayout;
+
+impl MigrationStep for MaterializeLayout {
+    fn source_version(&self) -> u32 {
+        6
+    }
+
+    fn description(&self) -> &'static str {
+        "Write out the layout of every list"
+    }
+
+    fn migrate(&self, kind: RecordKind, content: &str) -> Result<Option<String>> {
+        match kind {
+            RecordKind::Lists => convert::<Vec<v3::List>, Vec<v7::List>>(content, |lists| {
+                lists.into_iter().map(Into::into).collect()
+            }),
+            RecordKind::TrashedLists => {
+                convert::<Vec<v3::TrashedList>, Vec<v7::TrashedList>>(content, |lists| {
+                    lists.into_iter().map(Into::into).collect()
+                })
+            }
+            RecordKind::States | RecordKind::Task | RecordKind::TrashedTask => Ok(None),
+        }
+    }
+}
+
+/// Parses a file and writes it back out, filling in whatever `T` reads
+/// differently from how it is written.
+fn rewrite<T: Serialize + DeserializeOwned>(content: &str) -> Result<Option<String>> {
+    convert::<T, T>(content, |value| value)
+}
+
+/// Converts a file from `T` to `U`, unless it is already a `U`: a field
+/// changing type cannot be read both ways, so the step's own output is
+/// recognised instead.
+fn upgrade<T, U>(content: &str) -> Result<Option<String>>
+where
+    T: DeserializeOwned + Into<U>,
+    U: Serialize + DeserializeOwned,
+{
+    if ron::from_str::<U>(content).is_ok() {
+        return Ok(None);
+    }
+    convert::<T, U>(content, Into::into)
+}
+
+/// Parses a file as `T`, converts it and writes the result out.
+///
+/// Optional fields are also read when written bare, the way the next version
+/// writes them once they are required, so a step accepts its own output.
+fn convert<T: DeserializeOwned, U: Serialize>(
+    content: &str,
+    convert: impl FnOnce(T) -> U,
+) -> Result<Option<String>> {
+    let value: T = ron::Options::default()
+        .with_default_extension(Extensions::IMPLICIT_SOME)
+        .from_str(content)?;
+    let rewritten = ron::ser::to_string_pretty(&convert(value), pretty())?;
+    Ok((rewritten != content).then_some(rewritten))
+}
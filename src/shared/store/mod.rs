@@ -1,5 +1,21 @@
+mod atomic;
+pub mod backend;
+mod cache;
+pub mod change;
+pub mod check;
+mod echo;
+pub mod history;
+mod journal;
+mod lock;
 pub mod migrations;
+pub mod my_day;
+pub mod quarantine;
+pub mod query;
+pub mod snapshot;
+pub mod states;
 pub mod store;
+pub mod tags;
+mod transaction;
 pub mod watcher;
 
 pub use store::Store;
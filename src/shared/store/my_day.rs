@@ -0,0 +1,44 @@
+//! The tasks picked for today, across every list.
+//!
+//! A task is in My Day while [`Task::today`] is set. The flag does not
+//! expire by itself: on the first launch of a day the user chooses to carry
+//! the tasks over or start afresh with [`Store::clear_my_day`].
+
+use jiff::civil::Date;
+use uuid::Uuid;
+
+use super::query::{TaskQuery, TaskSort};
+use super::Store;
+use crate::features::tasks::task::Task;
+use crate::Result;
+
+impl Store {
+    /// Takes every task out of My Day, as one undo step. Returns how many
+    /// tasks changed.
+    pub fn clear_my_day(&self) -> Result<usize> {
+        let picked = self.query(&TaskQuery::new().today(true))?;
+        if picked.is_empty() {
+            return Ok(0);
+        }
+
+        self.transaction(|tx| {
+            for (list_id, task) in &picked {
+                tx.tasks(*list_id)
+                    .update(task.id, |task| task.today = false)?;
+            }
+            Ok(picked.len())
+        })
+    }
+
+    /// Open tasks worth adding to My Day on `today`: those due by then and
+    /// not picked yet, most overdue first.
+    pub fn my_day_suggestions(&self, today: Date) -> Result<Vec<(Uuid, Task)>> {
+        self.query(
+            &TaskQuery::new()
+                .today(false)
+                .completed(false)
+                .due_between(Date::MIN, today)
+                .sort_by(TaskSort::Due),
+        )
+    }
+}
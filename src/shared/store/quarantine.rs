@@ -0,0 +1,59 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use jiff::Timestamp;
+use ron::error::SpannedError;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::backend::RecordKind;
+
+/// A record that could not be parsed and was moved aside instead of being
+/// skipped, so it can be inspected, fixed by hand and put back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedItem {
+    pub id: Uuid,
+    pub kind: RecordKind,
+    /// Where the record was found, relative to the store root.
+    pub origin: PathBuf,
+    pub error: ParseError,
+    pub quarantined_at: Timestamp,
+    /// The damaged file as it sits in quarantine now.
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl QuarantinedItem {
+    /// File name the record had before it was quarantined.
+    pub fn file_name(&self) -> String {
+        self.origin
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// Why a record could not be parsed, with the region of the file ron
+/// pointed at. Lines and columns start at 1.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseError {
+    pub message: String,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+}
+
+impl From<&SpannedError> for ParseError {
+    fn from(error: &SpannedError) -> Self {
+        Self {
+            message: error.code.to_string(),
+            start: (error.span.start.line, error.span.start.col),
+            end: (error.span.end.line, error.span.end.col),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.start.0, self.start.1, self.message)
+    }
+}
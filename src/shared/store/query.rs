@@ -0,0 +1,284 @@
+//! Declarative lookups of tasks across lists.
+//!
+//! A [`TaskQuery`] describes the tasks to find and [`Store::query`] runs it
+//! over every registered list. Filters combine: a task has to pass all of
+//! them. Queries that bound the reminder or due date, or ask for favorites,
+//! are answered from the cache's secondary indexes instead of a full scan.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use jiff::civil::Date;
+use jiff::{Timestamp, ToSpan};
+use uuid::Uuid;
+
+use super::cache::TaskKey;
+use super::Store;
+use crate::features::tasks::due::DueDate;
+use crate::features::tasks::task::{Priority, Task};
+use crate::Result;
+
+/// Which tasks to find, how to order them and how many to return.
+///
+/// Built up from [`TaskQuery::new`], which matches every task:
+///
+/// ```ignore
+/// let next = TaskQuery::new()
+///     .completed(false)
+///     .due_between(today, today.saturating_add(7.days()))
+///     .sort_by(TaskSort::Due)
+///     .limit(10);
+/// let tasks = store.query(&next)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TaskQuery {
+    lists: Vec<Uuid>,
+    states: Vec<Uuid>,
+    priorities: Vec<Priority>,
+    tags: Vec<String>,
+    favorite: Option<bool>,
+    today: Option<bool>,
+    completed: Option<bool>,
+    due: Option<RangeInclusive<Date>>,
+    reminder: Option<RangeInclusive<Timestamp>>,
+    parent: Option<Parent>,
+    sort: Option<TaskSort>,
+    descending: bool,
+    limit: Option<usize>,
+}
+
+/// Where a task sits in the sub-task tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parent {
+    /// Tasks that are not a sub-task of another.
+    None,
+    /// Sub-tasks of any task.
+    Any,
+    /// Direct sub-tasks of one task.
+    Task(Uuid),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskSort {
+    Created,
+    Updated,
+    Title,
+    Priority,
+    Due,
+    Reminder,
+    Manual,
+}
+
+#[allow(dead_code)]
+impl TaskQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only tasks of this list. Repeat to allow several lists.
+    pub fn in_list(mut self, list_id: Uuid) -> Self {
+        self.lists.push(list_id);
+        self
+    }
+
+    /// Only tasks in this state. Repeat to allow several states.
+    pub fn in_state(mut self, state_id: Uuid) -> Self {
+        self.states.push(state_id);
+        self
+    }
+
+    /// Only tasks of this priority. Repeat to allow several priorities.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priorities.push(priority);
+        self
+    }
+
+    /// Only tasks carrying this tag. Repeat to require several tags.
+    pub fn tagged(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    pub fn favorite(mut self, favorite: bool) -> Self {
+        self.favorite = Some(favorite);
+        self
+    }
+
+    pub fn today(mut self, today: bool) -> Self {
+        self.today = Some(today);
+        self
+    }
+
+    pub fn completed(mut self, completed: bool) -> Self {
+        self.completed = Some(completed);
+        self
+    }
+
+    /// Only tasks due between `start` and `end`, both included, on the
+    /// dates they fall on in the system zone.
+    pub fn due_between(mut self, start: Date, end: Date) -> Self {
+        self.due = Some(start..=end);
+        self
+    }
+
+    /// Only tasks with a reminder between `start` and `end`, both included.
+    pub fn reminder_between(mut self, start: Timestamp, end: Timestamp) -> Self {
+        self.reminder = Some(start..=end);
+        self
+    }
+
+    pub fn parent(mut self, parent: Parent) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Orders the results. Tasks missing the sorted-on date go last either
+    /// way. Without it, results follow list order, then creation date.
+    pub fn sort_by(mut self, sort: TaskSort) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
+    pub fn descending(mut self) -> Self {
+        self.descending = true;
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Whether a task of `list_id` passes every filter. Views holding the
+    /// results of a query use it to decide whether a task that changed
+    /// still belongs in them.
+    pub fn matches(&self, list_id: Uuid, task: &Task) -> bool {
+        (self.lists.is_empty() || self.lists.contains(&list_id))
+            && (self.states.is_empty() || self.states.contains(&task.effective_state_id()))
+            && (self.priorities.is_empty() || self.priorities.contains(&task.priority))
+            && self.tags.iter().all(|tag| task.tags.contains(tag))
+            && self
+                .favorite
+                .is_none_or(|favorite| task.favorite == favorite)
+            && self.today.is_none_or(|today| task.today == today)
+            && self
+                .completed
+                .is_none_or(|completed| task.is_completed() == completed)
+            && self.due.as_ref().is_none_or(|due| {
+                task.due_date
+                    .as_ref()
+                    .is_some_and(|date| due.contains(&date.local_date()))
+            })
+            && self.reminder.as_ref().is_none_or(|reminder| {
+                task.reminder_date
+                    .is_some_and(|date| reminder.contains(&date))
+            })
+            && self.parent.is_none_or(|parent| match parent {
+                Parent::None => task.parent_id.is_none(),
+                Parent::Any => task.parent_id.is_some(),
+                Parent::Task(parent_id) => task.parent_id == Some(parent_id),
+            })
+    }
+
+    fn compare(&self, a: &Task, b: &Task) -> Ordering {
+        let Some(sort) = self.sort else {
+            return Ordering::Equal;
+        };
+        let ordering = match sort {
+            TaskSort::Created => a.creation_date.cmp(&b.creation_date),
+            TaskSort::Updated => a.updated_at.cmp(&b.updated_at),
+            TaskSort::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+            TaskSort::Priority => (a.priority as u8).cmp(&(b.priority as u8)),
+            TaskSort::Manual => a.sort_order.cmp(&b.sort_order),
+            TaskSort::Due => {
+                return self.compare_missing_last(
+                    a.due_date.as_ref().map(DueDate::deadline),
+                    b.due_date.as_ref().map(DueDate::deadline),
+                )
+            }
+            TaskSort::Reminder => {
+                return self.compare_missing_last(a.reminder_date, b.reminder_date)
+            }
+        };
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    fn compare_missing_last<T: Ord>(&self, a: Option<T>, b: Option<T>) -> Ordering {
+        match (a, b) {
+            (Some(a), Some(b)) if self.descending => b.cmp(&a),
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+impl Store {
+    /// Runs `query` across every registered list. Each result carries the id
+    /// of the list the task belongs to. Lists whose tasks cannot be read are
+    /// left out rather than failing the whole query.
+    pub fn query(&self, query: &TaskQuery) -> Result<Vec<(Uuid, Task)>> {
+        let lists: Vec<Uuid> = self
+            .lists()
+            .load_all()?
+            .into_iter()
+            .map(|list| list.id)
+            .filter(|list_id| query.lists.is_empty() || query.lists.contains(list_id))
+            .collect();
+        for list_id in &lists {
+            if let Err(err) = self.load_list(*list_id) {
+                tracing::error!("Failed to load tasks for list {list_id}: {err}");
+            }
+        }
+        let positions: HashMap<Uuid, usize> = lists
+            .iter()
+            .enumerate()
+            .map(|(position, list_id)| (*list_id, position))
+            .collect();
+
+        let cache = self.cache();
+        let keys: Vec<TaskKey> = if let Some(reminder) = &query.reminder {
+            cache.reminders(reminder.clone()).collect()
+        } else if let Some(due) = &query.due {
+            // The index holds dates as stored; `matches` narrows them down
+            // to the local ones.
+            let start = due.start().saturating_sub(2.days());
+            let end = due.end().saturating_add(2.days());
+            cache.due(start..=end).collect()
+        } else if query.favorite == Some(true) {
+            cache.favorites().collect()
+        } else {
+            lists
+                .iter()
+                .filter_map(|list_id| cache.list_tasks(*list_id).map(|tasks| (list_id, tasks)))
+                .flat_map(|(list_id, tasks)| tasks.map(|task| (*list_id, task.id)))
+                .collect()
+        };
+
+        let mut found: Vec<(Uuid, Task)> = keys
+            .into_iter()
+            .filter(|(list_id, _)| positions.contains_key(list_id))
+            .filter_map(|key| cache.task(key).map(|task| (key.0, task)))
+            .filter(|(list_id, task)| query.matches(*list_id, task))
+            .map(|(list_id, task)| (list_id, task.clone()))
+            .collect();
+        drop(cache);
+
+        found.sort_by(|(a_list, a), (b_list, b)| {
+            positions[a_list]
+                .cmp(&positions[b_list])
+                .then(a.creation_date.cmp(&b.creation_date))
+        });
+        found.sort_by(|(_, a), (_, b)| query.compare(a, b));
+        if let Some(limit) = query.limit {
+            found.truncate(limit);
+        }
+        Ok(found)
+    }
+}
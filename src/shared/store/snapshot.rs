@@ -0,0 +1,558 @@
+//! Rolling snapshots of the directory layout.
+//!
+//! A snapshot is a plain copy of the registries, every list directory and
+//! the trash, taken at most once an hour and thinned out by a
+//! [`SnapshotRetention`] policy. Snapshots live outside the store's base
+//! directory, so whatever damages the store does not take them along.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use jiff::tz::TimeZone;
+use jiff::{SignedDuration, Timestamp};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::atomic;
+use super::backend::{pretty, RonDirBackend, StorageBackend};
+use super::journal::same;
+use super::migrations::{SchemaMigrator, CURRENT_VERSION, SCHEMA_VERSION_FILE};
+use super::Store;
+use crate::features::lists::list::List;
+use crate::features::tasks::task::Task;
+use crate::{Error, Result, StoreError};
+
+const SNAPSHOT_RECORD: &str = "snapshot.ron";
+
+/// Scheduled snapshots are taken when the newest one is at least this old.
+const SNAPSHOT_INTERVAL: SignedDuration = SignedDuration::from_hours(1);
+
+/// How many snapshots to keep per period. Each period keeps the newest
+/// snapshot of its last N hours, days or weeks; a snapshot is pruned once no
+/// period wants it. The newest snapshot is always kept.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotRetention {
+    pub hourly: u32,
+    pub daily: u32,
+    pub weekly: u32,
+}
+
+impl Default for SnapshotRetention {
+    fn default() -> Self {
+        Self {
+            hourly: 24,
+            daily: 7,
+            weekly: 4,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SnapshotReason {
+    Scheduled,
+    Manual,
+    /// Taken right before a restore, so the restore can be undone.
+    BeforeRestore,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Name of the snapshot's directory.
+    #[serde(skip)]
+    pub id: String,
+    pub taken_at: Timestamp,
+    pub reason: SnapshotReason,
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiffStatus {
+    /// Exists now but not in the snapshot.
+    Added,
+    /// Exists in the snapshot but not anymore.
+    Removed,
+    Changed,
+}
+
+#[derive(Clone, Debug)]
+pub struct TaskDiff {
+    pub task_id: Uuid,
+    pub title: String,
+    pub status: DiffStatus,
+}
+
+#[derive(Clone, Debug)]
+pub struct ListDiff {
+    pub list_id: Uuid,
+    pub name: String,
+    pub status: DiffStatus,
+    pub tasks: Vec<TaskDiff>,
+}
+
+/// How the store differs from a snapshot. Lists that are the same in both
+/// are left out.
+#[derive(Clone, Debug, Default)]
+pub struct SnapshotDiff {
+    pub lists: Vec<ListDiff>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.lists.is_empty()
+    }
+}
+
+/// Takes, prunes and restores snapshots of a RON directory store. Stores
+/// kept in a database have no snapshots.
+#[derive(Debug, Clone)]
+pub struct SnapshotArchive {
+    store: Store,
+    live: RonDirBackend,
+    dir: PathBuf,
+}
+
+impl SnapshotArchive {
+    /// Keeps snapshots of `store` in `dir`. Fails if the store is not
+    /// backed by a directory.
+    pub fn open(store: Store, dir: impl AsRef<Path>) -> Result<Self> {
+        let base_dir = store
+            .base_dir()
+            .ok_or_else(|| Error::Io(std::io::ErrorKind::Unsupported.into()))?
+            .to_path_buf();
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            live: RonDirBackend::open(base_dir)?,
+            store,
+            dir,
+        })
+    }
+
+    /// Every snapshot, newest first. Unreadable ones are skipped.
+    pub fn list(&self) -> Result<Vec<Snapshot>> {
+        let mut snapshots = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let hidden = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_none_or(|n| n.starts_with('.'));
+            if hidden || !path.is_dir() {
+                continue;
+            }
+            match Self::read_record(&path) {
+                Ok(snapshot) => snapshots.push(snapshot),
+                Err(err) => tracing::warn!("Skipping snapshot {path:?}: {err}"),
+            }
+        }
+        snapshots.sort_by_key(|s| std::cmp::Reverse(s.taken_at));
+        Ok(snapshots)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Snapshot> {
+        let path = self.dir.join(id);
+        if id.starts_with('.') || !path.is_dir() {
+            return Err(Error::Store(StoreError::SnapshotNotFound(id.to_string())));
+        }
+        Self::read_record(&path)
+    }
+
+    /// Copies the current state of the store into a new snapshot. The copy
+    /// is made under the store lock and only shows up in [`list`](Self::list)
+    /// once it is complete.
+    pub fn take(&self, reason: SnapshotReason) -> Result<Snapshot> {
+        let _lock = self.store.lock()?;
+        let taken_at = Timestamp::now();
+        let id = taken_at.as_millisecond().to_string();
+        let staging = self.dir.join(format!(".{id}"));
+        let path = self.dir.join(&id);
+
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        fs::create_dir_all(&staging)?;
+        let base_dir = self.base_dir();
+        for entry in self.archived_entries(base_dir)? {
+            copy_tree(&base_dir.join(&entry), &staging.join(&entry))?;
+        }
+        // The store is open, so its records are current whether or not the
+        // version was ever written down. Restoring must not take them for v1.
+        if !staging.join(SCHEMA_VERSION_FILE).exists() {
+            atomic::write(
+                &staging.join(SCHEMA_VERSION_FILE),
+                format!("{CURRENT_VERSION}\n"),
+            )?;
+        }
+
+        let snapshot = Snapshot {
+            id,
+            taken_at,
+            reason,
+            path,
+        };
+        let record = ron::ser::to_string_pretty(&snapshot, pretty())?;
+        atomic::write(&staging.join(SNAPSHOT_RECORD), record)?;
+        fs::rename(&staging, &snapshot.path)?;
+
+        tracing::info!("Took snapshot {} ({:?})", snapshot.id, reason);
+        Ok(snapshot)
+    }
+
+    /// Takes a scheduled snapshot if the newest one is older than an hour,
+    /// then prunes what `retention` no longer keeps.
+    pub fn take_if_due(&self, retention: SnapshotRetention) -> Result<Option<Snapshot>> {
+        let due = match self.list()?.first() {
+            Some(newest) => Timestamp::now().duration_since(newest.taken_at) >= SNAPSHOT_INTERVAL,
+            None => true,
+        };
+        let taken = if due {
+            Some(self.take(SnapshotReason::Scheduled)?)
+        } else {
+            None
+        };
+        self.prune(retention)?;
+        Ok(taken)
+    }
+
+    /// Deletes the snapshots `retention` does not keep. Returns how many were
+    /// deleted.
+    pub fn prune(&self, retention: SnapshotRetention) -> Result<usize> {
+        let snapshots = self.list()?;
+        let tz = TimeZone::system();
+
+        let mut keep: HashSet<&str> = HashSet::new();
+        keep.extend(snapshots.first().map(|s| s.id.as_str()));
+        keep.extend(keep_per_bucket(&snapshots, retention.hourly, |s| {
+            s.taken_at.as_second().div_euclid(3600)
+        }));
+        keep.extend(keep_per_bucket(&snapshots, retention.daily, |s| {
+            let date = s.taken_at.to_zoned(tz.clone()).date();
+            i64::from(date.year()) * 1000 + i64::from(date.day_of_year())
+        }));
+        keep.extend(keep_per_bucket(&snapshots, retention.weekly, |s| {
+            let week = s.taken_at.to_zoned(tz.clone()).date().iso_week_date();
+            i64::from(week.year()) * 100 + i64::from(week.week())
+        }));
+
+        let mut pruned = 0;
+        for snapshot in snapshots.iter().filter(|s| !keep.contains(s.id.as_str())) {
+            fs::remove_dir_all(&snapshot.path)?;
+            pruned += 1;
+        }
+        if pruned > 0 {
+            tracing::info!("Pruned {pruned} snapshots");
+        }
+        Ok(pruned)
+    }
+
+    /// Compares the store as it is now with a snapshot, list by list and
+    /// task by task.
+    pub fn diff(&self, id: &str) -> Result<SnapshotDiff> {
+        self.with_upgraded_copy(&self.get(id)?, |snapshot| self.diff_with(snapshot))
+    }
+
+    fn diff_with(&self, snapshot: &RonDirBackend) -> Result<SnapshotDiff> {
+        let current = self.store.backend();
+
+        let old_lists = snapshot.read_lists()?;
+        let new_lists = current.read_lists()?;
+        let new_by_id: HashMap<Uuid, &List> = new_lists.iter().map(|l| (l.id, l)).collect();
+        let old_ids: HashSet<Uuid> = old_lists.iter().map(|l| l.id).collect();
+
+        let mut lists = Vec::new();
+        for old in &old_lists {
+            let old_tasks = read_tasks(snapshot, old.id);
+            let diff = match new_by_id.get(&old.id) {
+                Some(new) => {
+                    let tasks = diff_tasks(&old_tasks, &read_tasks(current, old.id));
+                    if tasks.is_empty() && same(*new, old) {
+                        continue;
+                    }
+                    ListDiff {
+                        list_id: old.id,
+                        name: new.name.clone(),
+                        status: DiffStatus::Changed,
+                        tasks,
+                    }
+                }
+                None => ListDiff {
+                    list_id: old.id,
+                    name: old.name.clone(),
+                    status: DiffStatus::Removed,
+                    tasks: diff_tasks(&old_tasks, &[]),
+                },
+            };
+            lists.push(diff);
+        }
+        for new in new_lists.iter().filter(|l| !old_ids.contains(&l.id)) {
+            lists.push(ListDiff {
+                list_id: new.id,
+                name: new.name.clone(),
+                status: DiffStatus::Added,
+                tasks: diff_tasks(&[], &read_tasks(current, new.id)),
+            });
+        }
+        Ok(SnapshotDiff { lists })
+    }
+
+    /// Puts the whole store back the way it was in a snapshot. The current
+    /// state is snapshotted first.
+    pub fn restore(&self, id: &str) -> Result<()> {
+        let _lock = self.store.lock()?;
+        let snapshot = self.get(id)?;
+        self.take(SnapshotReason::BeforeRestore)?;
+
+        let base_dir = self.base_dir();
+        let staging = base_dir.join(format!(".restore-{id}"));
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        let restored = self.archived_entries(&snapshot.path)?;
+        for entry in &restored {
+            copy_tree(&snapshot.path.join(entry), &staging.join(entry))?;
+        }
+
+        for entry in self.archived_entries(base_dir)? {
+            remove_tree(&base_dir.join(entry))?;
+        }
+        for entry in &restored {
+            fs::rename(staging.join(entry), base_dir.join(entry))?;
+        }
+        remove_tree(&staging)?;
+
+        // Snapshots taken before a schema upgrade come back in the old format.
+        SchemaMigrator::new(base_dir).migrate()?;
+        self.store.invalidate(base_dir);
+        // The undo history describes data that is no longer there.
+        self.store.journal().clear()?;
+
+        tracing::info!("Restored snapshot {id}");
+        Ok(())
+    }
+
+    /// Puts one list back the way it was in a snapshot, replacing its tasks
+    /// and registry entry. A copy of the list in the trash is dropped. The
+    /// current state is snapshotted first, and undo history is cleared like
+    /// for a full restore.
+    pub fn restore_list(&self, id: &str, list_id: Uuid) -> Result<List> {
+        let _lock = self.store.lock()?;
+        let snapshot = self.get(id)?;
+        let name = list_id.to_string();
+        let base_dir = self.base_dir();
+        let staging = base_dir.join(format!(".restore-{name}"));
+        remove_tree(&staging)?;
+        // The list is taken from an upgraded copy, since the snapshot may
+        // predate the schema the store is at now.
+        let list = self.with_upgraded_copy(&snapshot, |copy| {
+            let list = copy
+                .read_lists()?
+                .into_iter()
+                .find(|l| l.id == list_id)
+                .ok_or(Error::Store(StoreError::ListNotFound(list_id)))?;
+            let root = copy.root().expect("directory backend always has a root");
+            copy_tree(&root.join(&name), &staging)?;
+            Ok(list)
+        })?;
+        if let Err(err) = self.take(SnapshotReason::BeforeRestore) {
+            remove_tree(&staging)?;
+            return Err(err);
+        }
+
+        remove_tree(&base_dir.join(&name))?;
+        if staging.exists() {
+            fs::rename(&staging, base_dir.join(&name))?;
+        }
+        self.store.invalidate(&base_dir.join(&name));
+
+        self.store.lists().save(&list)?;
+        if self
+            .store
+            .backend()
+            .read_trashed_lists()?
+            .iter()
+            .any(|t| t.list.id == list_id)
+        {
+            self.store.trash().delete_list(list_id)?;
+        }
+        self.store.journal().clear()?;
+
+        tracing::info!("Restored list {list_id} from snapshot {id}");
+        Ok(list)
+    }
+
+    fn base_dir(&self) -> &Path {
+        self.live
+            .root()
+            .expect("directory backend always has a root")
+    }
+
+    /// Copies a snapshot aside, brings the copy up to the current schema and
+    /// hands it to `f`, so old snapshots read like the store does now.
+    /// Whatever opening the copy repairs or sets aside stays in the copy;
+    /// the snapshot itself is only ever read. The copy is removed afterwards.
+    fn with_upgraded_copy<T>(
+        &self,
+        snapshot: &Snapshot,
+        f: impl FnOnce(&RonDirBackend) -> Result<T>,
+    ) -> Result<T> {
+        let copy = self.dir.join(format!(".upgrade-{}", snapshot.id));
+        remove_tree(&copy)?;
+        let result = self
+            .upgraded_copy(snapshot, &copy)
+            .and_then(|backend| f(&backend));
+        remove_tree(&copy)?;
+        result
+    }
+
+    fn upgraded_copy(&self, snapshot: &Snapshot, copy: &Path) -> Result<RonDirBackend> {
+        for entry in self.archived_entries(&snapshot.path)? {
+            copy_tree(&snapshot.path.join(&entry), &copy.join(&entry))?;
+        }
+        SchemaMigrator::new(copy).migrate()?;
+        RonDirBackend::open(copy)
+    }
+
+    /// Entries a snapshot holds: the store's data and its schema version.
+    fn archived_entries(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut entries = RonDirBackend::data_entries(dir)?;
+        if dir.join(SCHEMA_VERSION_FILE).exists() {
+            entries.push(PathBuf::from(SCHEMA_VERSION_FILE));
+        }
+        Ok(entries)
+    }
+
+    fn read_record(dir: &Path) -> Result<Snapshot> {
+        let content = fs::read_to_string(dir.join(SNAPSHOT_RECORD))?;
+        let mut snapshot: Snapshot = ron::from_str(&content)?;
+        snapshot.id = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        snapshot.path = dir.to_path_buf();
+        Ok(snapshot)
+    }
+}
+
+/// Ids of the newest snapshot in each of the `count` newest buckets.
+/// `snapshots` must be sorted newest first.
+fn keep_per_bucket<F>(snapshots: &[Snapshot], count: u32, bucket: F) -> Vec<&str>
+where
+    F: Fn(&Snapshot) -> i64,
+{
+    let mut kept: BTreeMap<i64, &str> = BTreeMap::new();
+    for snapshot in snapshots {
+        if kept.len() >= count as usize {
+            break;
+        }
+        kept.entry(bucket(snapshot)).or_insert(&snapshot.id);
+    }
+    kept.into_values().collect()
+}
+
+fn read_tasks(backend: &dyn StorageBackend, list_id: Uuid) -> Vec<Task> {
+    backend.read_tasks(list_id).unwrap_or_else(|err| {
+        tracing::warn!("Failed to read tasks of list {list_id}: {err}");
+        vec![]
+    })
+}
+
+fn diff_tasks(old: &[Task], new: &[Task]) -> Vec<TaskDiff> {
+    let new_by_id: HashMap<Uuid, &Task> = new.iter().map(|t| (t.id, t)).collect();
+    let old_ids: HashSet<Uuid> = old.iter().map(|t| t.id).collect();
+
+    let mut diffs = Vec::new();
+    for task in old {
+        let status = match new_by_id.get(&task.id) {
+            Some(current) if same(*current, task) => continue,
+            Some(_) => DiffStatus::Changed,
+            None => DiffStatus::Removed,
+        };
+        diffs.push(TaskDiff {
+            task_id: task.id,
+            title: new_by_id.get(&task.id).unwrap_or(&task).title.clone(),
+            status,
+        });
+    }
+    for task in new.iter().filter(|t| !old_ids.contains(&t.id)) {
+        diffs.push(TaskDiff {
+            task_id: task.id,
+            title: task.title.clone(),
+            status: DiffStatus::Added,
+        });
+    }
+    diffs
+}
+
+fn copy_tree(from: &Path, to: &Path) -> std::io::Result<()> {
+    if !from.exists() {
+        return Ok(());
+    }
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_tree(&entry.path(), &to.join(entry.file_name()))?;
+        }
+    } else {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+fn remove_tree(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else if path.exists() {
+        fs::remove_file(path)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restore_list_from_an_older_schema() {
+        let root = std::env::temp_dir().join(format!("tasks-snapshot-{}", Uuid::new_v4()));
+        let store = Store::open(root.join("data")).unwrap();
+        let list = List::new("Groceries");
+        let task = Task::new("Milk");
+        store.lists().save(&list).unwrap();
+        store.tasks(list.id).save(&task).unwrap();
+        let archive = SnapshotArchive::open(store.clone(), root.join("snapshots")).unwrap();
+        let snapshot = archive.take(SnapshotReason::Manual).unwrap();
+
+        // Lists had no layout before v7.
+        let registry = snapshot.path.join("lists.ron");
+        let v6 = fs::read_to_string(&registry)
+            .unwrap()
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("layout:"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        fs::write(&registry, &v6).unwrap();
+        fs::write(snapshot.path.join(SCHEMA_VERSION_FILE), "6\n").unwrap();
+
+        store.trash().trash_list(list.id).unwrap();
+        let diff = archive.diff(&snapshot.id).unwrap();
+        assert_eq!(diff.lists[0].name, "Groceries");
+        assert_eq!(diff.lists[0].status, DiffStatus::Removed);
+
+        let restored = archive.restore_list(&snapshot.id, list.id).unwrap();
+        assert_eq!(restored.name, "Groceries");
+        assert_eq!(store.tasks(list.id).get(task.id).unwrap().title, "Milk");
+        assert!(store.quarantine().load_all().unwrap().is_empty());
+
+        // Reading the snapshot left it as it was.
+        assert_eq!(fs::read_to_string(&registry).unwrap(), v6);
+        assert!(!snapshot.path.join("_quarantine").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
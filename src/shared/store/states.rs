@@ -0,0 +1,106 @@
+//! Changes to task states that reach into the tasks in them.
+//!
+//! A task is in the state named by [`Task::state_id`], or in the built-in
+//! Pending or Completed state when it has none. Whether a state counts as
+//! completed decides whether its tasks are, so changing that or deleting
+//! a state rewrites the tasks along with the registry. Trashed tasks are
+//! rewritten too, so none comes back from the trash in a state that no
+//! longer exists or means something else now.
+
+use std::collections::HashMap;
+
+use jiff::Timestamp;
+use uuid::Uuid;
+
+use super::query::TaskQuery;
+use super::transaction::Transaction;
+use super::Store;
+use crate::features::tasks::state::{is_built_in, TaskState};
+use crate::features::tasks::task::Task;
+use crate::{Result, StoreError};
+
+impl Store {
+    /// How many tasks each state holds, across every list.
+    pub fn state_task_counts(&self) -> Result<HashMap<Uuid, usize>> {
+        let mut counts = HashMap::new();
+        for (_, task) in self.query(&TaskQuery::new())? {
+            *counts.entry(task.effective_state_id()).or_default() += 1;
+        }
+        Ok(counts)
+    }
+
+    /// Sets whether a state counts as completed, completing or reopening
+    /// its tasks to match, as one undo step. Returns how many tasks it
+    /// holds.
+    pub fn set_state_completed(&self, state_id: Uuid, completed: bool) -> Result<usize> {
+        if is_built_in(state_id) {
+            return Err(StoreError::BuiltInState(state_id).into());
+        }
+        self.transaction(|tx| {
+            let state = tx
+                .states()
+                .update(state_id, |state| state.is_completed = completed)?;
+            self.update_tasks_in_state(tx, state_id, |task| move_into(task, &state))
+        })
+    }
+
+    /// Deletes a state, as one undo step. Its tasks move to `into`, or back
+    /// to the built-in state matching whether they are completed. Returns
+    /// how many tasks moved.
+    pub fn delete_state(&self, state_id: Uuid, into: Option<&TaskState>) -> Result<usize> {
+        if is_built_in(state_id) {
+            return Err(StoreError::BuiltInState(state_id).into());
+        }
+        self.transaction(|tx| {
+            let moved = self.update_tasks_in_state(tx, state_id, |task| match into {
+                Some(state) => move_into(task, state),
+                None => task.state_id = None,
+            })?;
+            tx.states().delete(state_id)?;
+            Ok(moved)
+        })
+    }
+
+    /// Rewrites every task in `state_id` with `f`, wherever it is: in a
+    /// list, in the trash or in a trashed list. Returns how many there were.
+    fn update_tasks_in_state(
+        &self,
+        tx: &Transaction<'_>,
+        state_id: Uuid,
+        f: impl Fn(&mut Task),
+    ) -> Result<usize> {
+        let in_state = |task: &Task| task.state_id == Some(state_id);
+        let mut count = 0;
+        for (list_id, task) in self.query(&TaskQuery::new().in_state(state_id))? {
+            tx.tasks(list_id).update(task.id, &f)?;
+            count += 1;
+        }
+        for mut trashed in tx.trash().load_all()? {
+            if in_state(&trashed.task) {
+                f(&mut trashed.task);
+                tx.trash().save(&trashed)?;
+                count += 1;
+            }
+        }
+        for trashed in tx.trash().load_all_lists()? {
+            let list_id = trashed.list.id;
+            for task in tx.trash().load_trashed_list_tasks(list_id)? {
+                if in_state(&task) {
+                    tx.trash().update_in_list(list_id, task.id, &f)?;
+                    count += 1;
+                }
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// Puts a task in `state`, completing or reopening it to match. Unlike
+/// `TaskStore::set_state`, completing a recurring task adds no next
+/// occurrence: the task was not done, what its state means changed.
+fn move_into(task: &mut Task, state: &TaskState) {
+    if task.is_completed() != state.is_completed {
+        task.completion_date = state.is_completed.then(Timestamp::now);
+    }
+    task.state_id = Some(state.id);
+}
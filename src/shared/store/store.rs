@@ -1,33 +1,61 @@
+use super::backend::{LockGuard, MemoryBackend, RonDirBackend, StorageBackend};
+use super::cache::Cache;
+use super::change::Change;
+use super::history::HistoryStore;
+use super::journal::{same, Journal, Operation};
+use super::quarantine::QuarantinedItem;
 use crate::features::lists::list::{List, TrashedList};
 use crate::features::tasks::state::{default_states, TaskState};
 use crate::features::tasks::task::{Task, TrashedTask};
 use crate::StoreError;
 use crate::{Error, Result};
-use ron::ser::PrettyConfig;
-use std::fs;
-use std::path::{Path, PathBuf};
+use jiff::Timestamp;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
 use uuid::Uuid;
 
-const LISTS_REGISTRY: &str = "lists.ron";
-const STATES_REGISTRY: &str = "states.ron";
-const TRASH_DIR: &str = "_trash";
-const TRASHED_LISTS_REGISTRY: &str = "lists.ron";
-const TRASHED_LISTS_DIR: &str = "lists";
-
-fn pretty() -> PrettyConfig {
-    PrettyConfig::new().depth_limit(6).struct_names(true)
-}
-
+/// Reads are served from an in-memory cache shared by every clone of the
+/// store. The store keeps it current for its own writes; changes made by
+/// anyone else must be reported through [`Store::invalidate`].
+///
+/// Read-modify-write cycles always read from the backend, so they never act
+/// on a cached value another process has already replaced.
+///
+/// Every change made through the views is recorded in the journal so it can
+/// be undone; see [`Store::undo`].
 #[derive(Debug, Clone)]
 pub struct Store {
-    base_dir: PathBuf,
+    backend: Arc<dyn StorageBackend>,
+    cache: Arc<Mutex<Cache>>,
+    journal: Arc<Journal>,
 }
 
 impl Store {
+    /// Opens the RON directory store rooted at `base_dir`.
     pub fn open(base_dir: impl AsRef<Path>) -> Result<Self> {
-        let base_dir = base_dir.as_ref().to_path_buf();
-        fs::create_dir_all(&base_dir)?;
-        Ok(Self { base_dir })
+        Ok(Self::with_backend(RonDirBackend::open(base_dir)?))
+    }
+
+    /// Creates an empty store that lives only in memory.
+    #[allow(dead_code)]
+    pub fn in_memory() -> Self {
+        Self::with_backend(MemoryBackend::new())
+    }
+
+    pub fn with_backend(backend: impl StorageBackend + 'static) -> Self {
+        let journal = Journal::new(backend.root());
+        let store = Self {
+            backend: Arc::new(backend),
+            cache: Arc::default(),
+            journal: Arc::new(journal),
+        };
+        if let Err(err) = store.recover() {
+            tracing::error!("Failed to roll back interrupted transaction: {err}");
+        }
+        if let Err(err) = store.reload_history() {
+            tracing::error!("Failed to read the undo history: {err}");
+        }
+        store
     }
 
     pub fn lists(&self) -> ListStore<'_> {
@@ -49,44 +77,96 @@ impl Store {
         StateStore { store: self }
     }
 
-    pub fn base_dir(&self) -> &Path {
-        &self.base_dir
+    pub fn history(&self) -> HistoryStore<'_> {
+        HistoryStore { store: self }
+    }
+
+    pub fn quarantine(&self) -> QuarantineStore<'_> {
+        QuarantineStore { store: self }
     }
 
-    fn registry_path(&self) -> PathBuf {
-        self.base_dir.join(LISTS_REGISTRY)
+    /// Directory the store keeps its data in, if it is backed by files.
+    pub fn base_dir(&self) -> Option<&Path> {
+        self.backend.root()
     }
 
-    fn states_registry_path(&self) -> PathBuf {
-        self.base_dir.join(STATES_REGISTRY)
+    /// Drops whatever the cache holds for a path that changed on disk and
+    /// returns what it belonged to. Returns `None` if the store does not use
+    /// the path, or if the change is an echo of the store's own write, which
+    /// the cache already reflects.
+    pub fn invalidate(&self, path: &Path) -> Option<Change> {
+        if self.backend.is_echo(path) {
+            return None;
+        }
+        let change = self.backend.classify(path)?;
+        self.cache().invalidate(change);
+        Some(change)
     }
 
-    fn trash_dir(&self) -> PathBuf {
-        self.base_dir.join(TRASH_DIR)
+    /// Takes the store-wide lock that serializes read-modify-write cycles
+    /// across windows, instances and external tools. Reentrant within a
+    /// thread, so locked operations may call each other.
+    pub(super) fn lock(&self) -> Result<Box<dyn LockGuard + '_>> {
+        self.backend.lock()
     }
 
-    fn trashed_task_path(&self, task_id: Uuid) -> PathBuf {
-        self.trash_dir().join(format!("{task_id}.ron"))
+    pub(super) fn backend(&self) -> &dyn StorageBackend {
+        self.backend.as_ref()
     }
 
-    fn trashed_lists_dir(&self) -> PathBuf {
-        self.trash_dir().join(TRASHED_LISTS_DIR)
+    pub(super) fn journal(&self) -> &Journal {
+        &self.journal
     }
 
-    fn trashed_lists_registry_path(&self) -> PathBuf {
-        self.trashed_lists_dir().join(TRASHED_LISTS_REGISTRY)
+    /// Drops whatever the cache holds for a part of the store that was
+    /// rewritten behind the views' back, e.g. by undo or a restore.
+    pub(super) fn forget(&self, change: Change) {
+        self.cache().invalidate(change);
     }
 
-    fn trashed_list_data_dir(&self, list_id: Uuid) -> PathBuf {
-        self.trashed_lists_dir().join(list_id.to_string())
+    /// Records a change in the transaction in progress. Must be called
+    /// before the change is written, so it can be rolled back.
+    fn record(&self, operation: Operation) -> Result<()> {
+        self.journal.record(operation)
     }
 
-    fn list_dir(&self, list_id: Uuid) -> PathBuf {
-        self.base_dir.join(list_id.to_string())
+    pub(super) fn cache(&self) -> MutexGuard<'_, Cache> {
+        self.cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
     }
 
-    fn task_path(&self, list_id: Uuid, task_id: Uuid) -> PathBuf {
-        self.list_dir(list_id).join(format!("{task_id}.ron"))
+    /// Makes sure the tasks of `list_id` are cached and up to date.
+    pub(super) fn load_list(&self, list_id: Uuid) -> Result<()> {
+        let stale = {
+            let mut cache = self.cache();
+            cache.has_list(list_id).then(|| cache.take_stale(list_id))
+        };
+
+        match stale {
+            Some(stale) => {
+                for task_id in stale {
+                    match self.backend().read_task(list_id, task_id) {
+                        Ok(task) => self.cache().insert_task(list_id, task),
+                        Err(Error::Store(StoreError::TaskNotFound(_))) => {
+                            self.cache().remove_task(list_id, task_id)
+                        }
+                        Err(err) => {
+                            tracing::error!("Failed to reload task {task_id}: {err}");
+                            self.cache().remove_task(list_id, task_id);
+                        }
+                    }
+                }
+            }
+            None => {
+                if !self.backend().has_list(list_id) {
+                    return Err(Error::Store(StoreError::ListNotFound(list_id)));
+                }
+                let tasks = self.backend().read_tasks(list_id)?;
+                self.cache().insert_list(list_id, tasks);
+            }
+        }
+        Ok(())
     }
 }
 
@@ -96,188 +176,290 @@ pub struct TrashStore<'s> {
 
 impl TrashStore<'_> {
     pub fn save(&self, trashed: &TrashedTask) -> crate::Result<()> {
-        fs::create_dir_all(self.store.trash_dir())?;
-        let path = self.store.trashed_task_path(trashed.task.id);
-        let content = ron::ser::to_string_pretty(trashed, pretty())?;
-        fs::write(path, content)?;
-        Ok(())
+        self.store.journaled(|| {
+            let before = self.find(trashed.task.id)?;
+            self.store.record(Operation::TrashedTask {
+                task_id: trashed.task.id,
+                before,
+                after: Some(trashed.clone()),
+            })?;
+            self.store.backend().write_trashed_task(trashed)?;
+            self.store.cache().trashed_tasks = None;
+            Ok(())
+        })
+    }
+
+    /// Moves a task from its list into the trash.
+    pub fn trash_task(&self, list_id: Uuid, task: &Task) -> crate::Result<()> {
+        self.store.journaled(|| {
+            self.save(&TrashedTask::new(task.clone(), list_id))?;
+            self.store.tasks(list_id).delete(task.id)
+        })
+    }
+
+    /// Puts a trashed task back into the list it was deleted from.
+    pub fn restore_task(&self, task_id: Uuid) -> crate::Result<TrashedTask> {
+        self.store.journaled(|| {
+            let trashed = self
+                .find(task_id)?
+                .ok_or(Error::Store(StoreError::TaskNotFound(task_id)))?;
+            self.store
+                .tasks(trashed.original_list_id)
+                .save(&trashed.task)?;
+            self.remove(task_id)?;
+            Ok(trashed)
+        })
     }
 
     pub fn load_all(&self) -> crate::Result<Vec<TrashedTask>> {
-        let trash_dir = self.store.trash_dir();
-        if !trash_dir.exists() {
-            return Ok(vec![]);
-        }
-        let mut tasks = Vec::new();
-        for entry in fs::read_dir(&trash_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.extension().and_then(|e| e.to_str()) != Some("ron") {
-                continue;
-            }
-            match fs::read_to_string(&path).map(|s| ron::from_str::<TrashedTask>(&s)) {
-                Ok(Ok(task)) => tasks.push(task),
-                Ok(Err(e)) => tracing::error!("skipping {:?}: {e}", path.file_name()),
-                Err(e) => tracing::error!("could not read {:?}: {e}", path.file_name()),
-            }
+        if let Some(tasks) = self.store.cache().trashed_tasks.clone() {
+            return Ok(tasks);
         }
+        let mut tasks = self.store.backend().read_trashed_tasks()?;
         tasks.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+        self.store.cache().trashed_tasks = Some(tasks.clone());
         Ok(tasks)
     }
 
+    /// Deletes a trashed task for good, along with its history.
     pub fn delete(&self, task_id: Uuid) -> crate::Result<()> {
-        let path = self.store.trashed_task_path(task_id);
-        fs::remove_file(&path)
-            .map_err(|_| crate::Error::Store(crate::StoreError::TaskNotFound(task_id)))
+        self.remove(task_id)?;
+        self.store.history().forget(task_id);
+        Ok(())
     }
 
-    pub fn trash_list(&self, list_id: Uuid) -> crate::Result<()> {
-        let list = self.store.lists().detach(list_id)?;
-
-        fs::create_dir_all(self.store.trashed_lists_dir())?;
-        let list_dir = self.store.list_dir(list_id);
-        if list_dir.exists() {
-            fs::rename(&list_dir, self.store.trashed_list_data_dir(list_id))?;
-        }
+    fn remove(&self, task_id: Uuid) -> crate::Result<()> {
+        self.store.journaled(|| {
+            let before = self.find(task_id)?;
+            self.store.record(Operation::TrashedTask {
+                task_id,
+                before,
+                after: None,
+            })?;
+            self.store.backend().remove_trashed_task(task_id)?;
+            self.store.cache().trashed_tasks = None;
+            Ok(())
+        })
+    }
+
+    fn find(&self, task_id: Uuid) -> crate::Result<Option<TrashedTask>> {
+        Ok(self
+            .store
+            .backend()
+            .read_trashed_tasks()?
+            .into_iter()
+            .find(|t| t.task.id == task_id))
+    }
 
-        let mut lists = self.load_all_lists()?;
-        lists.retain(|t| t.list.id != list_id);
-        lists.push(TrashedList::new(list));
-        self.flush_lists_registry(&lists)
+    pub fn trash_list(&self, list_id: Uuid) -> crate::Result<()> {
+        self.store.journaled(|| {
+            let list = self.store.lists().detach(list_id)?;
+
+            self.store.record(Operation::ListTrashed {
+                list_id,
+                trashed: true,
+            })?;
+            self.store.backend().move_list_to_trash(list_id)?;
+            self.forget_list(list_id);
+
+            let mut lists = self.store.backend().read_trashed_lists()?;
+            let before = lists.clone();
+            lists.retain(|t| t.list.id != list_id);
+            lists.push(TrashedList::new(list));
+            self.flush_lists_registry(before, &lists)
+        })
     }
 
     pub fn load_all_lists(&self) -> crate::Result<Vec<TrashedList>> {
-        let path = self.store.trashed_lists_registry_path();
-        if !path.exists() {
-            return Ok(vec![]);
+        if let Some(lists) = self.store.cache().trashed_lists.clone() {
+            return Ok(lists);
         }
-        let content = fs::read_to_string(&path)?;
-        Ok(ron::from_str(&content)?)
+        let lists = self.store.backend().read_trashed_lists()?;
+        self.store.cache().trashed_lists = Some(lists.clone());
+        Ok(lists)
     }
 
     pub fn restore_list(&self, list_id: Uuid) -> crate::Result<List> {
-        let mut lists = self.load_all_lists()?;
-        let pos = lists
-            .iter()
-            .position(|t| t.list.id == list_id)
-            .ok_or(Error::Store(StoreError::ListNotFound(list_id)))?;
-        let trashed = lists.remove(pos);
-
-        let data_dir = self.store.trashed_list_data_dir(list_id);
-        let list_dir = self.store.list_dir(list_id);
-        if data_dir.exists() {
-            fs::rename(&data_dir, &list_dir)?;
-        } else {
-            fs::create_dir_all(&list_dir)?;
-        }
-
-        self.store.lists().save(&trashed.list)?;
-        self.flush_lists_registry(&lists)?;
-        Ok(trashed.list)
+        self.store.journaled(|| {
+            let mut lists = self.store.backend().read_trashed_lists()?;
+            let before = lists.clone();
+            let pos = lists
+                .iter()
+                .position(|t| t.list.id == list_id)
+                .ok_or(Error::Store(StoreError::ListNotFound(list_id)))?;
+            let trashed = lists.remove(pos);
+
+            self.store.record(Operation::ListTrashed {
+                list_id,
+                trashed: false,
+            })?;
+            self.store.backend().move_list_from_trash(list_id)?;
+            self.forget_list(list_id);
+
+            self.store.lists().save(&trashed.list)?;
+            self.flush_lists_registry(before, &lists)?;
+            Ok(trashed.list)
+        })
     }
 
     pub fn delete_list(&self, list_id: Uuid) -> crate::Result<()> {
-        let mut lists = self.load_all_lists()?;
-        let before = lists.len();
-        lists.retain(|t| t.list.id != list_id);
-
-        if lists.len() == before {
-            return Err(Error::Store(StoreError::ListNotFound(list_id)));
-        }
+        self.store.journaled(|| {
+            let mut lists = self.store.backend().read_trashed_lists()?;
+            let before = lists.clone();
+            lists.retain(|t| t.list.id != list_id);
 
-        let data_dir = self.store.trashed_list_data_dir(list_id);
-        if data_dir.exists() {
-            fs::remove_dir_all(&data_dir)?;
-        }
+            if lists.len() == before.len() {
+                return Err(Error::Store(StoreError::ListNotFound(list_id)));
+            }
 
-        self.flush_lists_registry(&lists)
+            // Kept task by task, so undo can put them back.
+            let tasks = self.store.backend().read_trashed_list_tasks(list_id)?;
+            for task in &tasks {
+                self.store.record(Operation::TrashedListTask {
+                    list_id,
+                    task_id: task.id,
+                    before: Some(task.clone()),
+                    after: None,
+                })?;
+            }
+            self.store.backend().remove_trashed_list(list_id)?;
+            for task in &tasks {
+                self.store.history().forget(task.id);
+            }
+            self.forget_list(list_id);
+
+            self.flush_lists_registry(before, &lists)
+        })
+    }
+
+    fn flush_lists_registry(
+        &self,
+        before: Vec<TrashedList>,
+        lists: &[TrashedList],
+    ) -> crate::Result<()> {
+        self.store.record(Operation::TrashedLists {
+            before,
+            after: lists.to_vec(),
+        })?;
+        self.store.backend().write_trashed_lists(lists)?;
+        self.store.cache().trashed_lists = Some(lists.to_vec());
+        Ok(())
     }
 
-    fn flush_lists_registry(&self, lists: &[TrashedList]) -> crate::Result<()> {
-        fs::create_dir_all(self.store.trashed_lists_dir())?;
-        let content = ron::ser::to_string_pretty(lists, pretty())?;
-        fs::write(self.store.trashed_lists_registry_path(), content)?;
-        Ok(())
+    /// Drops the cached tasks of a list that moved in or out of the trash.
+    fn forget_list(&self, list_id: Uuid) {
+        let mut cache = self.store.cache();
+        cache.remove_list(list_id);
+        cache.trashed_list_tasks.remove(&list_id);
     }
 
     pub fn load_trashed_list_tasks(&self, list_id: Uuid) -> crate::Result<Vec<Task>> {
-        let data_dir = self.store.trashed_list_data_dir(list_id);
-        if !data_dir.exists() {
-            return Ok(vec![]);
-        }
-
-        let mut tasks = Vec::new();
-        for entry in fs::read_dir(&data_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.extension().and_then(|e| e.to_str()) != Some("ron") {
-                continue;
-            }
-            match fs::read_to_string(&path).map(|s| ron::from_str::<Task>(&s)) {
-                Ok(Ok(task)) => tasks.push(task),
-                Ok(Err(e)) => tracing::error!("skipping {:?}: {e}", path.file_name()),
-                Err(e) => tracing::error!("could not read {:?}: {e}", path.file_name()),
-            }
+        if let Some(tasks) = self.store.cache().trashed_list_tasks.get(&list_id) {
+            return Ok(tasks.clone());
         }
+        let mut tasks = self.store.backend().read_trashed_list_tasks(list_id)?;
         tasks.sort_by(|a, b| a.creation_date.cmp(&b.creation_date));
+        self.store
+            .cache()
+            .trashed_list_tasks
+            .insert(list_id, tasks.clone());
         Ok(tasks)
     }
 
+    /// Changes a task kept inside a trashed list.
+    pub fn update_in_list<F>(&self, list_id: Uuid, task_id: Uuid, f: F) -> crate::Result<Task>
+    where
+        F: FnOnce(&mut Task),
+    {
+        self.store.journaled(|| {
+            let before = self.find_in_list(list_id, task_id)?;
+            let mut task = before.clone();
+            f(&mut task);
+            self.store.record(Operation::TrashedListTask {
+                list_id,
+                task_id,
+                before: Some(before),
+                after: Some(task.clone()),
+            })?;
+            self.store
+                .backend()
+                .write_trashed_list_task(list_id, &task)?;
+            self.store.cache().trashed_list_tasks.remove(&list_id);
+            Ok(task)
+        })
+    }
+
     pub fn restore_task_from_list(&self, list_id: Uuid, task_id: Uuid) -> crate::Result<List> {
-        let mut lists = self.load_all_lists()?;
-        let pos = lists
-            .iter()
-            .position(|t| t.list.id == list_id)
-            .ok_or(Error::Store(StoreError::ListNotFound(list_id)))?;
-        let list = lists[pos].list.clone();
-
-        self.store.lists().save(&list)?;
-        let list_dir = self.store.list_dir(list_id);
-        fs::create_dir_all(&list_dir)?;
-
-        let data_dir = self.store.trashed_list_data_dir(list_id);
-        let src = data_dir.join(format!("{task_id}.ron"));
-        let dest = self.store.task_path(list_id, task_id);
-        fs::rename(&src, &dest)
-            .map_err(|_| crate::Error::Store(crate::StoreError::TaskNotFound(task_id)))?;
-
-        if !Self::dir_has_ron_files(&data_dir) {
-            if data_dir.exists() {
-                fs::remove_dir_all(&data_dir)?;
+        self.store.journaled(|| {
+            let mut lists = self.store.backend().read_trashed_lists()?;
+            let pos = lists
+                .iter()
+                .position(|t| t.list.id == list_id)
+                .ok_or(Error::Store(StoreError::ListNotFound(list_id)))?;
+            let list = lists[pos].list.clone();
+
+            self.store.lists().save(&list)?;
+
+            let backend = self.store.backend();
+            let task = self.find_in_list(list_id, task_id)?;
+            self.store.record(Operation::TrashedListTask {
+                list_id,
+                task_id,
+                before: Some(task.clone()),
+                after: None,
+            })?;
+            self.store.record(Operation::Task {
+                list_id,
+                before: None,
+                after: Some(task),
+            })?;
+            backend.restore_trashed_list_task(list_id, task_id)?;
+            self.forget_list(list_id);
+
+            if !backend.has_trashed_list_tasks(list_id) {
+                backend.remove_trashed_list(list_id)?;
+                let before = lists.clone();
+                lists.remove(pos);
+                self.flush_lists_registry(before, &lists)?;
             }
-            lists.remove(pos);
-            self.flush_lists_registry(&lists)?;
-        }
 
-        Ok(list)
+            Ok(list)
+        })
     }
 
     pub fn delete_task_from_list(&self, list_id: Uuid, task_id: Uuid) -> crate::Result<()> {
-        let data_dir = self.store.trashed_list_data_dir(list_id);
-        let path = data_dir.join(format!("{task_id}.ron"));
-        fs::remove_file(&path)
-            .map_err(|_| crate::Error::Store(crate::StoreError::TaskNotFound(task_id)))?;
-
-        if !Self::dir_has_ron_files(&data_dir) {
-            if data_dir.exists() {
-                fs::remove_dir_all(&data_dir)?;
+        self.store.journaled(|| {
+            let backend = self.store.backend();
+            let task = self.find_in_list(list_id, task_id)?;
+            self.store.record(Operation::TrashedListTask {
+                list_id,
+                task_id,
+                before: Some(task),
+                after: None,
+            })?;
+            backend.remove_trashed_list_task(list_id, task_id)?;
+            self.store.cache().trashed_list_tasks.remove(&list_id);
+            self.store.history().forget(task_id);
+
+            if !backend.has_trashed_list_tasks(list_id) {
+                backend.remove_trashed_list(list_id)?;
+                let mut lists = backend.read_trashed_lists()?;
+                let before = lists.clone();
+                lists.retain(|t| t.list.id != list_id);
+                self.flush_lists_registry(before, &lists)?;
             }
-            let mut lists = self.load_all_lists()?;
-            lists.retain(|t| t.list.id != list_id);
-            self.flush_lists_registry(&lists)?;
-        }
 
-        Ok(())
+            Ok(())
+        })
     }
 
-    fn dir_has_ron_files(dir: &Path) -> bool {
-        fs::read_dir(dir)
-            .map(|entries| {
-                entries
-                    .filter_map(|e| e.ok())
-                    .any(|e| e.path().extension().and_then(|x| x.to_str()) == Some("ron"))
-            })
-            .unwrap_or(false)
+    fn find_in_list(&self, list_id: Uuid, task_id: Uuid) -> crate::Result<Task> {
+        self.store
+            .backend()
+            .read_trashed_list_tasks(list_id)?
+            .into_iter()
+            .find(|t| t.id == task_id)
+            .ok_or(Error::Store(StoreError::TaskNotFound(task_id)))
     }
 }
 
@@ -286,7 +468,6 @@ pub struct ListStore<'s> {
 }
 
 impl ListStore<'_> {
-    #[allow(dead_code)]
     pub fn get(&self, list_id: Uuid) -> Result<List> {
         self.load_all()?
             .into_iter()
@@ -295,59 +476,83 @@ impl ListStore<'_> {
     }
 
     pub fn load_all(&self) -> Result<Vec<List>> {
-        let path = self.store.registry_path();
-        if !path.exists() {
-            return Ok(vec![]);
+        if let Some(lists) = self.store.cache().lists.clone() {
+            return Ok(lists);
         }
-        let content = fs::read_to_string(&path)?;
-        Ok(ron::from_str(&content)?)
+        let lists = self.store.backend().read_lists()?;
+        self.store.cache().lists = Some(lists.clone());
+        Ok(lists)
     }
 
     pub fn save(&self, list: &List) -> Result<()> {
-        fs::create_dir_all(self.store.list_dir(list.id))?;
+        self.store.journaled(|| {
+            if !self.store.backend().has_list(list.id) {
+                self.store.record(Operation::ListStorage {
+                    list_id: list.id,
+                    created: true,
+                })?;
+                self.store.backend().create_list(list.id)?;
+            }
 
-        let mut lists = self.load_all()?;
-        match lists.iter_mut().find(|l| l.id == list.id) {
-            Some(existing) => *existing = list.clone(),
-            None => lists.push(list.clone()),
-        }
+            let mut lists = self.store.backend().read_lists()?;
+            let before = lists.clone();
+            match lists.iter_mut().find(|l| l.id == list.id) {
+                Some(existing) => *existing = list.clone(),
+                None => lists.push(list.clone()),
+            }
 
-        self.flush_registry(&lists)
+            self.flush_registry(before, &lists)
+        })
     }
 
     pub fn update<F>(&self, list_id: Uuid, f: F) -> Result<List>
     where
         F: FnOnce(&mut List),
     {
-        let mut lists = self.load_all()?;
-        let list = lists
-            .iter_mut()
-            .find(|l| l.id == list_id)
-            .ok_or(Error::Store(StoreError::ListNotFound(list_id)))?;
-
-        f(list);
-        let updated = list.clone();
-        self.flush_registry(&lists)?;
-        Ok(updated)
+        self.store.journaled(|| {
+            let mut lists = self.store.backend().read_lists()?;
+            let before = lists.clone();
+            let list = lists
+                .iter_mut()
+                .find(|l| l.id == list_id)
+                .ok_or(Error::Store(StoreError::ListNotFound(list_id)))?;
+
+            let original = list.clone();
+            f(list);
+            list.updated_at = original.updated_at;
+            if !same(&original, list) {
+                list.updated_at = Timestamp::now();
+            }
+            let updated = list.clone();
+            self.flush_registry(before, &lists)?;
+            Ok(updated)
+        })
     }
 
-    /// Removes a list from the registry without touching its task directory.
-    /// Used when moving a list to trash, where the directory is relocated
+    /// Removes a list from the registry without touching its tasks.
+    /// Used when moving a list to trash, where the tasks are relocated
     /// rather than deleted.
     pub fn detach(&self, list_id: Uuid) -> Result<List> {
-        let mut lists = self.load_all()?;
-        let pos = lists
-            .iter()
-            .position(|l| l.id == list_id)
-            .ok_or(Error::Store(StoreError::ListNotFound(list_id)))?;
-        let list = lists.remove(pos);
-        self.flush_registry(&lists)?;
-        Ok(list)
-    }
-
-    fn flush_registry(&self, lists: &[List]) -> Result<()> {
-        let content = ron::ser::to_string_pretty(lists, pretty())?;
-        fs::write(self.store.registry_path(), content)?;
+        self.store.journaled(|| {
+            let mut lists = self.store.backend().read_lists()?;
+            let before = lists.clone();
+            let pos = lists
+                .iter()
+                .position(|l| l.id == list_id)
+                .ok_or(Error::Store(StoreError::ListNotFound(list_id)))?;
+            let list = lists.remove(pos);
+            self.flush_registry(before, &lists)?;
+            Ok(list)
+        })
+    }
+
+    fn flush_registry(&self, before: Vec<List>, lists: &[List]) -> Result<()> {
+        self.store.record(Operation::Lists {
+            before,
+            after: lists.to_vec(),
+        })?;
+        self.store.backend().write_lists(lists)?;
+        self.store.cache().lists = Some(lists.to_vec());
         Ok(())
     }
 }
@@ -358,59 +563,105 @@ pub struct StateStore<'s> {
 
 impl StateStore<'_> {
     pub fn load_all(&self) -> Result<Vec<TaskState>> {
-        let path = self.store.states_registry_path();
-        if !path.exists() {
-            let states = default_states();
-            self.flush_registry(&states)?;
+        if let Some(states) = self.store.cache().states.clone() {
             return Ok(states);
         }
-        let content = fs::read_to_string(&path)?;
-        Ok(ron::from_str(&content)?)
+        if let Some(states) = self.store.backend().read_states()? {
+            self.store.cache().states = Some(states.clone());
+            return Ok(states);
+        }
+
+        let _lock = self.store.lock()?;
+        let states = self.read_or_seed()?;
+        self.store.cache().states = Some(states.clone());
+        Ok(states)
     }
 
-    #[allow(dead_code)]
     pub fn save(&self, state: &TaskState) -> Result<()> {
-        let mut states = self.load_all()?;
-        match states.iter_mut().find(|s| s.id == state.id) {
-            Some(existing) => *existing = state.clone(),
-            None => states.push(state.clone()),
-        }
-        self.flush_registry(&states)
+        self.store.journaled(|| {
+            let mut states = self.read_or_seed()?;
+            let before = states.clone();
+            match states.iter_mut().find(|s| s.id == state.id) {
+                Some(existing) => *existing = state.clone(),
+                None => states.push(state.clone()),
+            }
+            self.flush_registry(before, &states)
+        })
     }
 
-    #[allow(dead_code)]
     pub fn update<F>(&self, state_id: Uuid, f: F) -> Result<TaskState>
     where
         F: FnOnce(&mut TaskState),
     {
-        let mut states = self.load_all()?;
-        let state = states
-            .iter_mut()
-            .find(|s| s.id == state_id)
-            .ok_or(Error::Store(StoreError::StateNotFound(state_id)))?;
-
-        f(state);
-        let updated = state.clone();
-        self.flush_registry(&states)?;
-        Ok(updated)
-    }
-
-    #[allow(dead_code)]
+        self.store.journaled(|| {
+            let mut states = self.read_or_seed()?;
+            let before = states.clone();
+            let state = states
+                .iter_mut()
+                .find(|s| s.id == state_id)
+                .ok_or(Error::Store(StoreError::StateNotFound(state_id)))?;
+
+            f(state);
+            let updated = state.clone();
+            self.flush_registry(before, &states)?;
+            Ok(updated)
+        })
+    }
+
+    /// Deletes a state without touching its tasks. [`Store::delete_state`]
+    /// moves them somewhere first.
     pub fn delete(&self, state_id: Uuid) -> Result<()> {
-        let mut states = self.load_all()?;
-        let before = states.len();
-        states.retain(|s| s.id != state_id);
+        self.store.journaled(|| {
+            let mut states = self.read_or_seed()?;
+            let before = states.clone();
+            states.retain(|s| s.id != state_id);
 
-        if states.len() == before {
-            return Err(Error::Store(StoreError::StateNotFound(state_id)));
-        }
+            if states.len() == before.len() {
+                return Err(Error::Store(StoreError::StateNotFound(state_id)));
+            }
 
-        self.flush_registry(&states)
+            self.flush_registry(before, &states)
+        })
+    }
+
+    /// Renumbers the states in the order of `state_ids`. States left out
+    /// keep their relative order after the ones given.
+    pub fn reorder(&self, state_ids: &[Uuid]) -> Result<()> {
+        self.store.journaled(|| {
+            let mut states = self.read_or_seed()?;
+            let before = states.clone();
+            states.sort_by_key(|state| {
+                let given = state_ids.iter().position(|id| *id == state.id);
+                (given.is_none(), given, state.position)
+            });
+            for (position, state) in states.iter_mut().enumerate() {
+                state.position = position as u32;
+            }
+            self.flush_registry(before, &states)
+        })
     }
 
-    fn flush_registry(&self, states: &[TaskState]) -> Result<()> {
-        let content = ron::ser::to_string_pretty(states, pretty())?;
-        fs::write(self.store.states_registry_path(), content)?;
+    /// Reads the registry from the backend, seeding the default states if
+    /// none have been written yet. Callers must hold the store lock.
+    ///
+    /// Seeding is not journaled; there is nothing before it to go back to.
+    fn read_or_seed(&self) -> Result<Vec<TaskState>> {
+        if let Some(states) = self.store.backend().read_states()? {
+            return Ok(states);
+        }
+        let states = default_states();
+        self.store.backend().write_states(&states)?;
+        self.store.cache().states = Some(states.clone());
+        Ok(states)
+    }
+
+    fn flush_registry(&self, before: Vec<TaskState>, states: &[TaskState]) -> Result<()> {
+        self.store.record(Operation::States {
+            before,
+            after: states.to_vec(),
+        })?;
+        self.store.backend().write_states(states)?;
+        self.store.cache().states = Some(states.to_vec());
         Ok(())
     }
 }
@@ -422,63 +673,242 @@ pub struct TaskStore<'s> {
 
 impl TaskStore<'_> {
     pub fn get(&self, task_id: Uuid) -> Result<Task> {
-        let path = self.store.task_path(self.list_id, task_id);
-        let content = fs::read_to_string(&path)
-            .map_err(|_| Error::Store(StoreError::TaskNotFound(task_id)))?;
-        Ok(ron::from_str(&content)?)
-    }
-
-    pub fn load_all(&self) -> Result<Vec<Task>> {
-        let list_dir = self.store.list_dir(self.list_id);
-        if !list_dir.exists() {
-            return Err(Error::Store(StoreError::ListNotFound(self.list_id)));
-        }
-
-        let mut tasks = Vec::new();
-        for entry in fs::read_dir(&list_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.extension().and_then(|e| e.to_str()) != Some("ron") {
-                continue;
-            }
-
-            match fs::read_to_string(&path).map(|s| ron::from_str::<Task>(&s)) {
-                Ok(Ok(task)) => tasks.push(task),
-                Ok(Err(e)) => tracing::error!("warn: skipping {:?}: {e}", path.file_name()),
-                Err(e) => tracing::error!("warn: could not read {:?}: {e}", path.file_name()),
+        if self.store.load_list(self.list_id).is_ok() {
+            if let Some(task) = self.store.cache().task((self.list_id, task_id)) {
+                return Ok(task.clone());
             }
         }
+        self.store.backend().read_task(self.list_id, task_id)
+    }
 
+    pub fn load_all(&self) -> Result<Vec<Task>> {
+        self.store.load_list(self.list_id)?;
+
+        let mut tasks: Vec<Task> = self
+            .store
+            .cache()
+            .list_tasks(self.list_id)
+            .map(|tasks| tasks.cloned().collect())
+            .unwrap_or_default();
         tasks.sort_by(|a, b| a.creation_date.cmp(&b.creation_date));
         Ok(tasks)
     }
 
     pub fn save(&self, task: &Task) -> Result<()> {
-        let list_dir = self.store.list_dir(self.list_id);
-        if !list_dir.exists() {
+        if !self.store.cache().has_list(self.list_id)
+            && !self.store.backend().has_list(self.list_id)
+        {
             return Err(Error::Store(StoreError::ListNotFound(self.list_id)));
         }
 
-        let path = self.store.task_path(self.list_id, task.id);
-        let content = ron::ser::to_string_pretty(task, pretty())?;
-        fs::write(path, content)?;
-        Ok(())
+        self.store.journaled(|| {
+            let before = self.store.backend().read_task(self.list_id, task.id).ok();
+            self.store.record(Operation::Task {
+                list_id: self.list_id,
+                before: before.clone(),
+                after: Some(task.clone()),
+            })?;
+            self.store.backend().write_task(self.list_id, task)?;
+            self.store.cache().insert_task(self.list_id, task.clone());
+            if let Err(err) = self.store.history().record(before.as_ref(), task) {
+                tracing::error!("Failed to record revision of task {}: {err}", task.id);
+            }
+            Ok(())
+        })
     }
 
     pub fn update<F>(&self, task_id: Uuid, f: F) -> Result<Task>
     where
         F: FnOnce(&mut Task),
     {
-        let mut task = self.get(task_id)?;
-        f(&mut task);
-        self.save(&task)?;
-        Ok(task)
+        self.store.journaled(|| {
+            let mut task = self.store.backend().read_task(self.list_id, task_id)?;
+            let original = task.clone();
+            f(&mut task);
+            // Only the store dates changes, and only real ones: saving a task
+            // as it already is leaves it alone.
+            task.updated_at = original.updated_at;
+            if !same(&original, &task) {
+                task.updated_at = Timestamp::now();
+            }
+            self.save(&task)?;
+            Ok(task)
+        })
+    }
+
+    /// Marks a task completed or open again. Completing a recurring task
+    /// also adds its next occurrence, unless the series already has an open
+    /// one, and returns it along with the task.
+    ///
+    /// A task that changes between open and completed leaves its state for
+    /// the built-in one; [`TaskStore::set_state`] picks another.
+    pub fn complete(&self, task_id: Uuid, completed: bool) -> Result<(Task, Option<Task>)> {
+        self.store.journaled(|| {
+            let task = self.update(task_id, |task| {
+                if task.is_completed() != completed {
+                    task.state_id = None;
+                }
+                task.completion_date = completed.then(Timestamp::now);
+                if completed && task.recurrence.is_some() {
+                    task.series_id.get_or_insert(task.id);
+                }
+            })?;
+            if !completed {
+                return Ok((task, None));
+            }
+            let Some(next) = task.next_occurrence(jiff::Zoned::now().date()) else {
+                return Ok((task, None));
+            };
+            let open = self.load_all()?.into_iter().any(|other| {
+                other.id != task.id && other.series_id == task.series_id && !other.is_completed()
+            });
+            if open {
+                return Ok((task, None));
+            }
+
+            self.save(&next)?;
+            if let Some(parent_id) = next.parent_id {
+                self.update(parent_id, |parent| parent.sub_task_ids.push(next.id))?;
+            }
+            Ok((task, Some(next)))
+        })
+    }
+
+    /// Moves a task to `state`, completing or reopening it to match. Returns
+    /// the task, and the next occurrence when that completes a recurring
+    /// one.
+    pub fn set_state(&self, task_id: Uuid, state: &TaskState) -> Result<(Task, Option<Task>)> {
+        self.store.journaled(|| {
+            let next = if self.get(task_id)?.is_completed() != state.is_completed {
+                self.complete(task_id, state.is_completed)?.1
+            } else {
+                None
+            };
+            let task = self.update(task_id, |task| task.state_id = Some(state.id))?;
+            Ok((task, next))
+        })
+    }
+
+    /// Moves a task to `state` and renumbers `sort_order` to follow `order`,
+    /// the ids of the tasks in that state with this one in its new place.
+    /// Returns those tasks as saved, and the next occurrence when the move
+    /// completes a recurring task.
+    pub fn move_to_state(
+        &self,
+        task_id: Uuid,
+        state: &TaskState,
+        order: &[Uuid],
+    ) -> Result<(Vec<Task>, Option<Task>)> {
+        self.store.journaled(|| {
+            let next = if self.get(task_id)?.effective_state_id() != state.id {
+                self.set_state(task_id, state)?.1
+            } else {
+                None
+            };
+            let mut tasks = Vec::with_capacity(order.len());
+            for (position, id) in order.iter().enumerate() {
+                let position = position as u32;
+                let task = self.get(*id)?;
+                tasks.push(if task.sort_order == position {
+                    task
+                } else {
+                    self.update(*id, |task| task.sort_order = position)?
+                });
+            }
+            Ok((tasks, next))
+        })
+    }
+
+    /// Makes a task a sub-task of `parent_id`, or a top-level task when
+    /// `None`, keeping `sub_task_ids` of its old and new parent in step. It
+    /// goes right after its new sibling `after`, or last. Returns every task
+    /// that changed.
+    pub fn set_parent(
+        &self,
+        task_id: Uuid,
+        parent_id: Option<Uuid>,
+        after: Option<Uuid>,
+    ) -> Result<Vec<Task>> {
+        self.store.journaled(|| {
+            let mut ancestor = parent_id;
+            while let Some(id) = ancestor {
+                if id == task_id {
+                    return Err(Error::Store(StoreError::SubTaskCycle(task_id)));
+                }
+                ancestor = self.get(id)?.parent_id;
+            }
+
+            let mut changed: Vec<Task> = Vec::new();
+            let mut push = |task: Task| {
+                changed.retain(|t| t.id != task.id);
+                changed.push(task);
+            };
+
+            if let Some(old_parent_id) = self.get(task_id)?.parent_id {
+                push(self.update(old_parent_id, |parent| {
+                    parent.sub_task_ids.retain(|id| *id != task_id)
+                })?);
+            }
+
+            let sort_order = match parent_id {
+                Some(parent_id) => {
+                    push(self.update(parent_id, |parent| {
+                        let at = after
+                            .and_then(|after| {
+                                parent.sub_task_ids.iter().position(|id| *id == after)
+                            })
+                            .map_or(parent.sub_task_ids.len(), |index| index + 1);
+                        parent.sub_task_ids.insert(at, task_id);
+                        parent.expanded = true;
+                    })?);
+                    None
+                }
+                None => {
+                    let mut top_level: Vec<Task> = self
+                        .load_all()?
+                        .into_iter()
+                        .filter(|task| task.parent_id.is_none() && task.id != task_id)
+                        .collect();
+                    top_level.sort_by(|a, b| {
+                        a.sort_order
+                            .cmp(&b.sort_order)
+                            .then(a.creation_date.cmp(&b.creation_date))
+                    });
+                    let at = after
+                        .and_then(|after| top_level.iter().position(|task| task.id == after))
+                        .map_or(top_level.len(), |index| index + 1);
+                    for (index, task) in top_level.iter().enumerate() {
+                        let position = if index < at { index } else { index + 1 } as u32;
+                        if task.sort_order != position {
+                            push(self.update(task.id, |task| task.sort_order = position)?);
+                        }
+                    }
+                    Some(at as u32)
+                }
+            };
+
+            push(self.update(task_id, |task| {
+                task.parent_id = parent_id;
+                if let Some(sort_order) = sort_order {
+                    task.sort_order = sort_order;
+                }
+            })?);
+            Ok(changed)
+        })
     }
 
     pub fn delete(&self, task_id: Uuid) -> Result<()> {
-        let path = self.store.task_path(self.list_id, task_id);
-        fs::remove_file(&path).map_err(|_| Error::Store(StoreError::TaskNotFound(task_id)))
+        self.store.journaled(|| {
+            let before = self.store.backend().read_task(self.list_id, task_id)?;
+            self.store.record(Operation::Task {
+                list_id: self.list_id,
+                before: Some(before),
+                after: None,
+            })?;
+            self.store.backend().remove_task(self.list_id, task_id)?;
+            self.store.cache().remove_task(self.list_id, task_id);
+            Ok(())
+        })
     }
 
     #[allow(dead_code)]
@@ -489,3 +919,29 @@ impl TaskStore<'_> {
         Ok(self.load_all()?.into_iter().filter(predicate).collect())
     }
 }
+
+/// Records set aside because they could not be parsed.
+pub struct QuarantineStore<'s> {
+    store: &'s Store,
+}
+
+impl QuarantineStore<'_> {
+    pub fn load_all(&self) -> Result<Vec<QuarantinedItem>> {
+        self.store.backend().quarantined()
+    }
+
+    /// Puts a damaged record back if it parses now, typically after it was
+    /// fixed by hand. Fails with the new parse error otherwise.
+    pub fn retry(&self, id: Uuid) -> Result<()> {
+        let _lock = self.store.lock()?;
+        let path = self.store.backend().restore_quarantined(id)?;
+        if let Some(change) = self.store.backend().classify(&path) {
+            self.store.cache().invalidate(change);
+        }
+        Ok(())
+    }
+
+    pub fn discard(&self, id: Uuid) -> Result<()> {
+        self.store.backend().discard_quarantined(id)
+    }
+}
@@ -0,0 +1,70 @@
+//! Tags across every list.
+//!
+//! Tags have no registry of their own: they are the strings on
+//! [`Task::tags`], and a tag exists as long as some task carries it.
+//! Renaming or merging tags rewrites every task that has them.
+
+use std::collections::BTreeMap;
+
+use super::query::TaskQuery;
+use super::Store;
+use crate::features::tasks::task::Task;
+use crate::Result;
+
+impl Store {
+    /// Every tag in use, with the number of tasks carrying it, sorted by
+    /// name ignoring case.
+    pub fn tags(&self) -> Result<Vec<(String, usize)>> {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for (_, task) in self.query(&TaskQuery::new())? {
+            for tag in task.tags {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+        let mut tags: Vec<(String, usize)> = counts.into_iter().collect();
+        tags.sort_by_cached_key(|(tag, _)| tag.to_lowercase());
+        Ok(tags)
+    }
+
+    /// Renames `from` to `to` on every task. A task that already carries
+    /// `to` keeps a single copy of it, so renaming to a tag in use merges
+    /// the two. Returns how many tasks changed.
+    pub fn rename_tag(&self, from: &str, to: &str) -> Result<usize> {
+        self.merge_tags(&[from.to_string()], to)
+    }
+
+    /// Replaces every tag of `tags` with `into` on every task, as one undo
+    /// step. Returns how many tasks changed.
+    pub fn merge_tags(&self, tags: &[String], into: &str) -> Result<usize> {
+        let into = into.trim();
+        let tagged: Vec<_> = self
+            .query(&TaskQuery::new())?
+            .into_iter()
+            .filter(|(_, task)| task.tags.iter().any(|tag| tags.contains(tag)))
+            .collect();
+        if into.is_empty() || tagged.is_empty() {
+            return Ok(0);
+        }
+
+        self.transaction(|tx| {
+            for (list_id, task) in &tagged {
+                tx.tasks(*list_id)
+                    .update(task.id, |task| retag(task, tags, into))?;
+            }
+            Ok(tagged.len())
+        })
+    }
+}
+
+fn retag(task: &mut Task, tags: &[String], into: &str) {
+    let mut merged = false;
+    task.tags.retain_mut(|tag| {
+        if !tags.contains(tag) && tag != into {
+            return true;
+        }
+        let keep = !merged;
+        merged = true;
+        into.clone_into(tag);
+        keep
+    });
+}
@@ -0,0 +1,171 @@
+//! All-or-nothing changes spanning several records.
+//!
+//! Every store operation runs as a transaction: the changes it makes are
+//! recorded in the journal before they are written, and if it fails halfway
+//! the ones already written are reverted. [`Store::transaction`] lets callers
+//! group several operations the same way.
+//!
+//! Each change is also appended to a write-ahead file next to the data, and
+//! synced, before it is written. The file is removed when the transaction
+//! commits. Finding it when the store is opened means a process died
+//! mid-transaction, and its changes are rolled back.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::journal::Operation;
+use super::store::{ListStore, StateStore, TaskStore, TrashStore};
+use super::{atomic, Store};
+use crate::Result;
+
+pub const WRITE_AHEAD_FILE: &str = "transaction";
+
+/// The changes of the transaction in progress, kept on disk until it
+/// commits.
+#[derive(Debug)]
+pub struct WriteAhead {
+    path: Option<PathBuf>,
+}
+
+impl WriteAhead {
+    pub fn new(root: Option<&Path>) -> Self {
+        Self {
+            path: root.map(|root| root.join(WRITE_AHEAD_FILE)),
+        }
+    }
+
+    /// Adds a change to the transaction on disk. Must return before the
+    /// change is written.
+    pub fn log(&self, operation: &Operation) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        atomic::append(path, format!("{}\n", ron::to_string(operation)?))?;
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        match &self.path {
+            Some(path) if path.exists() => Ok(fs::remove_file(path)?),
+            _ => Ok(()),
+        }
+    }
+
+    /// The changes of a transaction that never committed. A last line cut
+    /// short by the crash is dropped: its change was never written.
+    fn read(&self) -> Result<Option<Vec<Operation>>> {
+        let Some(path) = self.path.as_ref().filter(|path| path.exists()) else {
+            return Ok(None);
+        };
+        let content = fs::read_to_string(path)?;
+        let lines: Vec<&str> = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        let mut operations = Vec::with_capacity(lines.len());
+        for (index, line) in lines.iter().enumerate() {
+            match ron::from_str(line) {
+                Ok(operation) => operations.push(operation),
+                Err(err) if index + 1 == lines.len() => {
+                    tracing::warn!("Dropping a change cut short in the write-ahead file: {err}");
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(Some(operations))
+    }
+}
+
+/// Handle passed to [`Store::transaction`]. Changes made through its views
+/// are committed together when the closure returns, or not at all.
+pub struct Transaction<'s> {
+    store: &'s Store,
+}
+
+impl Transaction<'_> {
+    #[allow(dead_code)]
+    pub fn lists(&self) -> ListStore<'_> {
+        self.store.lists()
+    }
+
+    pub fn tasks(&self, list_id: uuid::Uuid) -> TaskStore<'_> {
+        self.store.tasks(list_id)
+    }
+
+    pub fn states(&self) -> StateStore<'_> {
+        self.store.states()
+    }
+
+    pub fn trash(&self) -> TrashStore<'_> {
+        self.store.trash()
+    }
+}
+
+impl Store {
+    /// Runs `f` as one transaction: if it returns an error, every change it
+    /// made is reverted. The whole transaction is undone and redone as one
+    /// step.
+    pub fn transaction<T>(&self, f: impl FnOnce(&Transaction<'_>) -> Result<T>) -> Result<T> {
+        self.journaled(|| f(&Transaction { store: self }))
+    }
+
+    /// Runs `f` under the store lock as a transaction, recording everything
+    /// it changes as one journal entry. Nested calls join the transaction of
+    /// the outermost one.
+    pub(super) fn journaled<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        let _lock = self.lock()?;
+        self.journal().begin();
+        match f() {
+            Ok(value) => {
+                // The changes are on disk at this point; failing to journal
+                // them only costs the ability to undo.
+                if let Err(err) = self.journal().end() {
+                    tracing::error!("Failed to journal change: {err}");
+                }
+                Ok(value)
+            }
+            Err(err) => {
+                if let Some(operations) = self.journal().abort() {
+                    self.roll_back(&operations);
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Rolls back a transaction left behind by a process that stopped in
+    /// the middle of it.
+    pub(super) fn recover(&self) -> Result<()> {
+        let _lock = self.lock()?;
+        if let Some(operations) = self.journal().write_ahead().read()? {
+            tracing::warn!(
+                "Rolling back an interrupted transaction of {} changes",
+                operations.len()
+            );
+            self.roll_back(&operations);
+        }
+        Ok(())
+    }
+
+    /// Reverts `operations`, last first. Operations that were recorded but
+    /// never written find their record unchanged and put it back as is.
+    /// The write-ahead file is kept if anything could not be reverted, so
+    /// the next open tries again.
+    fn roll_back(&self, operations: &[Operation]) {
+        let mut complete = true;
+        for operation in operations.iter().rev() {
+            match operation.apply(self.backend(), false) {
+                Ok(change) => self.forget(change),
+                Err(err) => {
+                    tracing::error!("Failed to roll back change: {err}");
+                    complete = false;
+                }
+            }
+        }
+        if complete {
+            if let Err(err) = self.journal().write_ahead().clear() {
+                tracing::error!("Failed to clear write-ahead record: {err}");
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -5,58 +6,146 @@ use cosmic::iced::futures::channel::mpsc::Sender;
 use cosmic::iced::futures::{SinkExt, StreamExt};
 use cosmic::iced::{stream, Subscription};
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use uuid::Uuid;
 
 use crate::app::Message;
+use crate::features::tasks::task::Task;
 use crate::shared::navigation::nav::TasksAction;
+use crate::shared::store::change::Change;
+use crate::shared::store::Store;
 
 const DEBOUNCE: Duration = Duration::from_millis(300);
 
+/// A change made to the store by another window or process.
+#[derive(Debug, Clone)]
+pub enum StoreEvent {
+    /// The list registry changed: lists were added, removed or renamed.
+    ListsChanged,
+    /// A list's tasks changed as a whole and should be reloaded.
+    ListChanged(Uuid),
+    TaskChanged(Uuid, Task),
+    TaskRemoved(Uuid, Uuid),
+    TrashChanged,
+    StatesChanged,
+    /// Damaged items were set aside, put back or discarded.
+    QuarantineChanged,
+    /// The backend could not tell what changed; everything is reloaded.
+    Reload,
+}
+
+/// Turns the changes collected over one debounce window into events,
+/// reading back the tasks that changed. Task changes are dropped when their
+/// whole list is reloaded anyway.
+fn events(store: &Store, changes: Vec<Change>) -> Vec<StoreEvent> {
+    if changes.contains(&Change::Unknown) {
+        return vec![StoreEvent::Reload];
+    }
+
+    let reloaded: Vec<Uuid> = changes
+        .iter()
+        .filter_map(|change| match change {
+            Change::List(list_id) => Some(*list_id),
+            _ => None,
+        })
+        .collect();
+
+    let mut seen = Vec::new();
+    let mut events = Vec::new();
+    for change in changes {
+        if seen.contains(&change) {
+            continue;
+        }
+        seen.push(change);
+
+        let event = match change {
+            Change::Lists => StoreEvent::ListsChanged,
+            Change::List(list_id) => StoreEvent::ListChanged(list_id),
+            Change::Task { list_id, .. } if reloaded.contains(&list_id) => continue,
+            Change::Task { list_id, task_id } => match store.tasks(list_id).get(task_id) {
+                Ok(task) => StoreEvent::TaskChanged(list_id, task),
+                Err(_) => StoreEvent::TaskRemoved(list_id, task_id),
+            },
+            Change::States => StoreEvent::StatesChanged,
+            Change::Trash => StoreEvent::TrashChanged,
+            Change::Quarantine => StoreEvent::QuarantineChanged,
+            Change::Unknown => StoreEvent::Reload,
+        };
+        events.push(event);
+    }
+    events
+}
+
+/// The subscription is identified by the directory it watches; the store
+/// comes along so changed paths can be dropped from its cache.
+struct Watched {
+    base_dir: PathBuf,
+    store: Store,
+}
+
+impl Hash for Watched {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base_dir.hash(state);
+    }
+}
+
 /// Watches the store directory for changes made from other windows or
-/// processes and emits a debounced sync message once the tree settles.
-pub fn subscription(base_dir: PathBuf) -> Subscription<Message> {
-    Subscription::run_with(base_dir.clone(), move |base_dir| {
-        let base_dir = base_dir.clone();
-        stream::channel(10, move |mut output: Sender<Message>| async move {
-            let (tx, mut rx) = cosmic::iced::futures::channel::mpsc::channel(100);
-
-            let mut watcher = match RecommendedWatcher::new(
-                move |res: notify::Result<notify::Event>| {
-                    if res.is_ok() {
-                        let _ = tx.clone().try_send(());
+/// processes, invalidates the store's cache for the paths that changed and
+/// emits a [`StoreEvent`] for each of them once the tree settles.
+pub fn subscription(store: Store) -> Option<Subscription<Message>> {
+    let base_dir = store.base_dir()?.to_path_buf();
+    Some(Subscription::run_with(
+        Watched { base_dir, store },
+        move |watched| {
+            let base_dir = watched.base_dir.clone();
+            let store = watched.store.clone();
+            stream::channel(10, move |mut output: Sender<Message>| async move {
+                let (tx, mut rx) = cosmic::iced::futures::channel::mpsc::channel(100);
+
+                let mut watcher = match RecommendedWatcher::new(
+                    move |res: notify::Result<notify::Event>| {
+                        if let Ok(event) = res {
+                            let _ = tx.clone().try_send(event.paths);
+                        }
+                    },
+                    notify::Config::default(),
+                ) {
+                    Ok(watcher) => watcher,
+                    Err(err) => {
+                        tracing::error!("Failed to create file watcher: {err}");
+                        return;
                     }
-                },
-                notify::Config::default(),
-            ) {
-                Ok(watcher) => watcher,
-                Err(err) => {
-                    tracing::error!("Failed to create file watcher: {err}");
+                };
+
+                if let Err(err) = watcher.watch(&base_dir, RecursiveMode::Recursive) {
+                    tracing::error!("Failed to watch store directory: {err}");
                     return;
                 }
-            };
 
-            if let Err(err) = watcher.watch(&base_dir, RecursiveMode::Recursive) {
-                tracing::error!("Failed to watch store directory: {err}");
-                return;
-            }
+                loop {
+                    let Some(mut paths) = rx.next().await else {
+                        break;
+                    };
 
-            loop {
-                if rx.next().await.is_none() {
-                    break;
-                }
+                    while let Ok(Some(more)) = tokio::time::timeout(DEBOUNCE, rx.next()).await {
+                        paths.extend(more);
+                    }
+
+                    let changes: Vec<Change> = paths
+                        .iter()
+                        .filter_map(|path| store.invalidate(path))
+                        .collect();
 
-                while tokio::time::timeout(DEBOUNCE, rx.next())
-                    .await
-                    .is_ok_and(|event| event.is_some())
-                {}
-
-                if output
-                    .send(Message::Tasks(TasksAction::SyncFromDisk))
-                    .await
-                    .is_err()
-                {
-                    break;
+                    for event in events(&store, changes) {
+                        if output
+                            .send(Message::Tasks(TasksAction::StoreChanged(event)))
+                            .await
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
                 }
-            }
-        })
-    })
+            })
+        },
+    ))
 }
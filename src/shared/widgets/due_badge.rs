@@ -0,0 +1,61 @@
+use cosmic::{
+    cosmic_theme::Spacing,
+    iced::{Alignment, Color},
+    theme, widget, Element,
+};
+use jiff::Zoned;
+
+use crate::{
+    features::tasks::{due::DueDate, task::Due},
+    fl,
+};
+
+/// A small label with when an open task is due, in the system zone,
+/// coloured once it has passed or when it is today.
+pub fn due_badge<'a, Message: 'static>(
+    due_date: &DueDate,
+    now: &Zoned,
+    spacing: &Spacing,
+) -> Element<'a, Message> {
+    let cosmic = theme::active().cosmic().clone();
+    let today = now.date();
+    let date = due_date.local_date();
+    let day = match Due::of(date, today) {
+        Due::Today => fl!("due-today"),
+        Due::Tomorrow => fl!("due-tomorrow"),
+        Due::ThisWeek => date.strftime("%A").to_string(),
+        Due::Overdue | Due::Later if date.year() == today.year() => {
+            date.strftime("%b %-d").to_string()
+        }
+        Due::Overdue | Due::Later => date.strftime("%b %-d, %Y").to_string(),
+    };
+    let label = match due_date.local() {
+        Some(local) => format!("{day} {}", local.strftime("%H:%M")),
+        None => day,
+    };
+    let due = if due_date.is_overdue(now.timestamp()) {
+        Due::Overdue
+    } else {
+        Due::of(date, today)
+    };
+    let color = match due {
+        Due::Overdue => Some(Color::from(cosmic.destructive.base)),
+        Due::Today => Some(Color::from(cosmic.accent.base)),
+        Due::Tomorrow | Due::ThisWeek | Due::Later => None,
+    };
+
+    let mut text = widget::text::caption(label);
+    if let Some(color) = color {
+        text = text.class(theme::Text::Color(color));
+    }
+    widget::container(
+        widget::row::with_capacity(2)
+            .spacing(spacing.space_xxxs)
+            .align_y(Alignment::Center)
+            .push(widget::icon::from_name("x-office-calendar-symbolic").size(12))
+            .push(text),
+    )
+    .class(theme::Container::Tooltip)
+    .padding([spacing.space_none, spacing.space_xs])
+    .into()
+}
@@ -1,5 +1,9 @@
 pub mod collapsible_section;
+pub mod due_badge;
 pub mod markdown;
 pub mod style;
+pub mod tag_chip;
 
+pub use due_badge::due_badge;
 pub use markdown::Markdown;
+pub use tag_chip::tag_chip;
@@ -0,0 +1,26 @@
+use cosmic::{cosmic_theme::Spacing, iced::Alignment, theme, widget, Element};
+
+/// A small rounded label for a tag. With `on_remove`, it carries a button
+/// that sends the message to take the tag off.
+pub fn tag_chip<'a, Message: Clone + 'static>(
+    tag: &'a str,
+    on_remove: Option<Message>,
+    spacing: &Spacing,
+) -> Element<'a, Message> {
+    let remove = on_remove.map(|message| {
+        widget::button::icon(widget::icon::from_name("window-close-symbolic").size(10))
+            .padding(spacing.space_none)
+            .on_press(message)
+    });
+
+    widget::container(
+        widget::row::with_capacity(2)
+            .align_y(Alignment::Center)
+            .spacing(spacing.space_xxxs)
+            .push(widget::text::caption(tag))
+            .push_maybe(remove),
+    )
+    .class(theme::Container::Tooltip)
+    .padding([spacing.space_none, spacing.space_xs])
+    .into()
+}